@@ -0,0 +1,137 @@
+use cassandra_protocol::compression::Compression;
+use cassandra_protocol::frame::raw_frame::RawFrame;
+use cassandra_protocol::frame::{Flags, Frame, FromCursor, Serialize, Version};
+use cassandra_protocol::query::{QueryValues, TupleValues};
+use cassandra_protocol::testing::{fixture_batch, fixture_rows_result};
+use cassandra_protocol::types::rows::decode_row_sparse;
+use cassandra_protocol::types::value::Value;
+use cassandra_protocol::types::{to_bigint, write_bigint, CBytes};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::io::Cursor;
+
+fn decode_rows_result(c: &mut Criterion) {
+    let encoded = fixture_rows_result(Version::V4, 100, 10)
+        .encode_with(Compression::None)
+        .unwrap();
+
+    c.bench_function("decode 100x10 rows result", |b| {
+        b.iter(|| {
+            RawFrame::from_bytes(black_box(&encoded))
+                .unwrap()
+                .into_frame(Compression::None)
+                .unwrap()
+                .response_body()
+                .unwrap()
+        })
+    });
+}
+
+fn decode_sparse_row(c: &mut Criterion) {
+    // 50 columns, every fifth one set
+    let mut row = Vec::new();
+    for index in 0..50 {
+        if index % 5 == 0 {
+            row.extend_from_slice(&[0, 0, 0, 8]);
+            row.extend_from_slice(&(index as i64).to_be_bytes());
+        } else {
+            row.extend_from_slice(&(-1i32).to_be_bytes());
+        }
+    }
+
+    let mut group = c.benchmark_group("decode 50 column row with 40 nulls");
+
+    group.bench_function("CBytes", |b| {
+        b.iter(|| {
+            let mut cursor = Cursor::new(black_box(row.as_slice()));
+            (0..50)
+                .map(|_| CBytes::from_cursor(&mut cursor).unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.bench_function("sparse", |b| {
+        b.iter(|| decode_row_sparse(&mut Cursor::new(black_box(row.as_slice())), 50).unwrap())
+    });
+
+    group.finish();
+}
+
+fn encode_batch(c: &mut Criterion) {
+    let batch = fixture_batch(50);
+
+    c.bench_function("encode 50 statement batch", |b| {
+        b.iter(|| {
+            Frame::new_req_batch(black_box(batch.clone()), Flags::empty(), Version::V4)
+                .encode_with(Compression::None)
+                .unwrap()
+        })
+    });
+}
+
+fn serialize_insert_values(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serialize 1000 insert values");
+
+    group.bench_function("tuple", |b| {
+        b.iter(|| {
+            let mut buffer = Vec::new();
+            for i in 0..1000i64 {
+                black_box((i, "name", 1.5f64)).write_counted_values(&mut buffer);
+            }
+            buffer
+        })
+    });
+
+    group.bench_function("Vec<Value>", |b| {
+        b.iter(|| {
+            let mut buffer = Vec::new();
+            for i in 0..1000i64 {
+                let values = QueryValues::SimpleValues(vec![
+                    Value::new(i),
+                    Value::new("name"),
+                    Value::new(1.5f64),
+                ]);
+                buffer.extend_from_slice(&(values.len() as u16).to_be_bytes());
+                buffer.extend(black_box(values).serialize_to_vec());
+            }
+            buffer
+        })
+    });
+
+    group.finish();
+}
+
+fn encode_bigints(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode 100k bigints");
+
+    group.bench_function("to_bigint", |b| {
+        b.iter(|| {
+            let mut buffer = Vec::new();
+            for i in 0..100_000i64 {
+                buffer.extend(to_bigint(black_box(i)));
+            }
+            buffer
+        })
+    });
+
+    group.bench_function("write_bigint", |b| {
+        b.iter(|| {
+            let mut buffer = Vec::new();
+            for i in 0..100_000i64 {
+                write_bigint(&mut buffer, black_box(i));
+            }
+            buffer
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    decode_rows_result,
+    decode_sparse_row,
+    encode_batch,
+    serialize_insert_values,
+    encode_bigints
+);
+criterion_main!(benches);
@@ -7,9 +7,22 @@
 /// must never be compressed.  However, once the STARTUP frame has been received
 /// by the server, messages can be compressed (including the response to the STARTUP
 /// request).
+///
+/// Each compression backend is available only when its feature (`lz4` or `snappy`) is enabled.
+/// Using a disabled compression results in `CompressionError::Unavailable`.
+///
+/// Compression is configured once per connection, optionally with a minimum body length below
+/// which frames are sent uncompressed (see `Frame::encode_with_threshold`). Individual frames
+/// can opt out with `Frame::compression_disabled` (exposed by request builders as
+/// `disable_compression()`), which always takes precedence over the connection settings.
+/// Frames can only be forced uncompressed - there is no way to compress a frame on a connection
+/// without compression, since the algorithm needs to be agreed upon during STARTUP.
 use derive_more::Display;
+#[cfg(feature = "snappy")]
 use snap::raw::{Decoder, Encoder};
-use std::convert::{From, TryInto};
+use std::convert::From;
+#[cfg(feature = "lz4")]
+use std::convert::TryInto;
 use std::error::Error;
 use std::fmt;
 use std::io;
@@ -25,16 +38,26 @@ pub const SNAPPY: &str = "snappy";
 #[derive(Debug)]
 pub enum CompressionError {
     /// Snappy error.
+    #[cfg(feature = "snappy")]
     Snappy(snap::Error),
     /// Lz4 error.
     Lz4(io::Error),
+    /// Compression backend has not been enabled by its feature.
+    Unavailable(Compression),
 }
 
 impl fmt::Display for CompressionError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
+            #[cfg(feature = "snappy")]
             CompressionError::Snappy(ref err) => write!(f, "Snappy Error: {:?}", err),
             CompressionError::Lz4(ref err) => write!(f, "Lz4 Error: {:?}", err),
+            CompressionError::Unavailable(compression) => write!(
+                f,
+                "{} compression is not available - enable the \"{}\" feature",
+                compression,
+                compression.as_str().unwrap_or_default()
+            ),
         }
     }
 }
@@ -42,8 +65,10 @@ impl fmt::Display for CompressionError {
 impl Error for CompressionError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match *self {
+            #[cfg(feature = "snappy")]
             CompressionError::Snappy(ref err) => Some(err),
             CompressionError::Lz4(ref err) => Some(err),
+            CompressionError::Unavailable(_) => None,
         }
     }
 }
@@ -65,13 +90,15 @@ impl Compression {
     /// # Examples
     ///
     /// ```
+    /// # #[cfg(feature = "snappy")]
+    /// # {
     ///    use cassandra_protocol::compression::Compression;
     ///
     ///   let snappy_compression = Compression::Snappy;
     ///   let bytes = String::from("Hello World").into_bytes().to_vec();
     ///   let encoded = snappy_compression.encode(&bytes).unwrap();
     ///   assert_eq!(snappy_compression.decode(encoded).unwrap(), bytes);
-    ///
+    /// # }
     /// ```
     pub fn encode(&self, bytes: &[u8]) -> Result<Vec<u8>> {
         match *self {
@@ -105,6 +132,7 @@ impl Compression {
         }
     }
 
+    #[cfg(feature = "snappy")]
     fn encode_snappy(bytes: &[u8]) -> Result<Vec<u8>> {
         let mut encoder = Encoder::new();
         encoder
@@ -112,6 +140,7 @@ impl Compression {
             .map_err(CompressionError::Snappy)
     }
 
+    #[cfg(feature = "snappy")]
     fn decode_snappy(bytes: Vec<u8>) -> Result<Vec<u8>> {
         let mut decoder = Decoder::new();
         decoder
@@ -119,6 +148,17 @@ impl Compression {
             .map_err(CompressionError::Snappy)
     }
 
+    #[cfg(not(feature = "snappy"))]
+    fn encode_snappy(_bytes: &[u8]) -> Result<Vec<u8>> {
+        Err(CompressionError::Unavailable(Compression::Snappy))
+    }
+
+    #[cfg(not(feature = "snappy"))]
+    fn decode_snappy(_bytes: Vec<u8>) -> Result<Vec<u8>> {
+        Err(CompressionError::Unavailable(Compression::Snappy))
+    }
+
+    #[cfg(feature = "lz4")]
     fn encode_lz4(bytes: &[u8]) -> Result<Vec<u8>> {
         let len = 4 + lz4_flex::block::get_maximum_output_size(bytes.len());
         assert!(len <= i32::MAX as usize);
@@ -135,15 +175,40 @@ impl Compression {
         Ok(result)
     }
 
+    #[cfg(feature = "lz4")]
     fn decode_lz4(bytes: Vec<u8>) -> Result<Vec<u8>> {
+        if bytes.len() < 4 {
+            return Err(CompressionError::Lz4(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Missing uncompressed length",
+            )));
+        }
+
         let uncompressed_size =
             i32::from_be_bytes(bytes[..4].try_into().map_err(|error| {
                 CompressionError::Lz4(io::Error::new(io::ErrorKind::Other, error))
             })?);
+        // lz4 cannot compress better than ~255:1, so anything more is certainly malformed
+        if uncompressed_size < 0 || uncompressed_size as usize > (bytes.len() - 4) * 255 + 16 {
+            return Err(CompressionError::Lz4(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid uncompressed length: {}", uncompressed_size),
+            )));
+        }
 
         lz4_flex::decompress(&bytes[4..], uncompressed_size as usize)
             .map_err(|error| CompressionError::Lz4(io::Error::new(io::ErrorKind::Other, error)))
     }
+
+    #[cfg(not(feature = "lz4"))]
+    fn encode_lz4(_bytes: &[u8]) -> Result<Vec<u8>> {
+        Err(CompressionError::Unavailable(Compression::Lz4))
+    }
+
+    #[cfg(not(feature = "lz4"))]
+    fn decode_lz4(_bytes: Vec<u8>) -> Result<Vec<u8>> {
+        Err(CompressionError::Unavailable(Compression::Lz4))
+    }
 }
 
 impl From<String> for Compression {
@@ -190,6 +255,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "snappy")]
     fn test_compression_encode_snappy() {
         let snappy_compression = Compression::Snappy;
         let bytes = String::from("Hello World").into_bytes().to_vec();
@@ -199,6 +265,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "snappy")]
     fn test_compression_decode_snappy() {
         let snappy_compression = Compression::Snappy;
         let bytes = String::from("Hello World").into_bytes().to_vec();
@@ -207,6 +274,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "lz4")]
     fn test_compression_encode_lz4() {
         let snappy_compression = Compression::Lz4;
         let bytes = String::from("Hello World").into_bytes().to_vec();
@@ -216,6 +284,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "lz4")]
     fn test_compression_decode_lz4() {
         let lz4_compression = Compression::Lz4;
         let bytes = String::from("Hello World").into_bytes().to_vec();
@@ -241,6 +310,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "lz4")]
     fn test_compression_encode_lz4_with_invalid_input() {
         let lz4_compression = Compression::Lz4;
         let decode = lz4_compression.decode(vec![0, 0, 0, 0x7f, 0]);
@@ -248,6 +318,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "snappy")]
     fn test_compression_encode_snappy_with_non_utf8() {
         let snappy_compression = Compression::Snappy;
         let v = vec![0xff, 0xff];
@@ -256,4 +327,22 @@ mod tests {
             .expect("Should work without exceptions");
         assert_eq!(snappy_compression.decode(encoded).unwrap(), v);
     }
+
+    #[test]
+    #[cfg(not(feature = "lz4"))]
+    fn test_compression_lz4_unavailable() {
+        assert!(matches!(
+            Compression::Lz4.encode(&[1, 2, 3]),
+            Err(CompressionError::Unavailable(Compression::Lz4))
+        ));
+    }
+
+    #[test]
+    #[cfg(not(feature = "snappy"))]
+    fn test_compression_snappy_unavailable() {
+        assert!(matches!(
+            Compression::Snappy.decode(vec![1, 2, 3]),
+            Err(CompressionError::Unavailable(Compression::Snappy))
+        ));
+    }
 }
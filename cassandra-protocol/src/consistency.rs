@@ -7,7 +7,7 @@ use std::io;
 use std::str::FromStr;
 
 use crate::error;
-use crate::frame::{FromBytes, FromCursor, Serialize};
+use crate::frame::{DecodeMode, FromBytes, FromCursor, Serialize};
 use crate::types::*;
 
 /// `Consistency` is an enum which represents Cassandra's consistency levels.
@@ -72,8 +72,9 @@ pub enum Consistency {
     /// to online nodes in other data centers if an offline node goes down.
     LocalOne,
     /// This is an error scenario either the client code doesn't support it or server is sending
-    /// bad headers
-    Unknown,
+    /// bad headers. Contains the raw code, which is serialized back unchanged.
+    #[display(fmt = "Unknown({})", _0)]
+    Unknown(i16),
 }
 
 impl Default for Consistency {
@@ -131,7 +132,7 @@ impl From<i16> for Consistency {
             0x0008 => Consistency::Serial,
             0x0009 => Consistency::LocalSerial,
             0x000A => Consistency::LocalOne,
-            _ => Consistency::Unknown,
+            code => Consistency::Unknown(code),
         }
     }
 }
@@ -150,8 +151,7 @@ impl From<Consistency> for i16 {
             Consistency::Serial => 0x0008,
             Consistency::LocalSerial => 0x0009,
             Consistency::LocalOne => 0x000A,
-            Consistency::Unknown => 0x0063,
-            // giving Unknown a value of 99
+            Consistency::Unknown(code) => code,
         }
     }
 }
@@ -165,12 +165,29 @@ impl FromBytes for Consistency {
 }
 
 impl FromCursor for Consistency {
+    /// Decodes a consistency using the default (lenient) mode, i.e. codes not defined by the spec
+    /// result in `Consistency::Unknown`. Use `from_cursor_with_mode` to reject them.
     fn from_cursor(cursor: &mut io::Cursor<&[u8]>) -> error::Result<Consistency> {
-        CIntShort::from_cursor(cursor).map(Into::into)
+        Self::from_cursor_with_mode(cursor, DecodeMode::default())
     }
 }
 
 impl Consistency {
+    /// Decodes a consistency. Unknown values result in `Error::UnknownConsistency` in strict
+    /// mode, or `Consistency::Unknown` in lenient mode.
+    pub fn from_cursor_with_mode(
+        cursor: &mut io::Cursor<&[u8]>,
+        mode: DecodeMode,
+    ) -> error::Result<Consistency> {
+        let value = CIntShort::from_cursor(cursor)?;
+        match Consistency::from(value) {
+            Consistency::Unknown(_) if mode == DecodeMode::Strict => {
+                Err(error::Error::UnknownConsistency(value as u16))
+            }
+            consistency => Ok(consistency),
+        }
+    }
+
     /// Does this consistency require local dc.
     #[inline]
     pub fn is_dc_local(self) -> bool {
@@ -179,6 +196,65 @@ impl Consistency {
             Consistency::LocalOne | Consistency::LocalQuorum | Consistency::LocalSerial
         )
     }
+
+    /// Is this a serial consistency, used for the Paxos phase of lightweight transactions.
+    #[inline]
+    pub fn is_serial(self) -> bool {
+        matches!(self, Consistency::Serial | Consistency::LocalSerial)
+    }
+
+    /// Checks if `consistency` is a regular level and `serial_consistency`, if present, is a
+    /// serial one, since using one in place of the other gets rejected by the server for writes,
+    /// e.g. batches. Use `validate_serial_consistency` for reads, which can use serial levels.
+    pub fn validate_levels(
+        consistency: Consistency,
+        serial_consistency: Option<Consistency>,
+    ) -> error::Result<()> {
+        if consistency.is_serial() {
+            return Err(error::Error::General(format!(
+                "Serial consistency {} cannot be used as a regular consistency",
+                consistency
+            )));
+        }
+
+        Consistency::validate_serial_consistency(serial_consistency)
+    }
+
+    /// Checks if `serial_consistency`, if present, is a serial level. Unlike `validate_levels`,
+    /// the main consistency is not restricted, since linearizable reads use a serial one.
+    pub fn validate_serial_consistency(
+        serial_consistency: Option<Consistency>,
+    ) -> error::Result<()> {
+        match serial_consistency {
+            Some(serial_consistency) if !serial_consistency.is_serial() => {
+                Err(error::Error::General(format!(
+                    "Consistency {} cannot be used as a serial consistency",
+                    serial_consistency
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the next weaker consistency level, which can be used to retry a request after an
+    /// `Unavailable` or timeout error. The chain ends at `One` (or `LocalOne` for dc-local levels),
+    /// for which `None` is returned. Serial levels cannot be downgraded.
+    pub fn downgrade(&self) -> Option<Consistency> {
+        match self {
+            Consistency::All => Some(Consistency::Quorum),
+            Consistency::Quorum => Some(Consistency::One),
+            Consistency::Three => Some(Consistency::Two),
+            Consistency::Two => Some(Consistency::One),
+            Consistency::EachQuorum => Some(Consistency::LocalQuorum),
+            Consistency::LocalQuorum => Some(Consistency::LocalOne),
+            Consistency::Any
+            | Consistency::One
+            | Consistency::LocalOne
+            | Consistency::Serial
+            | Consistency::LocalSerial
+            | Consistency::Unknown(_) => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -200,7 +276,11 @@ mod tests {
         assert_eq!(Consistency::Serial.serialize_to_vec(), &[0, 8]);
         assert_eq!(Consistency::LocalSerial.serialize_to_vec(), &[0, 9]);
         assert_eq!(Consistency::LocalOne.serialize_to_vec(), &[0, 10]);
-        assert_eq!(Consistency::Unknown.serialize_to_vec(), &[0, 99]);
+        assert_eq!(Consistency::Unknown(99).serialize_to_vec(), &[0, 99]);
+        assert_eq!(
+            Consistency::Unknown(0x1234).serialize_to_vec(),
+            &[0x12, 0x34]
+        );
     }
 
     #[test]
@@ -216,7 +296,7 @@ mod tests {
         assert_eq!(Consistency::from(8), Consistency::Serial);
         assert_eq!(Consistency::from(9), Consistency::LocalSerial);
         assert_eq!(Consistency::from(10), Consistency::LocalOne);
-        assert_eq!(Consistency::from(11), Consistency::Unknown);
+        assert_eq!(Consistency::from(11), Consistency::Unknown(11));
     }
 
     #[test]
@@ -255,7 +335,7 @@ mod tests {
         );
         assert_eq!(
             Consistency::from_bytes(&[0, 11]).unwrap(),
-            Consistency::Unknown
+            Consistency::Unknown(11)
         );
     }
 
@@ -306,4 +386,103 @@ mod tests {
             Consistency::LocalOne
         );
     }
+
+    #[test]
+    fn test_consistency_from_cursor_unknown() {
+        assert!(matches!(
+            Consistency::from_cursor_with_mode(&mut Cursor::new(&[0, 11]), DecodeMode::Strict),
+            Err(error::Error::UnknownConsistency(0x000B))
+        ));
+        assert!(matches!(
+            Consistency::from_cursor_with_mode(&mut Cursor::new(&[0xff, 0xff]), DecodeMode::Strict),
+            Err(error::Error::UnknownConsistency(0xffff))
+        ));
+        assert_eq!(
+            Consistency::from_cursor_with_mode(&mut Cursor::new(&[0, 11]), DecodeMode::Lenient)
+                .unwrap(),
+            Consistency::Unknown(11)
+        );
+
+        let consistency = Consistency::from_cursor(&mut Cursor::new(&[0x12, 0x34])).unwrap();
+        assert_eq!(consistency, Consistency::Unknown(0x1234));
+        assert_eq!(consistency.serialize_to_vec(), &[0x12, 0x34]);
+        assert_eq!(consistency.to_string(), "Unknown(4660)");
+    }
+
+    #[test]
+    fn test_consistency_spec_levels_roundtrip() {
+        let levels = [
+            Consistency::Any,
+            Consistency::One,
+            Consistency::Two,
+            Consistency::Three,
+            Consistency::Quorum,
+            Consistency::All,
+            Consistency::LocalQuorum,
+            Consistency::EachQuorum,
+            Consistency::Serial,
+            Consistency::LocalSerial,
+            Consistency::LocalOne,
+        ];
+
+        for (code, level) in levels.iter().enumerate() {
+            let bytes = level.serialize_to_vec();
+            assert_eq!(bytes, &[0, code as u8]);
+            assert_eq!(
+                Consistency::from_cursor(&mut Cursor::new(bytes.as_slice())).unwrap(),
+                *level
+            );
+        }
+
+        assert!(Consistency::LocalOne.is_dc_local());
+    }
+
+    #[test]
+    fn test_consistency_local_quorum_roundtrip() {
+        let bytes = Consistency::LocalQuorum.serialize_to_vec();
+        assert_eq!(bytes, &[0, 6]);
+        assert_eq!(
+            Consistency::from_cursor(&mut Cursor::new(bytes.as_slice())).unwrap(),
+            Consistency::LocalQuorum
+        );
+    }
+
+    #[test]
+    fn test_consistency_downgrade() {
+        assert_eq!(Consistency::All.downgrade(), Some(Consistency::Quorum));
+        assert_eq!(Consistency::Quorum.downgrade(), Some(Consistency::One));
+        assert_eq!(Consistency::Three.downgrade(), Some(Consistency::Two));
+        assert_eq!(Consistency::Two.downgrade(), Some(Consistency::One));
+        assert_eq!(
+            Consistency::EachQuorum.downgrade(),
+            Some(Consistency::LocalQuorum)
+        );
+        assert_eq!(
+            Consistency::LocalQuorum.downgrade(),
+            Some(Consistency::LocalOne)
+        );
+        assert_eq!(Consistency::One.downgrade(), None);
+        assert_eq!(Consistency::LocalOne.downgrade(), None);
+        assert_eq!(Consistency::Any.downgrade(), None);
+        assert_eq!(Consistency::Serial.downgrade(), None);
+        assert_eq!(Consistency::LocalSerial.downgrade(), None);
+    }
+
+    #[test]
+    fn test_consistency_validate_levels() {
+        assert!(Consistency::validate_levels(Consistency::Quorum, None).is_ok());
+        assert!(
+            Consistency::validate_levels(Consistency::Quorum, Some(Consistency::LocalSerial))
+                .is_ok()
+        );
+        assert!(Consistency::validate_levels(Consistency::Serial, None).is_err());
+        assert!(Consistency::validate_levels(Consistency::Quorum, Some(Consistency::One)).is_err());
+    }
+
+    #[test]
+    fn test_consistency_validate_serial_consistency() {
+        assert!(Consistency::validate_serial_consistency(None).is_ok());
+        assert!(Consistency::validate_serial_consistency(Some(Consistency::Serial)).is_ok());
+        assert!(Consistency::validate_serial_consistency(Some(Consistency::Quorum)).is_err());
+    }
 }
@@ -0,0 +1,64 @@
+//! Constants defined by the native protocol.
+
+/// Length of a frame header, in bytes.
+pub const FRAME_HEADER_LEN: usize = 9;
+
+/// Length prefix of a `[bytes]` or `[value]` denoting a null value.
+pub const NULL_LENGTH: i32 = -1;
+
+/// Length prefix of a `[value]` denoting a value which is not set, e.g. a bound variable which
+/// should be left unchanged.
+pub const NOT_SET_LENGTH: i32 = -2;
+
+/// Maximum length of a frame body allowed by the protocol, in bytes (256 MiB).
+pub const MAX_FRAME_BODY_LEN: usize = 256 * 1024 * 1024;
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::compression::Compression;
+    use crate::error::Error;
+    use crate::frame::raw_frame::FrameHeader;
+    use crate::frame::{Frame, Serialize, Version};
+    use crate::types::value::Value;
+    use crate::types::CBytes;
+
+    #[test]
+    fn constants_match_serializers() {
+        let frame = Frame::new_req_options(Version::V4);
+        assert_eq!(
+            frame.encode_with(Compression::None).unwrap().len(),
+            FRAME_HEADER_LEN + frame.body.len()
+        );
+
+        assert_eq!(Value::Null.serialize_to_vec(), NULL_LENGTH.to_be_bytes());
+        assert_eq!(
+            Value::NotSet.serialize_to_vec(),
+            NOT_SET_LENGTH.to_be_bytes()
+        );
+        assert_eq!(
+            CBytes::new_empty().serialize_to_vec(),
+            NULL_LENGTH.to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn max_frame_body_len_enforced() {
+        let header = |body_len: usize| {
+            let mut header = [0x84, 0, 0, 0, 8, 0, 0, 0, 0];
+            header[5..].copy_from_slice(&(body_len as i32).to_be_bytes());
+            header
+        };
+
+        assert_eq!(
+            FrameHeader::parse(&header(MAX_FRAME_BODY_LEN))
+                .unwrap()
+                .body_length,
+            MAX_FRAME_BODY_LEN
+        );
+        assert!(matches!(
+            FrameHeader::parse(&header(MAX_FRAME_BODY_LEN + 1)),
+            Err(Error::Protocol(_))
+        ));
+    }
+}
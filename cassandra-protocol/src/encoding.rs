@@ -0,0 +1,318 @@
+//! Core encoding primitives, which only depend on `core` and `alloc`. They are available without
+//! the `std` feature, e.g. for embedded users which only need to encode values.
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+use core::num::{NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8};
+
+use crate::constants::{NOT_SET_LENGTH, NULL_LENGTH};
+
+/// Error decoding a primitive from a byte slice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecodeError {
+    /// Input ended before the whole value could be read.
+    UnexpectedEnd { needed: usize, remaining: usize },
+    /// Length prefix which is not valid in given context.
+    InvalidLength(i32),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedEnd { needed, remaining } => write!(
+                f,
+                "Unexpected end of input: needed {} bytes, {} remaining",
+                needed, remaining
+            ),
+            DecodeError::InvalidLength(length) => write!(f, "Invalid length: {}", length),
+        }
+    }
+}
+
+/// Appends the protocol representation of a value to a buffer. The `alloc`-only counterpart of
+/// `Serialize`.
+pub trait Encode {
+    fn encode(&self, buffer: &mut Vec<u8>);
+
+    fn encode_to_vec(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        self.encode(&mut buffer);
+        buffer
+    }
+}
+
+/// Decodes a value from the beginning of given input, advancing it past the value. The
+/// `alloc`-only counterpart of `FromCursor`.
+pub trait Decode: Sized {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError>;
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], DecodeError> {
+    if input.len() < len {
+        return Err(DecodeError::UnexpectedEnd {
+            needed: len,
+            remaining: input.len(),
+        });
+    }
+
+    let (value, rest) = input.split_at(len);
+    *input = rest;
+    Ok(value)
+}
+
+macro_rules! impl_primitive {
+    ($($t:ty),+) => {
+        $(
+            impl Encode for $t {
+                #[inline]
+                fn encode(&self, buffer: &mut Vec<u8>) {
+                    buffer.extend_from_slice(&self.to_be_bytes());
+                }
+            }
+
+            impl Decode for $t {
+                #[inline]
+                fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+                    let mut bytes = [0; core::mem::size_of::<$t>()];
+                    let len = bytes.len();
+                    bytes.copy_from_slice(take(input, len)?);
+                    Ok(<$t>::from_be_bytes(bytes))
+                }
+            }
+        )+
+    };
+}
+
+impl_primitive!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64);
+
+/// Cassandra value which could be an array of bytes, null and non-set values.
+#[derive(Debug, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+pub enum Value {
+    Some(Vec<u8>),
+    Null,
+    NotSet,
+}
+
+impl Value {
+    /// The factory method which creates a normal type value basing on provided bytes.
+    pub fn new<B>(v: B) -> Value
+    where
+        B: Into<Bytes>,
+    {
+        Value::Some(v.into().0)
+    }
+
+    /// Returns the raw bytes of a set value, or `None` for null and not set values.
+    #[inline]
+    pub fn bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Some(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Encodes the value as `[value]`.
+impl Encode for Value {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        match self {
+            Value::Null => NULL_LENGTH.encode(buffer),
+            Value::NotSet => NOT_SET_LENGTH.encode(buffer),
+            Value::Some(value) => {
+                (value.len() as i32).encode(buffer);
+                buffer.extend_from_slice(value);
+            }
+        }
+    }
+}
+
+/// Decodes a `[value]`.
+impl Decode for Value {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        match i32::decode(input)? {
+            len if len >= 0 => Ok(Value::Some(take(input, len as usize)?.to_vec())),
+            NULL_LENGTH => Ok(Value::Null),
+            NOT_SET_LENGTH => Ok(Value::NotSet),
+            len => Err(DecodeError::InvalidLength(len)),
+        }
+    }
+}
+
+impl<T: Into<Bytes>> From<T> for Value {
+    fn from(b: T) -> Value {
+        Value::new(b.into())
+    }
+}
+
+impl<T: Into<Bytes>> From<Option<T>> for Value {
+    fn from(b: Option<T>) -> Value {
+        match b {
+            Some(b) => Value::new(b.into()),
+            None => Value::Null,
+        }
+    }
+}
+
+/// Serialized representation of a single value, without the length.
+#[derive(Debug, Clone)]
+pub struct Bytes(pub(crate) Vec<u8>);
+
+impl Bytes {
+    #[inline]
+    pub fn new(bytes: Vec<u8>) -> Bytes {
+        Bytes(bytes)
+    }
+}
+
+impl From<String> for Bytes {
+    #[inline]
+    fn from(value: String) -> Self {
+        Bytes(value.into_bytes())
+    }
+}
+
+impl From<&str> for Bytes {
+    #[inline]
+    fn from(value: &str) -> Self {
+        Bytes(value.as_bytes().to_vec())
+    }
+}
+
+impl From<&String> for Bytes {
+    #[inline]
+    fn from(value: &String) -> Self {
+        Bytes(value.as_bytes().to_vec())
+    }
+}
+
+impl From<Cow<'_, str>> for Bytes {
+    #[inline]
+    fn from(value: Cow<'_, str>) -> Self {
+        Bytes::from(value.into_owned())
+    }
+}
+
+macro_rules! impl_bytes_from_primitive {
+    ($($t:ty),+) => {
+        $(
+            impl From<$t> for Bytes {
+                #[inline]
+                fn from(value: $t) -> Self {
+                    Bytes(value.encode_to_vec())
+                }
+            }
+        )+
+    };
+}
+
+impl_bytes_from_primitive!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64);
+
+macro_rules! impl_bytes_from_non_zero {
+    ($($t:ty),+) => {
+        $(
+            impl From<$t> for Bytes {
+                #[inline]
+                fn from(value: $t) -> Self {
+                    value.get().into()
+                }
+            }
+        )+
+    };
+}
+
+impl_bytes_from_non_zero!(NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64);
+
+impl From<bool> for Bytes {
+    #[inline]
+    fn from(value: bool) -> Self {
+        Bytes(alloc::vec![value as u8])
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Bytes {
+    /// Treats the array as a raw blob.
+    #[inline]
+    fn from(value: [u8; N]) -> Self {
+        Bytes(value.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn primitives_roundtrip() {
+        assert_eq!(1i16.encode_to_vec(), vec![0, 1]);
+        assert_eq!((-2i32).encode_to_vec(), vec![0xff, 0xff, 0xff, 0xfe]);
+        assert_eq!(1.5f64.encode_to_vec(), 1.5f64.to_be_bytes().to_vec());
+
+        let mut buffer = Vec::new();
+        7i64.encode(&mut buffer);
+        u16::MAX.encode(&mut buffer);
+        2.5f32.encode(&mut buffer);
+
+        let mut input = buffer.as_slice();
+        assert_eq!(i64::decode(&mut input), Ok(7));
+        assert_eq!(u16::decode(&mut input), Ok(u16::MAX));
+        assert_eq!(f32::decode(&mut input), Ok(2.5));
+        assert!(input.is_empty());
+
+        assert_eq!(
+            i32::decode(&mut [0, 1].as_ref()),
+            Err(DecodeError::UnexpectedEnd {
+                needed: 4,
+                remaining: 2
+            })
+        );
+    }
+
+    #[test]
+    fn value_roundtrip() {
+        let values = [
+            Value::new(1i32),
+            Value::new("abc"),
+            Value::from(None::<i64>),
+            Value::NotSet,
+        ];
+
+        let mut buffer = Vec::new();
+        for value in &values {
+            value.encode(&mut buffer);
+        }
+
+        assert_eq!(
+            &buffer[..8],
+            &[0, 0, 0, 4, 0, 0, 0, 1],
+            "int value should be length prefixed"
+        );
+
+        let mut input = buffer.as_slice();
+        for value in &values {
+            assert_eq!(&Value::decode(&mut input).unwrap(), value);
+        }
+        assert!(input.is_empty());
+
+        assert_eq!(
+            Value::decode(&mut [0xff, 0xff, 0xff, 0xfd].as_ref()),
+            Err(DecodeError::InvalidLength(-3))
+        );
+    }
+
+    #[test]
+    fn bytes_conversions() {
+        assert_eq!(Value::new(true), Value::Some(vec![1]));
+        assert_eq!(Value::new(-1i8), Value::Some(vec![0xff]));
+        assert_eq!(
+            Value::new(NonZeroI16::new(2).unwrap()),
+            Value::Some(vec![0, 2])
+        );
+        assert_eq!(Value::new([1u8, 2]), Value::Some(vec![1, 2]));
+        assert_eq!(
+            Value::new(Cow::Borrowed("a")),
+            Value::new(String::from("a"))
+        );
+    }
+}
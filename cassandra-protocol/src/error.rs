@@ -26,12 +26,30 @@ pub enum Error {
     /// General error
     #[error("General error: {0}")]
     General(String),
+    /// Malformed or unexpected data received from the other side, as opposed to an IO failure.
+    #[error("Protocol error: {0}")]
+    Protocol(String),
+    /// Protocol version (with the direction bit masked out) not supported by the driver.
+    #[error("Unsupported protocol version: {0}")]
+    UnsupportedVersion(u8),
+    /// Consistency code not defined by the protocol spec.
+    #[error("Unknown consistency: {0:#06x}")]
+    UnknownConsistency(u16),
     /// Internal error that may be raised during `String::from_utf8`
     #[error("Utf8 error: {0}")]
     FromUtf8(#[from] FromUtf8Error),
+    /// Protocol string which is not valid UTF-8. `offset` points to the first invalid byte.
+    #[error("Invalid UTF-8 in string at byte offset {offset}")]
+    InvalidUtf8 { offset: usize },
     /// Internal Compression/Decompression error
     #[error("Compressor error: {0}")]
     Compression(#[from] CompressionError),
+    /// Decoded value holds a different type than requested.
+    #[error("Type mismatch: expected {expected}, got {actual}")]
+    TypeMismatch {
+        expected: &'static str,
+        actual: &'static str,
+    },
     /// Server error.
     #[error("Server error: {0:?}")]
     Server(CdrsError),
@@ -1,14 +1,20 @@
 //! `frame` module contains general Frame functionality.
 use bitflags::bitflags;
 use derive_more::Display;
+use std::collections::BTreeMap;
 use std::convert::TryFrom;
+use std::io::{Cursor, Read};
 use std::sync::atomic::{AtomicI16, Ordering};
 use uuid::Uuid;
 
 use crate::compression::Compression;
-use crate::frame::frame_request::RequestBody;
+use crate::constants::FRAME_HEADER_LEN;
+use crate::frame::frame_request::{RequestBody, RequestMessage};
 use crate::frame::frame_response::ResponseBody;
+use crate::frame::raw_frame::FrameHeader;
 pub use crate::frame::traits::*;
+use crate::frame::warning::Warning;
+use crate::types::{serialize_str, CInt, CIntShort, INT_LEN, SHORT_LEN, UUID_LEN};
 
 /// Number of stream bytes in accordance to protocol.
 pub const STREAM_LEN: usize = 2;
@@ -21,6 +27,7 @@ pub mod frame_auth_response;
 pub mod frame_auth_success;
 pub mod frame_authenticate;
 pub mod frame_batch;
+pub mod frame_builder;
 pub mod frame_error;
 pub mod frame_event;
 pub mod frame_execute;
@@ -28,13 +35,17 @@ pub mod frame_options;
 pub mod frame_prepare;
 pub mod frame_query;
 pub mod frame_ready;
+pub mod frame_reassembler;
 pub mod frame_register;
 pub mod frame_request;
 pub mod frame_response;
 pub mod frame_result;
 pub mod frame_startup;
 pub mod frame_supported;
+pub mod interceptor;
+pub mod raw_frame;
 pub mod traits;
+pub mod warning;
 
 use crate::error;
 
@@ -74,6 +85,11 @@ pub struct Frame {
     pub body: Vec<u8>,
     pub tracing_id: Option<Uuid>,
     pub warnings: Vec<String>,
+    /// Custom payload, e.g. with implementation-specific information. Encoded before the body
+    /// when not empty.
+    pub custom_payload: BTreeMap<String, Vec<u8>>,
+    /// Forces sending this frame uncompressed, regardless of connection compression settings.
+    pub compression_disabled: bool,
 }
 
 impl Frame {
@@ -96,15 +112,93 @@ impl Frame {
             body,
             tracing_id,
             warnings,
+            custom_payload: BTreeMap::new(),
+            compression_disabled: false,
+        }
+    }
+
+    /// Creates a request frame carrying given body, with the opcode derived from the body type.
+    pub fn new_request<B: RequestMessage>(body: &B, flags: Flags, version: Version) -> Self {
+        Frame::new(
+            version,
+            Direction::Request,
+            flags,
+            B::OPCODE,
+            body.serialize_body(version),
+            None,
+            vec![],
+        )
+    }
+
+    /// Creates a frame from a header and an already serialized body, e.g. one captured earlier
+    /// for replaying. The stream id is taken from the header as-is.
+    ///
+    /// The caller guarantees that:
+    /// - `body` is a valid serialized message for the opcode and version in `header`,
+    /// - `body` is not compressed (the `COMPRESSION` flag is recomputed when encoding),
+    /// - `body` does not contain a tracing id or warnings prefix.
+    ///
+    /// In debug builds, the length declared in `header` is checked against the body.
+    pub fn from_raw_parts(header: FrameHeader, body: Vec<u8>) -> Self {
+        debug_assert_eq!(
+            header.body_length,
+            body.len(),
+            "declared body length doesn't match actual body"
+        );
+
+        Frame {
+            version: header.version,
+            direction: header.direction,
+            flags: header.flags,
+            opcode: header.opcode,
+            stream: header.stream,
+            body,
+            tracing_id: None,
+            warnings: vec![],
+            custom_payload: BTreeMap::new(),
+            compression_disabled: false,
         }
     }
 
     pub fn request_body(&self) -> error::Result<RequestBody> {
-        RequestBody::try_from(self.body.as_slice(), self.opcode)
+        #[cfg(feature = "tracing")]
+        let _span = self.parse_body_span().entered();
+
+        RequestBody::try_from(self.body.as_slice(), self.opcode, self.version)
     }
 
     pub fn response_body(&self) -> error::Result<ResponseBody> {
-        ResponseBody::try_from(self.body.as_slice(), self.opcode, self.version)
+        self.response_body_with_mode(DecodeMode::default())
+    }
+
+    /// Decodes the response body, handling unknown enum values according to given mode.
+    pub fn response_body_with_mode(&self, mode: DecodeMode) -> error::Result<ResponseBody> {
+        #[cfg(feature = "tracing")]
+        let _span = self.parse_body_span().entered();
+
+        ResponseBody::try_from_with_mode(self.body.as_slice(), self.opcode, self.version, mode)
+    }
+
+    #[cfg(feature = "tracing")]
+    fn parse_body_span(&self) -> tracing::Span {
+        tracing::trace_span!(
+            "parse_body",
+            opcode = %self.opcode,
+            stream = self.stream,
+            body_length = self.body.len()
+        )
+    }
+
+    /// Returns the protocol version of the frame, as read from its header when decoded.
+    #[inline]
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns a reader over the (decompressed) frame body, for use with external parsers.
+    #[inline]
+    pub fn body_reader(&self) -> impl Read + '_ {
+        Cursor::new(self.body.as_slice())
     }
 
     #[inline]
@@ -117,40 +211,204 @@ impl Frame {
         &self.warnings
     }
 
+    /// Returns server warnings classified by known message patterns.
+    pub fn typed_warnings(&self) -> Vec<Warning> {
+        self.warnings.iter().cloned().map(Warning::from).collect()
+    }
+
+    #[inline]
+    pub fn custom_payload(&self) -> &BTreeMap<String, Vec<u8>> {
+        &self.custom_payload
+    }
+
+    /// Merges given entries into the custom payload, e.g. when both a request and a middleware
+    /// layer set their own keys. Entries from `extra` replace existing ones with the same key.
+    pub fn merge_custom_payload<P>(&mut self, extra: P)
+    where
+        P: IntoIterator<Item = (String, Vec<u8>)>,
+    {
+        self.custom_payload.extend(extra);
+    }
+
+    /// Returns the length of the frame when encoded without compression, without actually
+    /// encoding it. The result should be compression-adjusted if applicable, since compressed
+    /// frames are usually smaller, although incompressible bodies can grow slightly.
+    #[inline]
+    pub fn estimated_len(&self) -> usize {
+        FRAME_HEADER_LEN + self.prefix_len() + self.body.len()
+    }
+
+    /// Encodes the frame, compressing the body with given compressor unless compression is
+    /// disabled for this frame.
+    #[inline]
     pub fn encode_with(&self, compressor: Compression) -> error::Result<Vec<u8>> {
-        let combined_version_byte = u8::from(self.version) | u8::from(self.direction);
-        let flag_byte = self.flags.bits();
-        let opcode_byte = u8::from(self.opcode);
+        self.encode_with_threshold(compressor, 0)
+    }
 
-        let mut v = Vec::with_capacity(9);
+    /// Encodes the frame, compressing the body with given compressor only if it's at least
+    /// `threshold` bytes long and compression is not disabled for this frame. The
+    /// `COMPRESSION` flag is set accordingly.
+    #[inline]
+    pub fn encode_with_threshold(
+        &self,
+        compressor: Compression,
+        threshold: usize,
+    ) -> error::Result<Vec<u8>> {
+        self.encode_with_extra_flags(compressor, threshold, Flags::empty())
+    }
+
+    /// Encodes the frame like `encode_with_threshold`, additionally setting given header flags,
+    /// e.g. `Flags::BETA` when a connection uses a beta protocol version. Only flags which don't
+    /// announce body content should be passed, since the body is not altered.
+    pub fn encode_with_extra_flags(
+        &self,
+        compressor: Compression,
+        threshold: usize,
+        extra_flags: Flags,
+    ) -> error::Result<Vec<u8>> {
+        let (flags, mut body) = self.encode_prefix();
+        let flags = flags | (extra_flags & Flags::supported_by(self.version));
+        body.extend_from_slice(&self.body);
+
+        let compressor = if self.compression_disabled || body.len() < threshold {
+            Compression::None
+        } else {
+            compressor
+        };
 
-        v.push(combined_version_byte);
-        v.push(flag_byte);
-        v.extend_from_slice(&self.stream.to_be_bytes());
-        v.push(opcode_byte);
+        let mut v = Vec::with_capacity(FRAME_HEADER_LEN + body.len());
 
         if compressor.is_compressed() {
-            let mut encoded_body = compressor.encode(&self.body)?;
+            let mut encoded_body = compressor.encode(&body)?;
 
-            let body_len = encoded_body.len() as i32;
-            v.extend_from_slice(&body_len.to_be_bytes());
+            v.extend_from_slice(&self.encode_header(flags, true, encoded_body.len()));
             v.append(&mut encoded_body);
         } else {
-            let body_len = self.body.len() as i32;
-            v.extend_from_slice(&body_len.to_be_bytes());
-            v.extend_from_slice(&self.body);
+            v.extend_from_slice(&self.encode_header(flags, false, body.len()));
+            v.append(&mut body);
         }
 
         Ok(v)
     }
+
+    /// Splits the frame into an encoded header and an uncompressed body, e.g. for writing them
+    /// with vectored IO without concatenating. The body includes the tracing id, warnings and
+    /// custom payload, if present.
+    pub fn into_parts(self) -> ([u8; FRAME_HEADER_LEN], Vec<u8>) {
+        let (flags, mut body) = self.encode_prefix();
+        let header = self.encode_header(flags, false, body.len() + self.body.len());
+
+        let body = if body.is_empty() {
+            self.body
+        } else {
+            body.extend_from_slice(&self.body);
+            body
+        };
+
+        (header, body)
+    }
+
+    // Returns the flags to encode, along with the body prefix they announce: tracing id and
+    // warnings for responses, followed by the custom payload.
+    fn encode_prefix(&self) -> (Flags, Vec<u8>) {
+        let supported = Flags::supported_by(self.version);
+        let mut flags = self.flags & supported;
+        let mut prefix = vec![];
+        let mut cursor = Cursor::new(&mut prefix);
+
+        if self.direction == Direction::Response {
+            flags.set(Flags::TRACING, self.tracing_id.is_some());
+            if let Some(tracing_id) = &self.tracing_id {
+                tracing_id.as_bytes().as_ref().serialize(&mut cursor);
+            }
+
+            flags.set(
+                Flags::WARNING,
+                supported.contains(Flags::WARNING) && !self.warnings.is_empty(),
+            );
+            if flags.contains(Flags::WARNING) {
+                (self.warnings.len() as CIntShort).serialize(&mut cursor);
+                for warning in &self.warnings {
+                    serialize_str(&mut cursor, warning);
+                }
+            }
+        }
+
+        if supported.contains(Flags::CUSTOM_PAYLOAD) && !self.custom_payload.is_empty() {
+            flags.insert(Flags::CUSTOM_PAYLOAD);
+        }
+
+        if flags.contains(Flags::CUSTOM_PAYLOAD) {
+            (self.custom_payload.len() as CIntShort).serialize(&mut cursor);
+            for (key, value) in &self.custom_payload {
+                serialize_str(&mut cursor, key);
+                (value.len() as CInt).serialize(&mut cursor);
+                value.serialize(&mut cursor);
+            }
+        }
+
+        (flags, prefix)
+    }
+
+    // Returns the length of the body prefix written by `encode_prefix`, without writing it.
+    fn prefix_len(&self) -> usize {
+        let supported = Flags::supported_by(self.version);
+        let mut len = 0;
+
+        if self.direction == Direction::Response {
+            if self.tracing_id.is_some() {
+                len += UUID_LEN;
+            }
+
+            if supported.contains(Flags::WARNING) && !self.warnings.is_empty() {
+                len += SHORT_LEN
+                    + self
+                        .warnings
+                        .iter()
+                        .map(|warning| SHORT_LEN + warning.len())
+                        .sum::<usize>();
+            }
+        }
+
+        if supported.contains(Flags::CUSTOM_PAYLOAD)
+            && (self.flags.contains(Flags::CUSTOM_PAYLOAD) || !self.custom_payload.is_empty())
+        {
+            len += SHORT_LEN
+                + self
+                    .custom_payload
+                    .iter()
+                    .map(|(key, value)| SHORT_LEN + key.len() + INT_LEN + value.len())
+                    .sum::<usize>();
+        }
+
+        len
+    }
+
+    fn encode_header(
+        &self,
+        mut flags: Flags,
+        compressed: bool,
+        body_len: usize,
+    ) -> [u8; FRAME_HEADER_LEN] {
+        flags.set(Flags::COMPRESSION, compressed);
+
+        let mut header = [0; FRAME_HEADER_LEN];
+        header[0] = u8::from(self.version) | u8::from(self.direction);
+        header[1] = flags.bits();
+        header[2..4].copy_from_slice(&self.stream.to_be_bytes());
+        header[4] = u8::from(self.opcode);
+        header[5..].copy_from_slice(&(body_len as i32).to_be_bytes());
+        header
+    }
 }
 
 #[derive(Debug, PartialEq, Copy, Clone, Ord, PartialOrd, Eq, Hash, Display)]
 pub enum Version {
     V3,
     V4,
-    // enable v5 feature when it's actually implemented
-    //V5,
+    /// Only the frame body changes are implemented - segment framing with checksums is not, so
+    /// this version cannot be used with v5 servers yet.
+    V5,
 }
 
 impl From<Version> for u8 {
@@ -158,7 +416,7 @@ impl From<Version> for u8 {
         match value {
             Version::V3 => 3,
             Version::V4 => 4,
-            //Version::V5 => 5,
+            Version::V5 => 5,
         }
     }
 }
@@ -166,15 +424,13 @@ impl From<Version> for u8 {
 impl TryFrom<u8> for Version {
     type Error = error::Error;
 
+    /// Extracts the version from a header version byte, ignoring the direction bit.
     fn try_from(version: u8) -> Result<Self, Self::Error> {
         match version & 0x7F {
             3 => Ok(Version::V3),
             4 => Ok(Version::V4),
-            //5 => Ok(Version::V5),
-            v => Err(error::Error::General(format!(
-                "Unknown cassandra version: {}",
-                v
-            ))),
+            5 => Ok(Version::V5),
+            v => Err(error::Error::UnsupportedVersion(v)),
         }
     }
 }
@@ -208,6 +464,23 @@ impl From<u8> for Direction {
     }
 }
 
+/// Handling of enum values unknown to the driver (e.g. write types or error codes) when
+/// decoding responses, which newer servers might send.
+#[derive(Debug, PartialEq, Copy, Clone, Ord, PartialOrd, Eq, Hash)]
+pub enum DecodeMode {
+    /// Unknown values result in an error.
+    Strict,
+    /// Unknown values are decoded as `Unknown` variants.
+    Lenient,
+}
+
+impl Default for DecodeMode {
+    #[inline]
+    fn default() -> Self {
+        DecodeMode::Lenient
+    }
+}
+
 bitflags! {
     /// Frame's flags
     pub struct Flags: u8 {
@@ -215,6 +488,7 @@ bitflags! {
         const TRACING = 0x02;
         const CUSTOM_PAYLOAD = 0x04;
         const WARNING = 0x08;
+        const BETA = 0x10;
     }
 }
 
@@ -228,6 +502,22 @@ impl Default for Flags {
 impl Flags {
     // Number of opcode bytes in accordance to protocol.
     pub const BYTE_LENGTH: usize = 1;
+
+    /// Returns flags defined by given protocol version. Custom payload, warning and beta flags
+    /// were introduced in protocol v4.
+    pub fn supported_by(version: Version) -> Flags {
+        match version {
+            Version::V3 => Flags::COMPRESSION | Flags::TRACING,
+            Version::V4 | Version::V5 => Flags::all(),
+        }
+    }
+
+    /// Creates flags from header bits, interpreted according to given protocol version. Bits
+    /// not defined by the version are ignored.
+    #[inline]
+    pub fn from_bits_for_version(bits: u8, version: Version) -> Flags {
+        Flags::from_bits_truncate(bits) & Flags::supported_by(version)
+    }
 }
 
 #[derive(Debug, PartialEq, Copy, Clone, Ord, PartialOrd, Eq, Hash, Display)]
@@ -299,7 +589,7 @@ impl TryFrom<u8> for Opcode {
             0x0E => Ok(Opcode::AuthChallenge),
             0x0F => Ok(Opcode::AuthResponse),
             0x10 => Ok(Opcode::AuthSuccess),
-            _ => Err(error::Error::General(format!("Unknown opcode: {}", value))),
+            _ => Err(error::Error::Protocol(format!("Unknown opcode: {}", value))),
         }
     }
 }
@@ -308,30 +598,94 @@ impl TryFrom<u8> for Opcode {
 mod tests {
     use super::*;
     use crate::consistency::Consistency;
+    use crate::frame::frame_builder::FrameBuilder;
     use crate::frame::frame_query::BodyReqQuery;
+    use crate::frame::raw_frame::{decode_frame, RawFrame};
     use crate::query::query_params::QueryParams;
     use crate::query::query_values::QueryValues;
+    use crate::query::Query;
+    use crate::types::paging_state::PagingState;
     use crate::types::value::Value;
-    use crate::types::{CBytes, CStringLong};
+    use crate::types::CStringLong;
+    use std::collections::HashMap;
 
     #[test]
     fn test_frame_version_as_byte() {
         assert_eq!(u8::from(Version::V3), 0x03);
         assert_eq!(u8::from(Version::V4), 0x04);
-        //assert_eq!(u8::from(Version::V5), 0x05);
+        assert_eq!(u8::from(Version::V5), 0x05);
 
         assert_eq!(u8::from(Direction::Request), 0x00);
         assert_eq!(u8::from(Direction::Response), 0x80);
     }
 
+    #[test]
+    fn test_flags_for_version() {
+        let bits = (Flags::TRACING | Flags::CUSTOM_PAYLOAD | Flags::WARNING | Flags::BETA).bits();
+
+        assert_eq!(
+            Flags::from_bits_for_version(bits, Version::V3),
+            Flags::TRACING
+        );
+        assert_eq!(
+            Flags::from_bits_for_version(bits, Version::V4),
+            Flags::from_bits_truncate(bits)
+        );
+        assert_eq!(
+            Flags::from_bits_for_version(bits, Version::V5),
+            Flags::from_bits_truncate(bits)
+        );
+        assert_eq!(
+            Flags::from_bits_for_version(0xE0, Version::V5),
+            Flags::empty()
+        );
+    }
+
+    #[test]
+    fn test_encode_flags_for_version() {
+        let mut frame = Frame::new_req_options(Version::V3);
+        frame.flags = Flags::TRACING | Flags::CUSTOM_PAYLOAD;
+        let encoded = frame.encode_with(Compression::None).unwrap();
+        assert_eq!(encoded[1], Flags::TRACING.bits());
+
+        frame.version = Version::V4;
+        let encoded = frame.encode_with(Compression::None).unwrap();
+        assert_eq!(encoded[1], (Flags::TRACING | Flags::CUSTOM_PAYLOAD).bits());
+    }
+
+    #[test]
+    fn test_encode_extra_flags() {
+        let frame = Frame::new_req_options(Version::V5);
+        let encoded = frame
+            .encode_with_extra_flags(Compression::None, 0, Flags::BETA)
+            .unwrap();
+        assert_eq!(encoded[1], Flags::BETA.bits());
+        assert_eq!(
+            encoded[2..],
+            frame.encode_with(Compression::None).unwrap()[2..]
+        );
+
+        let frame = Frame::new_req_options(Version::V3);
+        let encoded = frame
+            .encode_with_extra_flags(Compression::None, 0, Flags::BETA)
+            .unwrap();
+        assert_eq!(encoded[1], 0);
+    }
+
     #[test]
     fn test_frame_version_from() {
         assert_eq!(Version::try_from(0x03).unwrap(), Version::V3);
         assert_eq!(Version::try_from(0x83).unwrap(), Version::V3);
         assert_eq!(Version::try_from(0x04).unwrap(), Version::V4);
         assert_eq!(Version::try_from(0x84).unwrap(), Version::V4);
-        //assert_eq!(Version::try_from(0x05).unwrap(), Version::V5);
-        //assert_eq!(Version::try_from(0x85).unwrap(), Version::V5);
+        assert_eq!(Version::try_from(0x05).unwrap(), Version::V5);
+        assert_eq!(Version::try_from(0x85).unwrap(), Version::V5);
+
+        // serial consistency is only valid since v3, so older versions must never be accepted
+        assert!(matches!(
+            Version::try_from(0x02),
+            Err(error::Error::UnsupportedVersion(2))
+        ));
 
         assert_eq!(Direction::from(0x03), Direction::Request);
         assert_eq!(Direction::from(0x04), Direction::Request);
@@ -451,6 +805,8 @@ mod tests {
             body: vec![],
             tracing_id: None,
             warnings: vec![],
+            custom_payload: Default::default(),
+            compression_disabled: false,
         };
         let body = ResponseBody::Ready;
         test_encode_decode_roundtrip_response(&raw_frame, frame, body);
@@ -470,6 +826,8 @@ mod tests {
             body: vec![0, 0, 0, 4, 98, 108, 97, 104, 0, 0, 64],
             tracing_id: None,
             warnings: vec![],
+            custom_payload: Default::default(),
+            compression_disabled: false,
         };
         let body = RequestBody::Query(BodyReqQuery {
             query: CStringLong::new("blah".into()),
@@ -485,6 +843,9 @@ mod tests {
                 keyspace: None,
                 token: None,
                 routing_key: None,
+                now_in_seconds: None,
+                compression_disabled: false,
+                max_values_bytes: None,
             },
         });
         test_encode_decode_roundtrip_request(&raw_frame, frame, body);
@@ -508,6 +869,8 @@ mod tests {
             ],
             tracing_id: None,
             warnings: vec![],
+            custom_payload: Default::default(),
+            compression_disabled: false,
         };
         let body = RequestBody::Query(BodyReqQuery {
             query: CStringLong::new("some query".into()),
@@ -526,6 +889,9 @@ mod tests {
                 keyspace: None,
                 token: None,
                 routing_key: None,
+                now_in_seconds: None,
+                compression_disabled: false,
+                max_values_bytes: None,
             },
         });
         test_encode_decode_roundtrip_request(&raw_frame, frame, body);
@@ -542,6 +908,8 @@ mod tests {
             body: vec![],
             tracing_id: None,
             warnings: vec![],
+            custom_payload: Default::default(),
+            compression_disabled: false,
         };
         let body = RequestBody::Query(BodyReqQuery {
             query: CStringLong::new("another query".into()),
@@ -558,15 +926,264 @@ mod tests {
                     .collect(),
                 )),
                 page_size: Some(4),
-                paging_state: Some(CBytes::new(vec![0, 1, 2, 3])),
+                paging_state: Some(PagingState::new(vec![0, 1, 2, 3])),
                 serial_consistency: Some(Consistency::One),
                 timestamp: Some(2000),
                 is_idempotent: false,
                 keyspace: None,
                 token: None,
                 routing_key: None,
+                now_in_seconds: None,
+                compression_disabled: false,
+                max_values_bytes: None,
             },
         });
         test_encode_decode_roundtrip_nondeterministic_request(frame, body);
     }
+
+    #[test]
+    fn test_body_reader() {
+        let frame = Frame::new(
+            Version::V4,
+            Direction::Response,
+            Flags::empty(),
+            Opcode::Result,
+            vec![0, 0, 0, 1, 2, 3],
+            None,
+            vec![],
+        );
+
+        let mut buf = [0; 4];
+        frame.body_reader().read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_estimated_len() {
+        let frame = Frame::new_query(
+            Query {
+                query: "SELECT * FROM ks.tbl".into(),
+                params: QueryParams {
+                    values: Some(QueryValues::SimpleValues(vec![Value::new(1)])),
+                    ..Default::default()
+                },
+            },
+            Flags::empty(),
+            Version::V4,
+        );
+
+        assert_eq!(
+            frame.estimated_len(),
+            frame.encode_with(Compression::None).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn test_estimated_len_with_prefix() {
+        let mut frame = Frame::new(
+            Version::V4,
+            Direction::Response,
+            Flags::empty(),
+            Opcode::Result,
+            vec![0, 0, 0, 1],
+            Some(Uuid::new_v4()),
+            vec!["warning".into(), "another warning".into()],
+        );
+        frame.merge_custom_payload(vec![
+            ("key".to_string(), vec![1, 2, 3]),
+            ("other key".to_string(), vec![]),
+        ]);
+
+        assert_eq!(
+            frame.estimated_len(),
+            frame.encode_with(Compression::None).unwrap().len()
+        );
+
+        frame.version = Version::V3;
+        assert_eq!(
+            frame.estimated_len(),
+            frame.encode_with(Compression::None).unwrap().len()
+        );
+
+        let mut frame = Frame::new_req_options(Version::V4);
+        frame.flags = Flags::CUSTOM_PAYLOAD;
+        assert_eq!(
+            frame.estimated_len(),
+            frame.encode_with(Compression::None).unwrap().len()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "lz4")]
+    fn test_encode_compressed() {
+        let frame = FrameBuilder::new(Opcode::Query)
+            .stream(1)
+            .body(vec![0; 64])
+            .build();
+
+        let encoded = frame.encode_with_threshold(Compression::Lz4, 32).unwrap();
+        assert!(Flags::from_bits_truncate(encoded[1]).contains(Flags::COMPRESSION));
+        assert!(encoded.len() < frame.estimated_len());
+
+        let decoded = RawFrame::from_bytes(&encoded)
+            .unwrap()
+            .into_frame(Compression::Lz4)
+            .unwrap();
+        assert_eq!(decoded.body, frame.body);
+
+        let encoded = frame.encode_with_threshold(Compression::Lz4, 128).unwrap();
+        assert_eq!(encoded, frame.encode_with(Compression::None).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "lz4")]
+    fn test_encode_compressed_header() {
+        let frame = FrameBuilder::new(Opcode::Query)
+            .stream(7)
+            .body(vec![0; 64])
+            .build();
+
+        let plain = frame.encode_with(Compression::None).unwrap();
+        let compressed = frame.encode_with(Compression::Lz4).unwrap();
+
+        // only the body is compressed, and the length field holds its compressed length
+        let mut header = [0; FRAME_HEADER_LEN];
+        header.copy_from_slice(&compressed[..FRAME_HEADER_LEN]);
+        assert_eq!(
+            FrameHeader::parse(&header).unwrap().body_length,
+            compressed.len() - FRAME_HEADER_LEN
+        );
+        assert_eq!(
+            &compressed[FRAME_HEADER_LEN..],
+            Compression::Lz4.encode(&frame.body).unwrap().as_slice()
+        );
+
+        // apart from the compression flag and length, the header is unchanged
+        assert_eq!(compressed[0], plain[0]);
+        assert_eq!(compressed[1], plain[1] | Flags::COMPRESSION.bits());
+        assert_eq!(&compressed[2..5], &plain[2..5]);
+    }
+
+    #[test]
+    fn test_encode_compression_disabled() {
+        let frame = FrameBuilder::new(Opcode::Query)
+            .stream(1)
+            .body(vec![0; 64])
+            .disable_compression()
+            .build();
+
+        let encoded = frame.encode_with(Compression::Lz4).unwrap();
+        assert!(!Flags::from_bits_truncate(encoded[1]).contains(Flags::COMPRESSION));
+        assert_eq!(encoded, frame.encode_with(Compression::None).unwrap());
+    }
+
+    #[test]
+    fn test_merge_custom_payload() {
+        let mut frame = FrameBuilder::new(Opcode::Query)
+            .custom_payload(
+                vec![("a".to_string(), vec![1]), ("b".to_string(), vec![2])]
+                    .into_iter()
+                    .collect(),
+            )
+            .build();
+
+        let extra: HashMap<String, Vec<u8>> =
+            vec![("b".to_string(), vec![20]), ("c".to_string(), vec![30])]
+                .into_iter()
+                .collect();
+        frame.merge_custom_payload(extra);
+
+        assert_eq!(
+            frame.custom_payload().iter().collect::<Vec<_>>(),
+            vec![
+                (&"a".to_string(), &vec![1]),
+                (&"b".to_string(), &vec![20]),
+                (&"c".to_string(), &vec![30]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_merged_custom_payload() {
+        let mut frame = FrameBuilder::new(Opcode::Query).body(vec![1, 2, 3]).build();
+        frame.merge_custom_payload(vec![("a".to_string(), vec![1])]);
+        frame.merge_custom_payload(vec![("b".to_string(), vec![2, 3])]);
+
+        let encoded = frame.encode_with(Compression::None).unwrap();
+        assert!(Flags::from_bits_truncate(encoded[1]).contains(Flags::CUSTOM_PAYLOAD));
+
+        let (decoded, _) = decode_frame(&encoded, Compression::None).unwrap();
+        assert_eq!(decoded.custom_payload, frame.custom_payload);
+        assert_eq!(decoded.body, frame.body);
+
+        let (header, body) = frame.into_parts();
+        assert_eq!([&header[..], &body[..]].concat(), encoded);
+    }
+
+    #[test]
+    fn test_encode_response_prefix() {
+        let mut frame = FrameBuilder::new(Opcode::Result)
+            .direction(Direction::Response)
+            .tracing_id(Uuid::from_u128(1))
+            .warnings(vec!["warning".into()])
+            .body(vec![0, 0, 0, 1])
+            .build();
+        frame.merge_custom_payload(vec![("a".to_string(), vec![1])]);
+
+        let encoded = frame.encode_with(Compression::None).unwrap();
+        assert_eq!(frame.estimated_len(), encoded.len());
+
+        let (decoded, _) = decode_frame(&encoded, Compression::None).unwrap();
+        assert_eq!(
+            decoded.flags,
+            Flags::TRACING | Flags::WARNING | Flags::CUSTOM_PAYLOAD
+        );
+        assert_eq!(decoded.tracing_id, frame.tracing_id);
+        assert_eq!(decoded.warnings, frame.warnings);
+        assert_eq!(decoded.custom_payload, frame.custom_payload);
+        assert_eq!(decoded.body, frame.body);
+    }
+
+    #[test]
+    fn test_into_parts() {
+        let frame = FrameBuilder::new(Opcode::Query)
+            .stream(1)
+            .body(vec![1, 2, 3])
+            .build();
+        let encoded = frame.encode_with(Compression::None).unwrap();
+
+        let (header, body) = frame.into_parts();
+        assert_eq!([&header[..], &body[..]].concat(), encoded);
+    }
+
+    #[test]
+    fn test_encode_mutated_body_length() {
+        let mut frame = FrameBuilder::new(Opcode::Query).body(vec![1, 2, 3]).build();
+        frame.body.extend_from_slice(&[4, 5]);
+
+        let encoded = frame.encode_with(Compression::None).unwrap();
+        let mut header = [0; FRAME_HEADER_LEN];
+        header.copy_from_slice(&encoded[..FRAME_HEADER_LEN]);
+
+        assert_eq!(FrameHeader::parse(&header).unwrap().body_length, 5);
+        assert_eq!(&encoded[FRAME_HEADER_LEN..], &[1, 2, 3, 4, 5]);
+
+        let (header, _) = frame.into_parts();
+        assert_eq!(FrameHeader::parse(&header).unwrap().body_length, 5);
+    }
+
+    #[test]
+    fn test_from_raw_parts_roundtrip() {
+        let captured = Frame::new_req_options(Version::V4)
+            .encode_with(Compression::None)
+            .unwrap();
+
+        let mut header = [0; FRAME_HEADER_LEN];
+        header.copy_from_slice(&captured[..FRAME_HEADER_LEN]);
+        let header = FrameHeader::parse(&header).unwrap();
+
+        let frame = Frame::from_raw_parts(header, captured[FRAME_HEADER_LEN..].to_vec());
+        assert_eq!(frame.opcode, Opcode::Options);
+        assert_eq!(frame.encode_with(Compression::None).unwrap(), captured);
+    }
 }
@@ -140,7 +140,10 @@ impl FromCursor for ServerEvent {
             SCHEMA_CHANGE => Ok(ServerEvent::SchemaChange(SchemaChange::from_cursor(
                 cursor,
             )?)),
-            _ => Err(format!("Unexpected server event: {}", event_type).into()),
+            _ => Err(error::Error::Protocol(format!(
+                "Unexpected server event: {}",
+                event_type
+            ))),
         }
     }
 }
@@ -245,7 +248,10 @@ impl FromCursor for StatusChangeType {
             match sct {
                 UP => Ok(StatusChangeType::Up),
                 DOWN => Ok(StatusChangeType::Down),
-                _ => Err(format!("Unexpected status change type: {}", sct).into()),
+                _ => Err(error::Error::Protocol(format!(
+                    "Unexpected status change type: {}",
+                    sct
+                ))),
             }
         })
     }
@@ -307,7 +313,10 @@ impl FromCursor for SchemaChangeType {
                 CREATED => Ok(SchemaChangeType::Created),
                 UPDATED => Ok(SchemaChangeType::Updated),
                 DROPPED => Ok(SchemaChangeType::Dropped),
-                _ => Err(format!("Unexpected schema change type: {}", ct).into()),
+                _ => Err(error::Error::Protocol(format!(
+                    "Unexpected schema change type: {}",
+                    ct
+                ))),
             }
         })
     }
@@ -345,7 +354,10 @@ impl FromCursor for SchemaChangeTarget {
                 TYPE => Ok(SchemaChangeTarget::Type),
                 FUNCTION => Ok(SchemaChangeTarget::Function),
                 AGGREGATE => Ok(SchemaChangeTarget::Aggregate),
-                _ => Err(format!("Unexpected schema change target: {}", t).into()),
+                _ => Err(error::Error::Protocol(format!(
+                    "Unexpected schema change target: {}",
+                    t
+                ))),
             }
         })
     }
@@ -565,7 +577,24 @@ mod status_change_type_test {
             .unwrap_err()
             .to_string();
 
-        assert_eq!("General error: Unexpected status change type: N", err);
+        assert_eq!("Protocol error: Unexpected status change type: N", err);
+    }
+
+    #[test]
+    fn status_change_with_port() {
+        let a = &[
+            0, 2, 85, 80, // UP
+            4, 10, 0, 0, 1, // address
+            0, 0, 0x23, 0x53, // port 9043
+        ];
+        let mut cursor: Cursor<&[u8]> = Cursor::new(a);
+        let status_change = StatusChange::from_cursor(&mut cursor).unwrap();
+
+        assert_eq!(status_change.change_type, StatusChangeType::Up);
+        assert_eq!(
+            status_change.addr.addr,
+            "10.0.0.1:9043".parse::<std::net::SocketAddr>().unwrap()
+        );
     }
 }
 
@@ -1138,4 +1167,56 @@ mod server_event {
             test_encode_decode(bytes, expected);
         }
     }
+    #[test]
+    fn schema_change_function_with_arguments() {
+        let bytes = &[
+            // schema change
+            0, 13, 83, 67, 72, 69, 77, 65, 95, 67, 72, 65, 78, 71, 69, // updated
+            0, 7, 85, 80, 68, 65, 84, 69, 68, // function
+            0, 8, 70, 85, 78, 67, 84, 73, 79, 78, // my_ks
+            0, 5, 109, 121, 95, 107, 115, // name
+            0, 4, 110, 97, 109, 101, // list of parameters: int, text
+            0, 2, 0, 3, 105, 110, 116, 0, 4, 116, 101, 120, 116,
+        ];
+        let expected = ServerEvent::SchemaChange(SchemaChange {
+            change_type: SchemaChangeType::Updated,
+            target: SchemaChangeTarget::Function,
+            options: SchemaChangeOptions::FunctionAggregate(
+                "my_ks".to_string(),
+                "name".to_string(),
+                vec!["int".to_string(), "text".to_string()],
+            ),
+        });
+        test_encode_decode(bytes, expected);
+    }
+
+    #[test]
+    fn schema_change_dropped_aggregate_with_arguments() {
+        let bytes = &[
+            // schema change
+            0, 13, 83, 67, 72, 69, 77, 65, 95, 67, 72, 65, 78, 71, 69, // dropped
+            0, 7, 68, 82, 79, 80, 80, 69, 68, // aggregate
+            0, 9, 65, 71, 71, 82, 69, 71, 65, 84, 69, // my_ks
+            0, 5, 109, 121, 95, 107, 115, // avg
+            0, 3, 97, 118, 103, // list of parameters: int, text
+            0, 2, 0, 3, 105, 110, 116, 0, 4, 116, 101, 120, 116,
+        ];
+        let expected = ServerEvent::SchemaChange(SchemaChange {
+            change_type: SchemaChangeType::Dropped,
+            target: SchemaChangeTarget::Aggregate,
+            options: SchemaChangeOptions::FunctionAggregate(
+                "my_ks".to_string(),
+                "avg".to_string(),
+                vec!["int".to_string(), "text".to_string()],
+            ),
+        });
+        test_encode_decode(bytes, expected);
+
+        // the argument list is consumed, leaving following data intact
+        let mut with_trailing = bytes.to_vec();
+        with_trailing.push(42);
+        let mut cursor: Cursor<&[u8]> = Cursor::new(with_trailing.as_slice());
+        ServerEvent::from_cursor(&mut cursor).unwrap();
+        assert_eq!(cursor.position() as usize, bytes.len());
+    }
 }
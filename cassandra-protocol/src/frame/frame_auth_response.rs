@@ -1,6 +1,7 @@
 use derive_more::Constructor;
 use std::io::Cursor;
 
+use crate::frame::frame_request::{private, RequestMessage};
 use crate::frame::*;
 use crate::types::CBytes;
 
@@ -16,24 +17,24 @@ impl Serialize for BodyReqAuthResponse {
     }
 }
 
+impl private::Sealed for BodyReqAuthResponse {}
+
+impl RequestMessage for BodyReqAuthResponse {
+    const OPCODE: Opcode = Opcode::AuthResponse;
+
+    #[inline]
+    fn serialize_body(&self, _version: Version) -> Vec<u8> {
+        self.serialize_to_vec()
+    }
+}
+
 // Frame implementation related to BodyReqStartup
 
 impl Frame {
     /// Creates new frame of type `AuthResponse`.
     pub fn new_req_auth_response(token_bytes: CBytes, version: Version) -> Frame {
-        let direction = Direction::Request;
-        let opcode = Opcode::AuthResponse;
         let body = BodyReqAuthResponse::new(token_bytes);
-
-        Frame::new(
-            version,
-            direction,
-            Flags::empty(),
-            opcode,
-            body.serialize_to_vec(),
-            None,
-            vec![],
-        )
+        Frame::new_request(&body, Flags::empty(), version)
     }
 }
 
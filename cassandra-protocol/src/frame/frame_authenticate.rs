@@ -7,9 +7,20 @@ use crate::types::CString;
 /// A server authentication challenge.
 #[derive(Debug, PartialEq, Ord, PartialOrd, Eq, Hash, Clone)]
 pub struct BodyResAuthenticate {
+    /// Full class name of the authenticator used by the server.
     pub data: CString,
 }
 
+impl BodyResAuthenticate {
+    /// Returns the full class name of the authenticator used by the server, e.g.
+    /// `org.apache.cassandra.auth.PasswordAuthenticator`, which can be used to select a
+    /// matching `Authenticator`.
+    #[inline]
+    pub fn authenticator(&self) -> &str {
+        self.data.as_str()
+    }
+}
+
 impl FromCursor for BodyResAuthenticate {
     fn from_cursor(mut cursor: &mut Cursor<&[u8]>) -> error::Result<BodyResAuthenticate> {
         Ok(BodyResAuthenticate {
@@ -32,4 +43,15 @@ mod tests {
         let body = BodyResAuthenticate::from_cursor(&mut cursor).unwrap();
         assert_eq!(body.data.as_str(), "abcde");
     }
+
+    #[test]
+    fn body_res_authenticate_password_authenticator() {
+        let class_name = "org.apache.cassandra.auth.PasswordAuthenticator";
+        let mut data = vec![0, class_name.len() as u8];
+        data.extend_from_slice(class_name.as_bytes());
+
+        let mut cursor: Cursor<&[u8]> = Cursor::new(&data);
+        let body = BodyResAuthenticate::from_cursor(&mut cursor).unwrap();
+        assert_eq!(body.authenticator(), class_name);
+    }
 }
@@ -1,6 +1,8 @@
 use std::io::Cursor;
+use std::iter::FromIterator;
 
 use crate::consistency::Consistency;
+use crate::frame::frame_request::{private, RequestMessage};
 use crate::frame::*;
 use crate::query::QueryValues;
 use crate::query::{PreparedQuery, QueryFlags};
@@ -12,17 +14,82 @@ use crate::Error;
 pub struct BodyReqBatch {
     pub batch_type: BatchType,
     pub queries: Vec<BatchQuery>,
+    /// Batch consistency. Defaults to `Consistency::One`.
     pub consistency: Consistency,
     // **IMPORTANT NOTE:** with names flag does not work and should not be used.
     pub query_flags: QueryFlags,
     pub serial_consistency: Option<Consistency>,
+    /// Default timestamp in microseconds since the Unix epoch.
     pub timestamp: Option<i64>,
     pub is_idempotent: bool,
+    /// Keyspace to execute the batch in instead of the current one. Only sent with protocol v5
+    /// and above.
     pub keyspace: Option<String>,
+    /// Reference time in seconds for TTL and tombstone evaluation, used instead of the current
+    /// server time. Only sent with protocol v5 and above.
+    pub now_in_seconds: Option<i32>,
+    /// Forces sending the batch uncompressed, regardless of connection compression settings.
+    pub compression_disabled: bool,
+}
+
+impl Default for BodyReqBatch {
+    fn default() -> Self {
+        BodyReqBatch {
+            batch_type: BatchType::Logged,
+            queries: vec![],
+            consistency: Consistency::One,
+            query_flags: QueryFlags::empty(),
+            serial_consistency: None,
+            timestamp: None,
+            is_idempotent: false,
+            keyspace: None,
+            now_in_seconds: None,
+            compression_disabled: false,
+        }
+    }
+}
+
+impl FromIterator<BatchQuery> for BodyReqBatch {
+    /// Creates a batch with default parameters containing given queries.
+    fn from_iter<T: IntoIterator<Item = BatchQuery>>(iter: T) -> Self {
+        BodyReqBatch {
+            queries: iter.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl BodyReqBatch {
+    /// Returns flags which will be sent with given protocol version, i.e. `query_flags` with
+    /// serial consistency, timestamp, keyspace and now in seconds flags matching the presence of
+    /// the corresponding values.
+    pub fn flags(&self, version: Version) -> QueryFlags {
+        let mut flags = self.query_flags;
+        flags.set(
+            QueryFlags::WITH_SERIAL_CONSISTENCY,
+            self.serial_consistency.is_some(),
+        );
+        flags.set(QueryFlags::WITH_DEFAULT_TIMESTAMP, self.timestamp.is_some());
+
+        let is_v5 = version >= Version::V5;
+        flags.set(QueryFlags::WITH_KEYSPACE, is_v5 && self.keyspace.is_some());
+        flags.set(
+            QueryFlags::WITH_NOW_IN_SECONDS,
+            is_v5 && self.now_in_seconds.is_some(),
+        );
+        flags
+    }
 }
 
 impl Serialize for BodyReqBatch {
+    #[inline]
     fn serialize(&self, cursor: &mut Cursor<&mut Vec<u8>>) {
+        self.serialize_with_version(cursor, Version::V4);
+    }
+}
+
+impl SerializeWithVersion for BodyReqBatch {
+    fn serialize_with_version(&self, cursor: &mut Cursor<&mut Vec<u8>>, version: Version) {
         let batch_type = u8::from(self.batch_type);
         batch_type.serialize(cursor);
 
@@ -36,9 +103,12 @@ impl Serialize for BodyReqBatch {
         let consistency: i16 = self.consistency.into();
         consistency.serialize(cursor);
 
-        let flag_byte = self.query_flags.bits();
-
-        flag_byte.serialize(cursor);
+        let flag_bits = self.flags(version).bits();
+        if version >= Version::V5 {
+            flag_bits.serialize(cursor);
+        } else {
+            (flag_bits as u8).serialize(cursor);
+        }
 
         if let Some(serial_consistency) = self.serial_consistency {
             let serial_consistency: i16 = serial_consistency.into();
@@ -48,6 +118,16 @@ impl Serialize for BodyReqBatch {
         if let Some(timestamp) = self.timestamp {
             timestamp.serialize(cursor);
         }
+
+        if version >= Version::V5 {
+            if let Some(keyspace) = &self.keyspace {
+                serialize_str(cursor, keyspace);
+            }
+
+            if let Some(now_in_seconds) = self.now_in_seconds {
+                now_in_seconds.serialize(cursor);
+            }
+        }
     }
 }
 
@@ -72,7 +152,7 @@ impl TryFrom<u8> for BatchType {
             0 => Ok(BatchType::Logged),
             1 => Ok(BatchType::Unlogged),
             2 => Ok(BatchType::Counter),
-            _ => Err(Error::General(format!("Unknown batch type: {}", value))),
+            _ => Err(Error::Protocol(format!("Unknown batch type: {}", value))),
         }
     }
 }
@@ -90,8 +170,6 @@ impl From<BatchType> for u8 {
 /// The structure that represents a query to be batched.
 #[derive(Debug, Clone)]
 pub struct BatchQuery {
-    /// It indicates if a query was prepared.
-    pub is_prepared: bool,
     /// It contains either id of prepared query of a query itself.
     pub subject: BatchQuerySubj,
     /// It is the optional name of the following <value_i>. It must be present
@@ -104,6 +182,14 @@ pub struct BatchQuery {
     pub values: QueryValues,
 }
 
+impl BatchQuery {
+    /// It indicates if a query was prepared.
+    #[inline]
+    pub fn is_prepared(&self) -> bool {
+        matches!(self.subject, BatchQuerySubj::PreparedId(_))
+    }
+}
+
 /// It contains either an id of prepared query or CQL string.
 #[derive(Debug, Clone)]
 pub enum BatchQuerySubj {
@@ -111,14 +197,20 @@ pub enum BatchQuerySubj {
     QueryString(CStringLong),
 }
 
+impl BatchQuerySubj {
+    /// Returns the `kind` byte of a batched query.
+    #[inline]
+    pub fn kind(&self) -> u8 {
+        match self {
+            BatchQuerySubj::QueryString(_) => 0,
+            BatchQuerySubj::PreparedId(_) => 1,
+        }
+    }
+}
+
 impl Serialize for BatchQuery {
     fn serialize(&self, cursor: &mut Cursor<&mut Vec<u8>>) {
-        // kind
-        if self.is_prepared {
-            1u8.serialize(cursor);
-        } else {
-            0u8.serialize(cursor);
-        }
+        self.subject.kind().serialize(cursor);
 
         match &self.subject {
             BatchQuerySubj::PreparedId(s) => {
@@ -135,20 +227,126 @@ impl Serialize for BatchQuery {
     }
 }
 
+impl private::Sealed for BodyReqBatch {}
+
+impl RequestMessage for BodyReqBatch {
+    const OPCODE: Opcode = Opcode::Batch;
+
+    #[inline]
+    fn serialize_body(&self, version: Version) -> Vec<u8> {
+        self.serialize_with_version_to_vec(version)
+    }
+}
+
 impl Frame {
     /// **Note:** This function should be used internally for building query request frames.
     pub fn new_req_batch(query: BodyReqBatch, flags: Flags, version: Version) -> Frame {
-        let direction = Direction::Request;
-        let opcode = Opcode::Batch;
-
-        Frame::new(
-            version,
-            direction,
-            flags,
-            opcode,
+        let mut frame = Frame::new_request(&query, flags, version);
+
+        frame.compression_disabled = query.compression_disabled;
+        frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::value::Value;
+
+    #[test]
+    fn batch_query_string_serialize() {
+        let query = BatchQuery {
+            subject: BatchQuerySubj::QueryString(CStringLong::new("abc".into())),
+            values: QueryValues::SimpleValues(vec![Value::Some(vec![1])]),
+        };
+
+        assert!(!query.is_prepared());
+        assert_eq!(
             query.serialize_to_vec(),
-            None,
-            vec![],
-        )
+            vec![0, 0, 0, 0, 3, 97, 98, 99, 0, 1, 0, 0, 0, 1, 1]
+        );
+    }
+
+    #[test]
+    fn batch_query_prepared_serialize() {
+        let query = BatchQuery {
+            subject: BatchQuerySubj::PreparedId(PreparedQuery {
+                id: CBytesShort::new(vec![5, 6]),
+                result_metadata_id: None,
+                query: "abc".into(),
+                keyspace: None,
+                pk_indexes: vec![],
+            }),
+            values: QueryValues::SimpleValues(vec![Value::Null]),
+        };
+
+        assert!(query.is_prepared());
+        assert_eq!(
+            query.serialize_to_vec(),
+            vec![1, 0, 2, 5, 6, 0, 1, 255, 255, 255, 255]
+        );
+    }
+
+    #[test]
+    fn batch_default_omits_serial_consistency() {
+        let batch = BodyReqBatch {
+            queries: vec![BatchQuery {
+                subject: BatchQuerySubj::QueryString(CStringLong::new("abc".into())),
+                values: QueryValues::SimpleValues(vec![]),
+            }],
+            query_flags: QueryFlags::WITH_SERIAL_CONSISTENCY,
+            ..Default::default()
+        };
+
+        assert_eq!(batch.consistency, Consistency::One);
+        assert_eq!(
+            batch.serialize_to_vec(),
+            vec![0, 0, 1, 0, 0, 0, 0, 3, 97, 98, 99, 0, 0, 0, 1, 0]
+        );
+    }
+
+    #[test]
+    fn batch_v5_serialize() {
+        let batch = BodyReqBatch {
+            queries: vec![BatchQuery {
+                subject: BatchQuerySubj::QueryString(CStringLong::new("abc".into())),
+                values: QueryValues::SimpleValues(vec![]),
+            }],
+            keyspace: Some("ks".into()),
+            now_in_seconds: Some(7),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            batch.serialize_with_version_to_vec(Version::V5),
+            vec![
+                0, 0, 1, 0, 0, 0, 0, 3, 97, 98, 99, 0, 0, 0, 1, 0, 0, 1, 128, 0, 2, 107, 115, 0, 0,
+                0, 7
+            ]
+        );
+        assert_eq!(
+            batch.serialize_body(Version::V4),
+            vec![0, 0, 1, 0, 0, 0, 0, 3, 97, 98, 99, 0, 0, 0, 1, 0]
+        );
+    }
+
+    #[test]
+    fn batch_from_iter() {
+        let batch: BodyReqBatch = ["a", "b", "c"]
+            .iter()
+            .map(|query| BatchQuery {
+                subject: BatchQuerySubj::QueryString(CStringLong::new(query.to_string())),
+                values: QueryValues::SimpleValues(vec![]),
+            })
+            .collect();
+
+        assert_eq!(batch.queries.len(), 3);
+        assert_eq!(
+            batch.serialize_to_vec(),
+            vec![
+                0, 0, 3, 0, 0, 0, 0, 1, 97, 0, 0, 0, 0, 0, 0, 1, 98, 0, 0, 0, 0, 0, 0, 1, 99, 0, 0,
+                0, 1, 0
+            ]
+        );
     }
 }
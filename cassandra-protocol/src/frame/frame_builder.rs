@@ -0,0 +1,146 @@
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::frame::{next_stream_id, Direction, Flags, Frame, Opcode, StreamId, Version};
+
+/// Builder for frames with arbitrary header fields and a raw body. Useful for protocol testing
+/// and edge cases not covered by typed request builders - no validation of the body against
+/// the opcode is performed.
+#[derive(Debug, Clone)]
+pub struct FrameBuilder {
+    version: Version,
+    direction: Direction,
+    flags: Flags,
+    opcode: Opcode,
+    stream: Option<StreamId>,
+    body: Vec<u8>,
+    tracing_id: Option<Uuid>,
+    warnings: Vec<String>,
+    custom_payload: BTreeMap<String, Vec<u8>>,
+    compression_disabled: bool,
+}
+
+impl FrameBuilder {
+    /// Creates a builder for an empty v4 request with given opcode.
+    pub fn new(opcode: Opcode) -> Self {
+        FrameBuilder {
+            version: Version::V4,
+            direction: Direction::Request,
+            flags: Flags::empty(),
+            opcode,
+            stream: None,
+            body: vec![],
+            tracing_id: None,
+            warnings: vec![],
+            custom_payload: BTreeMap::new(),
+            compression_disabled: false,
+        }
+    }
+
+    pub fn version(mut self, version: Version) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn flags(mut self, flags: Flags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    pub fn opcode(mut self, opcode: Opcode) -> Self {
+        self.opcode = opcode;
+        self
+    }
+
+    // Sets stream id. If not set, next free id will be used.
+    builder_opt_field!(stream, StreamId);
+
+    /// Sets raw (uncompressed) body.
+    pub fn body(mut self, body: Vec<u8>) -> Self {
+        self.body = body;
+        self
+    }
+
+    // Sets tracing id.
+    builder_opt_field!(tracing_id, Uuid);
+
+    pub fn warnings(mut self, warnings: Vec<String>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    pub fn custom_payload(mut self, custom_payload: BTreeMap<String, Vec<u8>>) -> Self {
+        self.custom_payload = custom_payload;
+        self
+    }
+
+    /// Forces sending the frame uncompressed, regardless of connection compression settings.
+    pub fn disable_compression(mut self) -> Self {
+        self.compression_disabled = true;
+        self
+    }
+
+    pub fn build(self) -> Frame {
+        Frame {
+            version: self.version,
+            direction: self.direction,
+            flags: self.flags,
+            opcode: self.opcode,
+            stream: self.stream.unwrap_or_else(next_stream_id),
+            body: self.body,
+            tracing_id: self.tracing_id,
+            warnings: self.warnings,
+            custom_payload: self.custom_payload,
+            compression_disabled: self.compression_disabled,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::Compression;
+    use crate::frame::raw_frame::RawFrame;
+
+    #[test]
+    fn frame_builder_roundtrip() {
+        let frame = FrameBuilder::new(Opcode::Register)
+            .version(Version::V5)
+            .direction(Direction::Request)
+            .flags(Flags::BETA)
+            .stream(42)
+            .body(vec![1, 2, 3])
+            .build();
+
+        let encoded = frame.encode_with(Compression::None).unwrap();
+        let decoded = RawFrame::from_bytes(&encoded)
+            .unwrap()
+            .into_frame(Compression::None)
+            .unwrap();
+
+        assert_eq!(decoded, frame);
+    }
+
+    #[test]
+    fn frame_builder_custom_payload_roundtrip() {
+        let frame = FrameBuilder::new(Opcode::Query)
+            .flags(Flags::CUSTOM_PAYLOAD)
+            .stream(1)
+            .custom_payload(vec![("key".to_string(), vec![1, 2])].into_iter().collect())
+            .body(vec![1, 2, 3])
+            .build();
+
+        let encoded = frame.encode_with(Compression::None).unwrap();
+        let decoded = RawFrame::from_bytes(&encoded)
+            .unwrap()
+            .into_frame(Compression::None)
+            .unwrap();
+
+        assert_eq!(decoded, frame);
+    }
+}
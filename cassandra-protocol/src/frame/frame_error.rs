@@ -1,14 +1,19 @@
 /// This modules contains [Cassandra's errors](<https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec>)
 /// which server could respond to client.
 use derive_more::Display;
+use std::convert::TryFrom;
 use std::io;
 use std::io::Read;
+use std::net::IpAddr;
 use std::result;
+use std::time::Duration;
 
 use crate::consistency::Consistency;
 use crate::error;
 use crate::frame::traits::FromCursor;
-use crate::frame::Frame;
+use crate::frame::{DecodeMode, Frame, Opcode, Version};
+use crate::frame::{Serialize, SerializeWithVersion};
+use crate::types::data_serialization_types::decode_inet;
 use crate::types::*;
 
 /// CDRS specific `Result` which contains a [`Frame`] in case of `Ok` and `CdrsError` if `Err`.
@@ -26,22 +31,170 @@ pub struct CdrsError {
     pub message: CString,
     /// Additional information.
     pub additional_info: AdditionalErrorInfo,
+    /// Hint how long to wait before retrying, taken from the custom payload of the frame which
+    /// carried the error (see `Frame::retry_after`). Not a part of the error body itself.
+    pub retry_after: Option<Duration>,
+}
+
+impl CdrsError {
+    /// Checks if the server rejected compression requested in STARTUP, in which case the
+    /// connection can be re-established without compression.
+    pub fn is_unsupported_compression(&self) -> bool {
+        self.additional_info == AdditionalErrorInfo::Protocol
+            && self.message.as_str().to_lowercase().contains("compression")
+    }
+
+    /// Extracts the greatest protocol version supported by the server from an error returned
+    /// when connecting with an unsupported version. Both the "the lowest supported version is X
+    /// and the greatest is Y" and the "supported versions are (X/vX, Y/vY)" message formats are
+    /// recognized, with beta versions being skipped in the latter. Returns `None` for other
+    /// errors or if the message does not contain supported versions.
+    pub fn max_supported_version(&self) -> Option<u8> {
+        if self.additional_info != AdditionalErrorInfo::Protocol {
+            return None;
+        }
+
+        let message = self.message.as_str().to_lowercase();
+        if !message.contains("unsupported protocol version") {
+            return None;
+        }
+
+        if let Some(index) = message.find("greatest is") {
+            return message[index + "greatest is".len()..]
+                .trim_start()
+                .split(|c: char| !c.is_ascii_digit())
+                .next()
+                .and_then(|version| version.parse().ok());
+        }
+
+        let index = message.find("supported versions are")?;
+        message[index..]
+            .split(['(', ')', ','])
+            .map(str::trim)
+            .filter(|version| !version.contains("beta"))
+            .filter_map(|version| version.split('/').next()?.parse().ok())
+            .max()
+    }
+
+    /// Returns the version to retry connecting with after the server rejected the requested
+    /// protocol version, i.e. the greatest version supported by both the server and this crate.
+    /// Protocol v5 is never returned, since segment framing is not implemented yet.
+    pub fn fallback_version(&self) -> Option<Version> {
+        let max_version = self.max_supported_version()?.min(Version::V4.into());
+        Version::try_from(max_version).ok()
+    }
+
+    /// Returns a stable, low-cardinality label of the error kind, e.g. `"write_timeout"`,
+    /// suitable for metrics.
+    pub fn error_label(&self) -> &'static str {
+        match self.additional_info {
+            AdditionalErrorInfo::Server => "server_error",
+            AdditionalErrorInfo::Protocol => "protocol_error",
+            AdditionalErrorInfo::Authentication => "authentication_error",
+            AdditionalErrorInfo::Unavailable(_) => "unavailable",
+            AdditionalErrorInfo::Overloaded => "overloaded",
+            AdditionalErrorInfo::IsBootstrapping => "is_bootstrapping",
+            AdditionalErrorInfo::Truncate => "truncate_error",
+            AdditionalErrorInfo::WriteTimeout(_) => "write_timeout",
+            AdditionalErrorInfo::ReadTimeout(_) => "read_timeout",
+            AdditionalErrorInfo::ReadFailure(_) => "read_failure",
+            AdditionalErrorInfo::FunctionFailure(_) => "function_failure",
+            AdditionalErrorInfo::WriteFailure(_) => "write_failure",
+            AdditionalErrorInfo::Syntax => "syntax_error",
+            AdditionalErrorInfo::Unauthorized => "unauthorized",
+            AdditionalErrorInfo::Invalid => "invalid",
+            AdditionalErrorInfo::Config => "config_error",
+            AdditionalErrorInfo::AlreadyExists(_) => "already_exists",
+            AdditionalErrorInfo::Unprepared(_) => "unprepared",
+            AdditionalErrorInfo::Unknown(_) => "unknown",
+        }
+    }
 }
 
 impl FromCursor for CdrsError {
     fn from_cursor(cursor: &mut io::Cursor<&[u8]>) -> error::Result<CdrsError> {
+        Self::from_cursor_with_mode(cursor, Version::V4, DecodeMode::default())
+    }
+}
+
+impl CdrsError {
+    /// Decodes the error sent with given protocol version, handling unknown error codes and enum
+    /// values in additional info according to given mode.
+    pub fn from_cursor_with_mode(
+        cursor: &mut io::Cursor<&[u8]>,
+        version: Version,
+        mode: DecodeMode,
+    ) -> error::Result<CdrsError> {
         let error_code = CInt::from_cursor(cursor)?;
         let message = CString::from_cursor(cursor)?;
-        let additional_info = AdditionalErrorInfo::from_cursor_with_code(cursor, error_code)?;
+        let additional_info =
+            AdditionalErrorInfo::from_cursor_with_code_and_mode(cursor, error_code, version, mode)?;
 
         Ok(CdrsError {
             error_code,
             message,
             additional_info,
+            retry_after: None,
         })
     }
 }
 
+impl Serialize for CdrsError {
+    #[inline]
+    fn serialize(&self, cursor: &mut io::Cursor<&mut Vec<u8>>) {
+        self.serialize_with_version(cursor, Version::V4);
+    }
+}
+
+impl SerializeWithVersion for CdrsError {
+    fn serialize_with_version(&self, cursor: &mut io::Cursor<&mut Vec<u8>>, version: Version) {
+        self.error_code.serialize(cursor);
+        self.message.serialize(cursor);
+        self.additional_info.serialize_with_version(cursor, version);
+    }
+}
+
+/// Custom payload key with a hint how long to wait before retrying a request rejected due to
+/// server overload. This is a convention of this crate, not a server feature - no Cassandra
+/// version sends it. It's meant for custom `QueryHandler` implementations or proxies in front of
+/// the cluster, which need to put it in error responses themselves.
+pub const RETRY_AFTER_PAYLOAD_KEY: &str = "retry_after_ms";
+
+impl Frame {
+    /// Extracts a hint how long to wait before retrying from the custom payload of an `ERROR`
+    /// frame. The hint is a big-endian `[int]` or `[long]` number of milliseconds stored under
+    /// `RETRY_AFTER_PAYLOAD_KEY`, which needs to be set by a custom server component. Returns
+    /// `None` if this is not an error frame or there is no valid hint.
+    pub fn retry_after(&self) -> Option<Duration> {
+        if self.opcode != Opcode::Error {
+            return None;
+        }
+
+        let value = self.custom_payload.get(RETRY_AFTER_PAYLOAD_KEY)?;
+        let millis = match value.len() {
+            INT_LEN => try_i32_from_bytes(value).ok()? as i64,
+            LONG_LEN => try_i64_from_bytes(value).ok()?,
+            _ => return None,
+        };
+
+        u64::try_from(millis).ok().map(Duration::from_millis)
+    }
+
+    /// Reads only the error message from an `ERROR` frame, without decoding additional error
+    /// information. Returns `None` if this is not an error frame or the message cannot be read.
+    pub fn error_message(&self) -> Option<String> {
+        if self.opcode != Opcode::Error {
+            return None;
+        }
+
+        let mut cursor = io::Cursor::new(self.body.as_slice());
+        CInt::from_cursor(&mut cursor).ok()?;
+        CString::from_cursor(&mut cursor)
+            .ok()
+            .map(CString::into_plain)
+    }
+}
+
 /// Additional error info in accordance to
 /// [Cassandra protocol v4]
 /// (<https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec>).
@@ -65,37 +218,51 @@ pub enum AdditionalErrorInfo {
     Config,
     AlreadyExists(AlreadyExistsError),
     Unprepared(UnpreparedError),
+    /// Raw additional info of an error code not known to the driver.
+    Unknown(Vec<u8>),
 }
 
 impl AdditionalErrorInfo {
     pub fn from_cursor_with_code(
         cursor: &mut io::Cursor<&[u8]>,
         error_code: CInt,
+    ) -> error::Result<AdditionalErrorInfo> {
+        Self::from_cursor_with_code_and_mode(cursor, error_code, Version::V4, DecodeMode::default())
+    }
+
+    /// Decodes additional info for given error code, sent with given protocol version. Unknown
+    /// codes result in an error in strict mode, or `AdditionalErrorInfo::Unknown` holding the
+    /// remaining bytes in lenient mode.
+    pub fn from_cursor_with_code_and_mode(
+        cursor: &mut io::Cursor<&[u8]>,
+        error_code: CInt,
+        version: Version,
+        mode: DecodeMode,
     ) -> error::Result<AdditionalErrorInfo> {
         match error_code {
             0x0000 => Ok(AdditionalErrorInfo::Server),
             0x000A => Ok(AdditionalErrorInfo::Protocol),
             0x0100 => Ok(AdditionalErrorInfo::Authentication),
             0x1000 => Ok(AdditionalErrorInfo::Unavailable(
-                UnavailableError::from_cursor(cursor)?,
+                UnavailableError::from_cursor_with_mode(cursor, mode)?,
             )),
             0x1001 => Ok(AdditionalErrorInfo::Overloaded),
             0x1002 => Ok(AdditionalErrorInfo::IsBootstrapping),
             0x1003 => Ok(AdditionalErrorInfo::Truncate),
             0x1100 => Ok(AdditionalErrorInfo::WriteTimeout(
-                WriteTimeoutError::from_cursor(cursor)?,
+                WriteTimeoutError::from_cursor_with_mode(cursor, mode)?,
             )),
             0x1200 => Ok(AdditionalErrorInfo::ReadTimeout(
-                ReadTimeoutError::from_cursor(cursor)?,
+                ReadTimeoutError::from_cursor_with_mode(cursor, mode)?,
             )),
             0x1300 => Ok(AdditionalErrorInfo::ReadFailure(
-                ReadFailureError::from_cursor(cursor)?,
+                ReadFailureError::from_cursor_with_mode(cursor, version, mode)?,
             )),
             0x1400 => Ok(AdditionalErrorInfo::FunctionFailure(
                 FunctionFailureError::from_cursor(cursor)?,
             )),
             0x1500 => Ok(AdditionalErrorInfo::WriteFailure(
-                WriteFailureError::from_cursor(cursor)?,
+                WriteFailureError::from_cursor_with_mode(cursor, version, mode)?,
             )),
             0x2000 => Ok(AdditionalErrorInfo::Syntax),
             0x2100 => Ok(AdditionalErrorInfo::Unauthorized),
@@ -107,7 +274,43 @@ impl AdditionalErrorInfo {
             0x2500 => Ok(AdditionalErrorInfo::Unprepared(
                 UnpreparedError::from_cursor(cursor)?,
             )),
-            _ => Err(format!("Unexpected additional error info: {}", error_code).into()),
+            _ if mode == DecodeMode::Lenient => {
+                let mut info = vec![];
+                cursor.read_to_end(&mut info)?;
+                Ok(AdditionalErrorInfo::Unknown(info))
+            }
+            _ => Err(error::Error::Protocol(format!(
+                "Unexpected additional error info: {}",
+                error_code
+            ))),
+        }
+    }
+}
+
+impl Serialize for AdditionalErrorInfo {
+    #[inline]
+    fn serialize(&self, cursor: &mut io::Cursor<&mut Vec<u8>>) {
+        self.serialize_with_version(cursor, Version::V4);
+    }
+}
+
+impl SerializeWithVersion for AdditionalErrorInfo {
+    fn serialize_with_version(&self, cursor: &mut io::Cursor<&mut Vec<u8>>, version: Version) {
+        match self {
+            AdditionalErrorInfo::Unavailable(error) => error.serialize(cursor),
+            AdditionalErrorInfo::WriteTimeout(error) => error.serialize(cursor),
+            AdditionalErrorInfo::ReadTimeout(error) => error.serialize(cursor),
+            AdditionalErrorInfo::ReadFailure(error) => {
+                error.serialize_with_version(cursor, version)
+            }
+            AdditionalErrorInfo::FunctionFailure(error) => error.serialize(cursor),
+            AdditionalErrorInfo::WriteFailure(error) => {
+                error.serialize_with_version(cursor, version)
+            }
+            AdditionalErrorInfo::AlreadyExists(error) => error.serialize(cursor),
+            AdditionalErrorInfo::Unprepared(error) => error.serialize(cursor),
+            AdditionalErrorInfo::Unknown(info) => info.serialize(cursor),
+            _ => {}
         }
     }
 }
@@ -117,7 +320,9 @@ impl AdditionalErrorInfo {
 /// (<https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec>)
 #[derive(Debug, PartialEq, Ord, PartialOrd, Eq, Copy, Clone, Hash)]
 pub struct UnavailableError {
-    /// Consistency level of query.
+    /// Consistency level of query. For dc-local levels (see
+    /// [`Consistency::is_dc_local`]), `required` and `alive` refer to the local datacenter only,
+    /// while for `EachQuorum` the shortfall is in at least one of the datacenters.
     pub cl: Consistency,
     /// Number of nodes that should be available to respect `cl`.
     pub required: CInt,
@@ -127,7 +332,17 @@ pub struct UnavailableError {
 
 impl FromCursor for UnavailableError {
     fn from_cursor(cursor: &mut io::Cursor<&[u8]>) -> error::Result<UnavailableError> {
-        let cl = Consistency::from_cursor(cursor)?;
+        Self::from_cursor_with_mode(cursor, DecodeMode::default())
+    }
+}
+
+impl UnavailableError {
+    /// Decodes the error, handling unknown enum values according to given mode.
+    pub fn from_cursor_with_mode(
+        cursor: &mut io::Cursor<&[u8]>,
+        mode: DecodeMode,
+    ) -> error::Result<UnavailableError> {
+        let cl = Consistency::from_cursor_with_mode(cursor, mode)?;
         let required = CInt::from_cursor(cursor)?;
         let alive = CInt::from_cursor(cursor)?;
 
@@ -139,8 +354,16 @@ impl FromCursor for UnavailableError {
     }
 }
 
+impl Serialize for UnavailableError {
+    fn serialize(&self, cursor: &mut io::Cursor<&mut Vec<u8>>) {
+        self.cl.serialize(cursor);
+        self.required.serialize(cursor);
+        self.alive.serialize(cursor);
+    }
+}
+
 /// Timeout exception during a write request.
-#[derive(Debug, PartialEq, Copy, Clone, Ord, PartialOrd, Eq, Hash)]
+#[derive(Debug, PartialEq, Clone, Ord, PartialOrd, Eq, Hash)]
 pub struct WriteTimeoutError {
     /// Consistency level of query.
     pub cl: Consistency,
@@ -152,12 +375,30 @@ pub struct WriteTimeoutError {
     pub write_type: WriteType,
 }
 
+impl WriteTimeoutError {
+    /// Returns the ratio of acknowledgements received to required, or `None` if none are
+    /// required.
+    pub fn progress_ratio(&self) -> Option<f32> {
+        progress_ratio(self.received, self.block_for)
+    }
+}
+
 impl FromCursor for WriteTimeoutError {
     fn from_cursor(cursor: &mut io::Cursor<&[u8]>) -> error::Result<WriteTimeoutError> {
-        let cl = Consistency::from_cursor(cursor)?;
+        Self::from_cursor_with_mode(cursor, DecodeMode::default())
+    }
+}
+
+impl WriteTimeoutError {
+    /// Decodes the error, handling unknown enum values according to given mode.
+    pub fn from_cursor_with_mode(
+        cursor: &mut io::Cursor<&[u8]>,
+        mode: DecodeMode,
+    ) -> error::Result<WriteTimeoutError> {
+        let cl = Consistency::from_cursor_with_mode(cursor, mode)?;
         let received = CInt::from_cursor(cursor)?;
         let block_for = CInt::from_cursor(cursor)?;
-        let write_type = WriteType::from_cursor(cursor)?;
+        let write_type = WriteType::from_cursor_with_mode(cursor, mode)?;
 
         Ok(WriteTimeoutError {
             cl,
@@ -168,6 +409,15 @@ impl FromCursor for WriteTimeoutError {
     }
 }
 
+impl Serialize for WriteTimeoutError {
+    fn serialize(&self, cursor: &mut io::Cursor<&mut Vec<u8>>) {
+        self.cl.serialize(cursor);
+        self.received.serialize(cursor);
+        self.block_for.serialize(cursor);
+        self.write_type.serialize(cursor);
+    }
+}
+
 /// Timeout exception during a read request.
 #[derive(Debug, PartialEq, Ord, PartialOrd, Eq, Copy, Clone, Hash)]
 pub struct ReadTimeoutError {
@@ -186,11 +436,35 @@ impl ReadTimeoutError {
     pub fn replica_has_responded(&self) -> bool {
         self.data_present != 0
     }
+
+    /// Returns the ratio of responses received to required, or `None` if none are required.
+    pub fn progress_ratio(&self) -> Option<f32> {
+        progress_ratio(self.received, self.block_for)
+    }
+}
+
+// block_for should never be 0, but a malformed response must not result in NaN
+fn progress_ratio(received: CInt, block_for: CInt) -> Option<f32> {
+    if block_for == 0 {
+        None
+    } else {
+        Some(received as f32 / block_for as f32)
+    }
 }
 
 impl FromCursor for ReadTimeoutError {
     fn from_cursor(cursor: &mut io::Cursor<&[u8]>) -> error::Result<ReadTimeoutError> {
-        let cl = Consistency::from_cursor(cursor)?;
+        Self::from_cursor_with_mode(cursor, DecodeMode::default())
+    }
+}
+
+impl ReadTimeoutError {
+    /// Decodes the error, handling unknown enum values according to given mode.
+    pub fn from_cursor_with_mode(
+        cursor: &mut io::Cursor<&[u8]>,
+        mode: DecodeMode,
+    ) -> error::Result<ReadTimeoutError> {
+        let cl = Consistency::from_cursor_with_mode(cursor, mode)?;
         let received = CInt::from_cursor(cursor)?;
         let block_for = CInt::from_cursor(cursor)?;
 
@@ -208,8 +482,17 @@ impl FromCursor for ReadTimeoutError {
     }
 }
 
+impl Serialize for ReadTimeoutError {
+    fn serialize(&self, cursor: &mut io::Cursor<&mut Vec<u8>>) {
+        self.cl.serialize(cursor);
+        self.received.serialize(cursor);
+        self.block_for.serialize(cursor);
+        self.data_present.serialize(cursor);
+    }
+}
+
 /// A non-timeout exception during a read request.
-#[derive(Debug, PartialEq, Ord, PartialOrd, Eq, Copy, Clone, Hash)]
+#[derive(Debug, PartialEq, Ord, PartialOrd, Eq, Clone, Hash)]
 pub struct ReadFailureError {
     /// Consistency level of query.
     pub cl: Consistency,
@@ -219,6 +502,8 @@ pub struct ReadFailureError {
     pub block_for: CInt,
     /// Represents the number of nodes that experience a failure while executing the request.
     pub num_failures: CInt,
+    /// Reasons of the failures per replica. Sent since protocol v5 - empty before.
+    pub reasons: FailureReasonMap,
     data_present: u8,
 }
 
@@ -232,10 +517,22 @@ impl ReadFailureError {
 
 impl FromCursor for ReadFailureError {
     fn from_cursor(cursor: &mut io::Cursor<&[u8]>) -> error::Result<ReadFailureError> {
-        let cl = Consistency::from_cursor(cursor)?;
+        Self::from_cursor_with_mode(cursor, Version::V4, DecodeMode::default())
+    }
+}
+
+impl ReadFailureError {
+    /// Decodes the error sent with given protocol version, handling unknown enum values
+    /// according to given mode.
+    pub fn from_cursor_with_mode(
+        cursor: &mut io::Cursor<&[u8]>,
+        version: Version,
+        mode: DecodeMode,
+    ) -> error::Result<ReadFailureError> {
+        let cl = Consistency::from_cursor_with_mode(cursor, mode)?;
         let received = CInt::from_cursor(cursor)?;
         let block_for = CInt::from_cursor(cursor)?;
-        let num_failures = CInt::from_cursor(cursor)?;
+        let (num_failures, reasons) = decode_failures(cursor, version)?;
 
         let mut buff = [0];
         cursor.read_exact(&mut buff)?;
@@ -247,11 +544,55 @@ impl FromCursor for ReadFailureError {
             received,
             block_for,
             num_failures,
+            reasons,
             data_present,
         })
     }
 }
 
+// protocol v5 replaced the number of failures with a reason map
+fn decode_failures(
+    cursor: &mut io::Cursor<&[u8]>,
+    version: Version,
+) -> error::Result<(CInt, FailureReasonMap)> {
+    if version >= Version::V5 {
+        let reasons = FailureReasonMap::from_cursor(cursor)?;
+        Ok((reasons.reasons.len() as CInt, reasons))
+    } else {
+        Ok((CInt::from_cursor(cursor)?, FailureReasonMap::default()))
+    }
+}
+
+fn serialize_failures(
+    num_failures: CInt,
+    reasons: &FailureReasonMap,
+    cursor: &mut io::Cursor<&mut Vec<u8>>,
+    version: Version,
+) {
+    if version >= Version::V5 {
+        reasons.serialize(cursor);
+    } else {
+        num_failures.serialize(cursor);
+    }
+}
+
+impl Serialize for ReadFailureError {
+    #[inline]
+    fn serialize(&self, cursor: &mut io::Cursor<&mut Vec<u8>>) {
+        self.serialize_with_version(cursor, Version::V4);
+    }
+}
+
+impl SerializeWithVersion for ReadFailureError {
+    fn serialize_with_version(&self, cursor: &mut io::Cursor<&mut Vec<u8>>, version: Version) {
+        self.cl.serialize(cursor);
+        self.received.serialize(cursor);
+        self.block_for.serialize(cursor);
+        serialize_failures(self.num_failures, &self.reasons, cursor, version);
+        self.data_present.serialize(cursor);
+    }
+}
+
 /// A (user defined) function failed during execution.
 #[derive(Debug, PartialEq, Ord, PartialOrd, Eq, Hash, Clone)]
 pub struct FunctionFailureError {
@@ -263,6 +604,19 @@ pub struct FunctionFailureError {
     pub arg_types: CStringList,
 }
 
+impl FunctionFailureError {
+    /// Returns a readable signature of the failed function, e.g. `ks.func(int, text)`.
+    pub fn signature(&self) -> String {
+        let arg_types: Vec<&str> = self.arg_types.list.iter().map(CString::as_str).collect();
+        format!(
+            "{}.{}({})",
+            self.keyspace.as_str(),
+            self.function.as_str(),
+            arg_types.join(", ")
+        )
+    }
+}
+
 impl FromCursor for FunctionFailureError {
     fn from_cursor(cursor: &mut io::Cursor<&[u8]>) -> error::Result<FunctionFailureError> {
         let keyspace = CString::from_cursor(cursor)?;
@@ -277,9 +631,17 @@ impl FromCursor for FunctionFailureError {
     }
 }
 
+impl Serialize for FunctionFailureError {
+    fn serialize(&self, cursor: &mut io::Cursor<&mut Vec<u8>>) {
+        self.keyspace.serialize(cursor);
+        self.function.serialize(cursor);
+        self.arg_types.serialize(cursor);
+    }
+}
+
 /// A non-timeout exception during a write request.
 /// [Read more...](https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec#L1106)
-#[derive(Debug, PartialEq, Ord, PartialOrd, Eq, Hash, Copy, Clone)]
+#[derive(Debug, PartialEq, Ord, PartialOrd, Eq, Hash, Clone)]
 pub struct WriteFailureError {
     /// Consistency of the query having triggered the exception.
     pub cl: Consistency,
@@ -289,31 +651,149 @@ pub struct WriteFailureError {
     pub block_for: CInt,
     /// Represents the number of nodes that experience a failure while executing the request.
     pub num_failures: CInt,
+    /// Reasons of the failures per replica. Sent since protocol v5 - empty before.
+    pub reasons: FailureReasonMap,
     /// describes the type of the write that failed.
     pub write_type: WriteType,
 }
 
 impl FromCursor for WriteFailureError {
     fn from_cursor(cursor: &mut io::Cursor<&[u8]>) -> error::Result<WriteFailureError> {
-        let cl = Consistency::from_cursor(cursor)?;
+        Self::from_cursor_with_mode(cursor, Version::V4, DecodeMode::default())
+    }
+}
+
+impl WriteFailureError {
+    /// Decodes the error sent with given protocol version, handling unknown enum values
+    /// according to given mode.
+    pub fn from_cursor_with_mode(
+        cursor: &mut io::Cursor<&[u8]>,
+        version: Version,
+        mode: DecodeMode,
+    ) -> error::Result<WriteFailureError> {
+        let cl = Consistency::from_cursor_with_mode(cursor, mode)?;
         let received = CInt::from_cursor(cursor)?;
         let block_for = CInt::from_cursor(cursor)?;
-        let num_failures = CInt::from_cursor(cursor)?;
-        let write_type = WriteType::from_cursor(cursor)?;
+        let (num_failures, reasons) = decode_failures(cursor, version)?;
+        let write_type = WriteType::from_cursor_with_mode(cursor, mode)?;
 
         Ok(WriteFailureError {
             cl,
             received,
             block_for,
             num_failures,
+            reasons,
             write_type,
         })
     }
 }
 
+impl Serialize for WriteFailureError {
+    #[inline]
+    fn serialize(&self, cursor: &mut io::Cursor<&mut Vec<u8>>) {
+        self.serialize_with_version(cursor, Version::V4);
+    }
+}
+
+impl SerializeWithVersion for WriteFailureError {
+    fn serialize_with_version(&self, cursor: &mut io::Cursor<&mut Vec<u8>>, version: Version) {
+        self.cl.serialize(cursor);
+        self.received.serialize(cursor);
+        self.block_for.serialize(cursor);
+        serialize_failures(self.num_failures, &self.reasons, cursor, version);
+        self.write_type.serialize(cursor);
+    }
+}
+
+/// Reason of a replica failure, as found in a failure reason map.
+#[derive(Debug, PartialEq, Ord, PartialOrd, Eq, Hash, Copy, Clone)]
+pub enum FailureReason {
+    /// The replica read more tombstones than allowed by its configuration.
+    ReadTooManyTombstones,
+    Timeout,
+    /// The replica has a different schema than the coordinator.
+    IncompatibleSchema,
+    /// Unspecified reason (`0x0000`) or a code not known to the driver.
+    Unknown(u16),
+}
+
+impl From<u16> for FailureReason {
+    fn from(code: u16) -> Self {
+        match code {
+            0x0001 => FailureReason::ReadTooManyTombstones,
+            0x0002 => FailureReason::Timeout,
+            0x0003 => FailureReason::IncompatibleSchema,
+            code => FailureReason::Unknown(code),
+        }
+    }
+}
+
+impl From<FailureReason> for u16 {
+    fn from(reason: FailureReason) -> Self {
+        match reason {
+            FailureReason::ReadTooManyTombstones => 0x0001,
+            FailureReason::Timeout => 0x0002,
+            FailureReason::IncompatibleSchema => 0x0003,
+            FailureReason::Unknown(code) => code,
+        }
+    }
+}
+
+/// Map of replica addresses to failure reasons, sent with read and write failures since protocol
+/// v5. Endpoints are encoded as `[inetaddr]`, i.e. without a port.
+#[derive(Debug, PartialEq, Ord, PartialOrd, Eq, Hash, Clone, Default)]
+pub struct FailureReasonMap {
+    pub reasons: Vec<(IpAddr, FailureReason)>,
+}
+
+impl FromCursor for FailureReasonMap {
+    fn from_cursor(cursor: &mut io::Cursor<&[u8]>) -> error::Result<FailureReasonMap> {
+        let count = CInt::from_cursor(cursor)?;
+        if count < 0 {
+            return Err(error::Error::Protocol(format!(
+                "Invalid failure reason map size: {}",
+                count
+            )));
+        }
+
+        let mut reasons = Vec::with_capacity((count as usize).min(cursor.get_ref().len()));
+        for _ in 0..count {
+            let mut buff = [0];
+            cursor.read_exact(&mut buff)?;
+            let addr = decode_inet(&cursor_next_value(cursor, buff[0] as usize)?)?;
+
+            let code = CIntShort::from_cursor(cursor)? as u16;
+            reasons.push((addr, FailureReason::from(code)));
+        }
+
+        Ok(FailureReasonMap { reasons })
+    }
+}
+
+impl Serialize for FailureReasonMap {
+    fn serialize(&self, cursor: &mut io::Cursor<&mut Vec<u8>>) {
+        (self.reasons.len() as CInt).serialize(cursor);
+
+        for (addr, reason) in &self.reasons {
+            match addr {
+                IpAddr::V4(v4) => {
+                    [4].serialize(cursor);
+                    v4.octets().serialize(cursor);
+                }
+                IpAddr::V6(v6) => {
+                    [16].serialize(cursor);
+                    v6.octets().serialize(cursor);
+                }
+            }
+
+            (u16::from(*reason) as CIntShort).serialize(cursor);
+        }
+    }
+}
+
 /// Describes the type of the write that failed.
 /// [Read more...](https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec#L1118)
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Copy, Clone, Display)]
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Display)]
 pub enum WriteType {
     /// The write was a non-batched non-counter write
     Simple,
@@ -328,10 +808,23 @@ pub enum WriteType {
     /// The failure occurred during the write to the batch log when a (logged) batch
     /// write was requested.
     BatchLog,
+    /// Write type not known to the driver.
+    Unknown(String),
 }
 
 impl FromCursor for WriteType {
     fn from_cursor(cursor: &mut io::Cursor<&[u8]>) -> error::Result<WriteType> {
+        Self::from_cursor_with_mode(cursor, DecodeMode::default())
+    }
+}
+
+impl WriteType {
+    /// Decodes a write type. Unknown values result in an error in strict mode, or
+    /// `WriteType::Unknown` in lenient mode.
+    pub fn from_cursor_with_mode(
+        cursor: &mut io::Cursor<&[u8]>,
+        mode: DecodeMode,
+    ) -> error::Result<WriteType> {
         CString::from_cursor(cursor).and_then(|wt| {
             let wt = wt.as_str();
             match wt {
@@ -340,12 +833,31 @@ impl FromCursor for WriteType {
                 "UNLOGGED_BATCH" => Ok(WriteType::UnloggedBatch),
                 "COUNTER" => Ok(WriteType::Counter),
                 "BATCH_LOG" => Ok(WriteType::BatchLog),
-                _ => Err(format!("Unexpected write type: {}", wt).into()),
+                _ if mode == DecodeMode::Lenient => Ok(WriteType::Unknown(wt.into())),
+                _ => Err(error::Error::Protocol(format!(
+                    "Unexpected write type: {}",
+                    wt
+                ))),
             }
         })
     }
 }
 
+impl Serialize for WriteType {
+    fn serialize(&self, cursor: &mut io::Cursor<&mut Vec<u8>>) {
+        let value = match self {
+            WriteType::Simple => "SIMPLE",
+            WriteType::Batch => "BATCH",
+            WriteType::UnloggedBatch => "UNLOGGED_BATCH",
+            WriteType::Counter => "COUNTER",
+            WriteType::BatchLog => "BATCH_LOG",
+            WriteType::Unknown(value) => value.as_str(),
+        };
+
+        serialize_str(cursor, value);
+    }
+}
+
 /// The query attempted to create a keyspace or a table that was already existing.
 /// [Read more...](https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec#L1140)
 #[derive(Debug, PartialEq, Ord, PartialOrd, Eq, Hash, Clone)]
@@ -366,6 +878,13 @@ impl FromCursor for AlreadyExistsError {
     }
 }
 
+impl Serialize for AlreadyExistsError {
+    fn serialize(&self, cursor: &mut io::Cursor<&mut Vec<u8>>) {
+        self.ks.serialize(cursor);
+        self.table.serialize(cursor);
+    }
+}
+
 /// Can be thrown while a prepared statement tries to be
 /// executed if the provided prepared statement ID is not known by
 /// this host. [Read more...]
@@ -382,3 +901,353 @@ impl FromCursor for UnpreparedError {
         Ok(UnpreparedError { id })
     }
 }
+
+impl Serialize for UnpreparedError {
+    fn serialize(&self, cursor: &mut io::Cursor<&mut Vec<u8>>) {
+        self.id.serialize(cursor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::Compression;
+    use crate::frame::frame_response::ResponseBody;
+    use crate::frame::raw_frame::RawFrame;
+    use crate::frame::{Direction, Flags, Frame, Opcode, Version};
+
+    #[test]
+    fn cdrs_error_unsupported_compression() {
+        let error = CdrsError {
+            error_code: 0x000A,
+            message: CString::new("Unknown compression algorithm: lz4".into()),
+            additional_info: AdditionalErrorInfo::Protocol,
+            retry_after: None,
+        };
+        assert!(error.is_unsupported_compression());
+
+        let error = CdrsError {
+            error_code: 0x000A,
+            message: CString::new("Invalid or unsupported protocol version".into()),
+            additional_info: AdditionalErrorInfo::Protocol,
+            retry_after: None,
+        };
+        assert!(!error.is_unsupported_compression());
+    }
+
+    #[test]
+    fn cdrs_error_label() {
+        let error = |additional_info| CdrsError {
+            error_code: 0,
+            message: CString::new("".into()),
+            additional_info,
+            retry_after: None,
+        };
+
+        assert_eq!(
+            error(AdditionalErrorInfo::Unavailable(UnavailableError {
+                cl: Consistency::Quorum,
+                required: 2,
+                alive: 1,
+            }))
+            .error_label(),
+            "unavailable"
+        );
+        assert_eq!(
+            error(AdditionalErrorInfo::Overloaded).error_label(),
+            "overloaded"
+        );
+        assert_eq!(
+            error(AdditionalErrorInfo::Syntax).error_label(),
+            "syntax_error"
+        );
+        assert_eq!(
+            error(AdditionalErrorInfo::Unknown(vec![1])).error_label(),
+            "unknown"
+        );
+    }
+
+    #[test]
+    fn cdrs_error_fallback_version() {
+        let error = CdrsError {
+            error_code: 0x000A,
+            message: CString::new(
+                "Invalid or unsupported protocol version (5); the lowest supported version is 3 and the greatest is 4".into(),
+            ),
+            additional_info: AdditionalErrorInfo::Protocol, retry_after: None, };
+        assert_eq!(error.max_supported_version(), Some(4));
+        assert_eq!(error.fallback_version(), Some(Version::V4));
+
+        let error = CdrsError {
+            error_code: 0x000A,
+            message: CString::new(
+                "Invalid or unsupported protocol version (66); supported versions are (3/v3, 4/v4, 5/v5, 6/v6-beta)".into(),
+            ),
+            additional_info: AdditionalErrorInfo::Protocol, retry_after: None, };
+        assert_eq!(error.max_supported_version(), Some(5));
+        assert_eq!(error.fallback_version(), Some(Version::V4));
+
+        let error = CdrsError {
+            error_code: 0x000A,
+            message: CString::new("Unknown compression algorithm: lz4".into()),
+            additional_info: AdditionalErrorInfo::Protocol,
+            retry_after: None,
+        };
+        assert_eq!(error.fallback_version(), None);
+    }
+
+    #[test]
+    fn frame_error_message_unknown_code() {
+        let frame = Frame {
+            version: Version::V4,
+            direction: Direction::Response,
+            flags: Flags::empty(),
+            opcode: Opcode::Error,
+            stream: 0,
+            body: vec![0, 0, 0x42, 0x42, 0, 3, 102, 111, 111],
+            tracing_id: None,
+            warnings: vec![],
+            custom_payload: Default::default(),
+            compression_disabled: false,
+        };
+
+        assert!(frame.response_body_with_mode(DecodeMode::Strict).is_err());
+        assert_eq!(frame.error_message(), Some("foo".into()));
+
+        match frame.response_body().unwrap() {
+            ResponseBody::Error(error) => {
+                assert_eq!(error.error_code, 0x4242);
+                assert_eq!(error.additional_info, AdditionalErrorInfo::Unknown(vec![]));
+            }
+            body => panic!("Unexpected body: {:?}", body),
+        }
+    }
+
+    #[test]
+    fn write_type_unknown() {
+        let bytes = [0, 3, b'C', b'D', b'C'];
+
+        let mut cursor: io::Cursor<&[u8]> = io::Cursor::new(&bytes);
+        assert!(WriteType::from_cursor_with_mode(&mut cursor, DecodeMode::Strict).is_err());
+
+        let mut cursor: io::Cursor<&[u8]> = io::Cursor::new(&bytes);
+        let write_type =
+            WriteType::from_cursor_with_mode(&mut cursor, DecodeMode::Lenient).unwrap();
+        assert_eq!(write_type, WriteType::Unknown("CDC".into()));
+        assert_eq!(write_type.serialize_to_vec(), bytes);
+
+        let mut cursor: io::Cursor<&[u8]> = io::Cursor::new(&bytes);
+        assert_eq!(
+            WriteType::from_cursor(&mut cursor).unwrap(),
+            WriteType::Unknown("CDC".into())
+        );
+    }
+
+    #[test]
+    fn write_timeout_unknown_write_type() {
+        let mut bytes = vec![0, 1, 0, 0, 0, 0, 0, 0, 0, 1];
+        bytes.extend_from_slice(&[0, 3, b'C', b'A', b'S']);
+
+        let mut cursor: io::Cursor<&[u8]> = io::Cursor::new(&bytes);
+        assert!(WriteTimeoutError::from_cursor_with_mode(&mut cursor, DecodeMode::Strict).is_err());
+
+        let mut cursor: io::Cursor<&[u8]> = io::Cursor::new(&bytes);
+        let error = WriteTimeoutError::from_cursor(&mut cursor).unwrap();
+        assert_eq!(error.write_type, WriteType::Unknown("CAS".into()));
+    }
+
+    #[test]
+    fn frame_retry_after() {
+        let key = RETRY_AFTER_PAYLOAD_KEY.as_bytes();
+
+        let mut body = vec![0, 1, 0, key.len() as u8];
+        body.extend_from_slice(key);
+        body.extend_from_slice(&[0, 0, 0, 8]);
+        body.extend_from_slice(&100i64.to_be_bytes());
+        body.extend_from_slice(&[0, 0, 0x10, 0x01, 0, 3, 102, 111, 111]);
+
+        let mut bytes = vec![0x84, 0x04, 0, 1, 0x00];
+        bytes.extend_from_slice(&(body.len() as i32).to_be_bytes());
+        bytes.extend_from_slice(&body);
+
+        let frame = RawFrame::from_bytes(&bytes)
+            .unwrap()
+            .into_frame(Compression::None)
+            .unwrap();
+
+        assert_eq!(frame.retry_after(), Some(Duration::from_millis(100)));
+        assert_eq!(frame.error_message(), Some("foo".into()));
+        assert!(matches!(
+            frame.response_body().unwrap(),
+            ResponseBody::Error(CdrsError {
+                additional_info: AdditionalErrorInfo::Overloaded,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn frame_error_message_not_error() {
+        let frame = Frame {
+            version: Version::V4,
+            direction: Direction::Response,
+            flags: Flags::empty(),
+            opcode: Opcode::Ready,
+            stream: 0,
+            body: vec![],
+            tracing_id: None,
+            warnings: vec![],
+            custom_payload: Default::default(),
+            compression_disabled: false,
+        };
+
+        assert_eq!(frame.error_message(), None);
+    }
+
+    #[test]
+    fn write_type_errors() {
+        let mut cursor = io::Cursor::new(&[0, 6, 83, 73][..]);
+        assert!(matches!(
+            WriteType::from_cursor(&mut cursor),
+            Err(error::Error::Io(_))
+        ));
+
+        let mut cursor = io::Cursor::new(&[0, 3, 102, 111, 111][..]);
+        assert!(matches!(
+            WriteType::from_cursor_with_mode(&mut cursor, DecodeMode::Strict),
+            Err(error::Error::Protocol(_))
+        ));
+    }
+
+    #[test]
+    fn function_failure_signature() {
+        let error = FunctionFailureError {
+            keyspace: CString::new("ks".into()),
+            function: CString::new("func".into()),
+            arg_types: CStringList::new(vec![
+                CString::new("int".into()),
+                CString::new("text".into()),
+            ]),
+        };
+
+        assert_eq!(error.signature(), "ks.func(int, text)");
+    }
+
+    #[test]
+    fn failure_reason_map() {
+        let mut bytes = vec![0, 0, 0, 2, 4, 127, 0, 0, 1, 0, 1, 16];
+        bytes.extend_from_slice(&[0; 15]);
+        bytes.extend_from_slice(&[1, 0x12, 0x34]);
+
+        let mut cursor = io::Cursor::new(&bytes[..]);
+        let map = FailureReasonMap::from_cursor(&mut cursor).unwrap();
+
+        assert_eq!(
+            map.reasons,
+            vec![
+                (
+                    "127.0.0.1".parse().unwrap(),
+                    FailureReason::ReadTooManyTombstones
+                ),
+                ("::1".parse().unwrap(), FailureReason::Unknown(0x1234)),
+            ]
+        );
+        assert_eq!(cursor.position() as usize, bytes.len());
+        assert_eq!(map.serialize_to_vec(), bytes);
+
+        for code in [0, 1, 2, 3, 0x1234].iter() {
+            assert_eq!(u16::from(FailureReason::from(*code)), *code);
+        }
+    }
+
+    #[test]
+    fn read_failure_reasons_by_version() {
+        // quorum, received 1, block_for 2
+        let prefix = [0, 4, 0, 0, 0, 1, 0, 0, 0, 2];
+
+        let mut v4 = prefix.to_vec();
+        v4.extend_from_slice(&[0, 0, 0, 1, 1]);
+        let error = ReadFailureError::from_cursor_with_mode(
+            &mut io::Cursor::new(&v4[..]),
+            Version::V4,
+            DecodeMode::Strict,
+        )
+        .unwrap();
+        assert_eq!(error.num_failures, 1);
+        assert!(error.reasons.reasons.is_empty());
+        assert!(error.replica_has_responded());
+
+        let mut v5 = prefix.to_vec();
+        v5.extend_from_slice(&[0, 0, 0, 1, 4, 10, 0, 0, 2, 0, 1, 1]);
+        let error = ReadFailureError::from_cursor_with_mode(
+            &mut io::Cursor::new(&v5[..]),
+            Version::V5,
+            DecodeMode::Strict,
+        )
+        .unwrap();
+        assert_eq!(error.num_failures, 1);
+        assert_eq!(
+            error.reasons.reasons,
+            vec![(
+                "10.0.0.2".parse().unwrap(),
+                FailureReason::ReadTooManyTombstones
+            )]
+        );
+        assert!(error.replica_has_responded());
+        assert_eq!(error.serialize_with_version_to_vec(Version::V5), v5);
+    }
+
+    #[test]
+    fn write_failure_reasons_in_error() {
+        let mut bytes = vec![0, 0, 0x15, 0, 0, 3, 102, 111, 111];
+        bytes.extend_from_slice(&[0, 4, 0, 0, 0, 1, 0, 0, 0, 2]);
+        bytes.extend_from_slice(&[0, 0, 0, 1, 4, 10, 0, 0, 2, 0, 2]);
+        bytes.extend_from_slice(&[0, 6, b'S', b'I', b'M', b'P', b'L', b'E']);
+
+        let error = CdrsError::from_cursor_with_mode(
+            &mut io::Cursor::new(&bytes[..]),
+            Version::V5,
+            DecodeMode::Strict,
+        )
+        .unwrap();
+
+        match &error.additional_info {
+            AdditionalErrorInfo::WriteFailure(failure) => {
+                assert_eq!(failure.num_failures, 1);
+                assert_eq!(
+                    failure.reasons.reasons,
+                    vec![("10.0.0.2".parse().unwrap(), FailureReason::Timeout)]
+                );
+                assert_eq!(failure.write_type, WriteType::Simple);
+            }
+            info => panic!("unexpected additional info: {:?}", info),
+        }
+
+        assert_eq!(error.serialize_with_version_to_vec(Version::V5), bytes);
+    }
+
+    #[test]
+    fn timeout_progress_ratio() {
+        let error = WriteTimeoutError {
+            cl: Consistency::Quorum,
+            received: 1,
+            block_for: 2,
+            write_type: WriteType::Simple,
+        };
+        assert_eq!(error.progress_ratio(), Some(0.5));
+
+        let error = WriteTimeoutError {
+            block_for: 0,
+            ..error
+        };
+        assert_eq!(error.progress_ratio(), None);
+
+        let error = ReadTimeoutError {
+            cl: Consistency::Quorum,
+            received: 0,
+            block_for: 0,
+            data_present: 0,
+        };
+        assert_eq!(error.progress_ratio(), None);
+    }
+}
@@ -1,6 +1,7 @@
 use derive_more::Constructor;
 use std::io::Cursor;
 
+use crate::frame::frame_request::{private, RequestMessage};
 use crate::frame::*;
 use crate::query::QueryParams;
 use crate::types::*;
@@ -9,13 +10,63 @@ use crate::types::*;
 #[derive(Debug, Constructor)]
 pub struct BodyReqExecute<'a> {
     id: &'a CBytesShort,
+    result_metadata_id: Option<&'a CBytesShort>,
     query_parameters: &'a QueryParams,
 }
 
+impl<'a> BodyReqExecute<'a> {
+    /// Returns the id of executed prepared statement.
+    #[inline]
+    pub fn id(&self) -> &'a CBytesShort {
+        self.id
+    }
+
+    /// Returns the id of the result metadata known to the client, sent with protocol v5 and
+    /// above.
+    #[inline]
+    pub fn result_metadata_id(&self) -> Option<&'a CBytesShort> {
+        self.result_metadata_id
+    }
+
+    /// Returns the parameters of the execution.
+    #[inline]
+    pub fn query_parameters(&self) -> &'a QueryParams {
+        self.query_parameters
+    }
+}
+
 impl<'a> Serialize for BodyReqExecute<'a> {
+    #[inline]
     fn serialize(&self, cursor: &mut Cursor<&mut Vec<u8>>) {
+        self.serialize_with_version(cursor, Version::V4);
+    }
+}
+
+impl<'a> SerializeWithVersion for BodyReqExecute<'a> {
+    fn serialize_with_version(&self, cursor: &mut Cursor<&mut Vec<u8>>, version: Version) {
         self.id.serialize(cursor);
-        self.query_parameters.serialize(cursor);
+
+        if version >= Version::V5 {
+            // an empty id never matches, so the server responds with full result metadata
+            match self.result_metadata_id {
+                Some(result_metadata_id) => result_metadata_id.serialize(cursor),
+                None => CBytesShort::new(vec![]).serialize(cursor),
+            }
+        }
+
+        self.query_parameters
+            .serialize_with_version(cursor, version);
+    }
+}
+
+impl<'a> private::Sealed for BodyReqExecute<'a> {}
+
+impl<'a> RequestMessage for BodyReqExecute<'a> {
+    const OPCODE: Opcode = Opcode::Execute;
+
+    #[inline]
+    fn serialize_body(&self, version: Version) -> Vec<u8> {
+        self.serialize_with_version_to_vec(version)
     }
 }
 
@@ -26,19 +77,59 @@ impl Frame {
         flags: Flags,
         version: Version,
     ) -> Frame {
-        let direction = Direction::Request;
-        let opcode = Opcode::Execute;
-
-        let body = BodyReqExecute::new(id, query_parameters);
-
-        Frame::new(
-            version,
-            direction,
-            flags,
-            opcode,
-            body.serialize_to_vec(),
-            None,
-            vec![],
-        )
+        Frame::new_req_execute_with_result_metadata_id(id, None, query_parameters, flags, version)
+    }
+
+    pub fn new_req_execute_with_result_metadata_id(
+        id: &CBytesShort,
+        result_metadata_id: Option<&CBytesShort>,
+        query_parameters: &QueryParams,
+        flags: Flags,
+        version: Version,
+    ) -> Frame {
+        let body = BodyReqExecute::new(id, result_metadata_id, query_parameters);
+        let mut frame = Frame::new_request(&body, flags, version);
+
+        frame.compression_disabled = query_parameters.compression_disabled;
+        frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn body_req_execute_accessors() {
+        let id = CBytesShort::new(vec![1, 2, 3]);
+        let query_parameters = QueryParams::default();
+
+        let body = BodyReqExecute::new(&id, None, &query_parameters);
+        assert!(std::ptr::eq(body.id(), &id));
+        assert!(body.result_metadata_id().is_none());
+        assert!(std::ptr::eq(body.query_parameters(), &query_parameters));
+    }
+
+    #[test]
+    fn body_req_execute_result_metadata_id() {
+        let id = CBytesShort::new(vec![1, 2]);
+        let result_metadata_id = CBytesShort::new(vec![3]);
+        let query_parameters = QueryParams::default();
+
+        let body = BodyReqExecute::new(&id, Some(&result_metadata_id), &query_parameters);
+        assert_eq!(
+            body.serialize_with_version_to_vec(Version::V5),
+            vec![0, 2, 1, 2, 0, 1, 3, 0, 1, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            body.serialize_with_version_to_vec(Version::V4),
+            vec![0, 2, 1, 2, 0, 1, 0]
+        );
+
+        let body = BodyReqExecute::new(&id, None, &query_parameters);
+        assert_eq!(
+            body.serialize_with_version_to_vec(Version::V5),
+            vec![0, 2, 1, 2, 0, 0, 0, 1, 0, 0, 0, 0]
+        );
     }
 }
@@ -1,5 +1,6 @@
 use std::io::Cursor;
 
+use crate::frame::frame_request::{private, RequestMessage};
 use crate::frame::*;
 
 /// The structure which represents a body of a frame of type `options`.
@@ -13,22 +14,21 @@ impl Serialize for BodyReqOptions {
 
 // Frame implementation related to BodyReqStartup
 
+impl private::Sealed for BodyReqOptions {}
+
+impl RequestMessage for BodyReqOptions {
+    const OPCODE: Opcode = Opcode::Options;
+
+    #[inline]
+    fn serialize_body(&self, _version: Version) -> Vec<u8> {
+        self.serialize_to_vec()
+    }
+}
+
 impl Frame {
     /// Creates new frame of type `options`.
     pub fn new_req_options(version: Version) -> Frame {
-        let direction = Direction::Request;
-        let opcode = Opcode::Options;
-        let body: BodyReqOptions = Default::default();
-
-        Frame::new(
-            version,
-            direction,
-            Flags::empty(),
-            opcode,
-            body.serialize_to_vec(),
-            None,
-            vec![],
-        )
+        Frame::new_request(&BodyReqOptions, Flags::empty(), version)
     }
 }
 
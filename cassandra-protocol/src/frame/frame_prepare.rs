@@ -1,19 +1,44 @@
+use bitflags::bitflags;
 use std::io::Cursor;
 
+use crate::frame::frame_request::{private, RequestMessage};
 use crate::frame::*;
 use crate::types::*;
 
+bitflags! {
+    /// Flags of a `prepare` body, available since protocol v5.
+    pub struct PrepareFlags: u32 {
+        const WITH_KEYSPACE = 0x01;
+    }
+}
+
 /// Struct that represents a body of a frame of type `prepare`
 #[derive(Debug)]
 pub struct BodyReqPrepare {
     query: CStringLong,
+    keyspace: Option<CString>,
 }
 
 impl BodyReqPrepare {
     /// Creates new body of a frame of type `prepare` that prepares query `query`.
     pub fn new(query: String) -> BodyReqPrepare {
+        BodyReqPrepare::with_keyspace(query, None)
+    }
+
+    /// Creates new body of a frame of type `prepare` that prepares query `query` in given
+    /// keyspace. The keyspace is only sent with protocol v5 and later.
+    pub fn with_keyspace(query: String, keyspace: Option<String>) -> BodyReqPrepare {
         BodyReqPrepare {
             query: CStringLong::new(query),
+            keyspace: keyspace.map(CString::new),
+        }
+    }
+
+    fn flags(&self) -> PrepareFlags {
+        if self.keyspace.is_some() {
+            PrepareFlags::WITH_KEYSPACE
+        } else {
+            PrepareFlags::empty()
         }
     }
 }
@@ -21,24 +46,74 @@ impl BodyReqPrepare {
 impl Serialize for BodyReqPrepare {
     #[inline]
     fn serialize(&self, cursor: &mut Cursor<&mut Vec<u8>>) {
+        self.serialize_with_version(cursor, Version::V4);
+    }
+}
+
+impl SerializeWithVersion for BodyReqPrepare {
+    fn serialize_with_version(&self, cursor: &mut Cursor<&mut Vec<u8>>, version: Version) {
         self.query.serialize(cursor);
+
+        if version >= Version::V5 {
+            self.flags().bits().serialize(cursor);
+
+            if let Some(keyspace) = &self.keyspace {
+                keyspace.serialize(cursor);
+            }
+        }
+    }
+}
+
+impl private::Sealed for BodyReqPrepare {}
+
+impl RequestMessage for BodyReqPrepare {
+    const OPCODE: Opcode = Opcode::Prepare;
+
+    #[inline]
+    fn serialize_body(&self, version: Version) -> Vec<u8> {
+        self.serialize_with_version_to_vec(version)
     }
 }
 
 impl Frame {
     pub fn new_req_prepare(query: String, flags: Flags, version: Version) -> Frame {
-        let direction = Direction::Request;
-        let opcode = Opcode::Prepare;
-        let body = BodyReqPrepare::new(query);
-
-        Frame::new(
-            version,
-            direction,
-            flags,
-            opcode,
-            body.serialize_to_vec(),
-            None,
-            vec![],
-        )
+        Frame::new_req_prepare_with_keyspace(query, None, flags, version)
+    }
+
+    /// Creates a `prepare` request in given keyspace, so the prepared metadata is correct even
+    /// when no keyspace is in use by the connection. The keyspace is only sent with protocol
+    /// v5 and later.
+    pub fn new_req_prepare_with_keyspace(
+        query: String,
+        keyspace: Option<String>,
+        flags: Flags,
+        version: Version,
+    ) -> Frame {
+        let body = BodyReqPrepare::with_keyspace(query, keyspace);
+        Frame::new_request(&body, flags, version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prepare_with_keyspace_v5() {
+        let body = BodyReqPrepare::with_keyspace("q".into(), Some("ks".into()));
+        assert_eq!(
+            body.serialize_with_version_to_vec(Version::V5),
+            vec![0, 0, 0, 1, b'q', 0, 0, 0, 1, 0, 2, b'k', b's']
+        );
+        assert_eq!(
+            body.serialize_with_version_to_vec(Version::V4),
+            vec![0, 0, 0, 1, b'q']
+        );
+
+        let body = BodyReqPrepare::new("q".into());
+        assert_eq!(
+            body.serialize_with_version_to_vec(Version::V5),
+            vec![0, 0, 0, 1, b'q', 0, 0, 0, 0]
+        );
     }
 }
@@ -1,7 +1,9 @@
 use crate::consistency::Consistency;
+use crate::frame::frame_request::{private, RequestMessage};
 use crate::frame::traits::FromCursor;
 use crate::frame::*;
 use crate::query::{Query, QueryParams, QueryValues};
+use crate::types::paging_state::PagingState;
 use crate::types::*;
 use std::io::Cursor;
 
@@ -22,7 +24,7 @@ impl BodyReqQuery {
         values: Option<QueryValues>,
         with_names: bool,
         page_size: Option<i32>,
-        paging_state: Option<CBytes>,
+        paging_state: Option<PagingState>,
         serial_consistency: Option<Consistency>,
         timestamp: Option<i64>,
         is_idempotent: bool,
@@ -41,15 +43,29 @@ impl BodyReqQuery {
                 keyspace: None,
                 token: None,
                 routing_key: None,
+                now_in_seconds: None,
+                compression_disabled: false,
+                max_values_bytes: None,
             },
         }
     }
 }
 
 impl FromCursor for BodyReqQuery {
+    #[inline]
     fn from_cursor(cursor: &mut Cursor<&[u8]>) -> error::Result<BodyReqQuery> {
+        Self::from_cursor_with_version(cursor, Version::V4)
+    }
+}
+
+impl BodyReqQuery {
+    /// Decodes the body according to given protocol version.
+    pub fn from_cursor_with_version(
+        cursor: &mut Cursor<&[u8]>,
+        version: Version,
+    ) -> error::Result<BodyReqQuery> {
         let query = CStringLong::from_cursor(cursor)?;
-        let query_params = QueryParams::from_cursor(cursor)?;
+        let query_params = QueryParams::from_cursor_with_version(cursor, version)?;
 
         Ok(BodyReqQuery {
             query,
@@ -61,8 +77,26 @@ impl FromCursor for BodyReqQuery {
 impl Serialize for BodyReqQuery {
     #[inline]
     fn serialize(&self, cursor: &mut Cursor<&mut Vec<u8>>) {
+        self.serialize_with_version(cursor, Version::V4);
+    }
+}
+
+impl SerializeWithVersion for BodyReqQuery {
+    #[inline]
+    fn serialize_with_version(&self, cursor: &mut Cursor<&mut Vec<u8>>, version: Version) {
         self.query.serialize(cursor);
-        self.query_params.serialize(cursor);
+        self.query_params.serialize_with_version(cursor, version);
+    }
+}
+
+impl private::Sealed for BodyReqQuery {}
+
+impl RequestMessage for BodyReqQuery {
+    const OPCODE: Opcode = Opcode::Query;
+
+    #[inline]
+    fn serialize_body(&self, version: Version) -> Vec<u8> {
+        self.serialize_with_version_to_vec(version)
     }
 }
 
@@ -74,15 +108,13 @@ impl Frame {
         values: Option<QueryValues>,
         with_names: bool,
         page_size: Option<i32>,
-        paging_state: Option<CBytes>,
+        paging_state: Option<PagingState>,
         serial_consistency: Option<Consistency>,
         timestamp: Option<i64>,
         flags: Flags,
         is_idempotent: bool,
         version: Version,
     ) -> Frame {
-        let direction = Direction::Request;
-        let opcode = Opcode::Query;
         let body = BodyReqQuery::new(
             query,
             consistency,
@@ -95,31 +127,19 @@ impl Frame {
             is_idempotent,
         );
 
-        Frame::new(
-            version,
-            direction,
-            flags,
-            opcode,
-            body.serialize_to_vec(),
-            None,
-            vec![],
-        )
+        Frame::new_request(&body, flags, version)
     }
 
-    #[inline]
     pub fn new_query(query: Query, flags: Flags, version: Version) -> Frame {
-        Frame::new_req_query(
-            query.query,
-            query.params.consistency,
-            query.params.values,
-            query.params.with_names,
-            query.params.page_size,
-            query.params.paging_state,
-            query.params.serial_consistency,
-            query.params.timestamp,
-            flags,
-            query.params.is_idempotent,
-            version,
-        )
+        let compression_disabled = query.params.compression_disabled;
+        let body = BodyReqQuery {
+            query: CStringLong::new(query.query),
+            query_params: query.params,
+        };
+
+        let mut frame = Frame::new_request(&body, flags, version);
+
+        frame.compression_disabled = compression_disabled;
+        frame
     }
 }
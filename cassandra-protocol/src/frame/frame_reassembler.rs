@@ -0,0 +1,168 @@
+use crate::constants::FRAME_HEADER_LEN;
+use crate::error;
+use crate::frame::raw_frame::RawFrame;
+
+/// Reassembles frames from protocol v5 segment payloads. A self-contained segment carries one
+/// or more complete frames, while a frame larger than a single segment is split across
+/// consecutive non self-contained segments, which need to be accumulated before the frame can
+/// be decoded.
+#[derive(Debug, Default, Clone)]
+pub struct FrameReassembler {
+    buffer: Vec<u8>,
+}
+
+impl FrameReassembler {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Checks if a part of a frame is waiting for further segments.
+    #[inline]
+    pub fn is_pending(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    /// Feeds a segment payload and returns all frames completed by it. On error, any partially
+    /// accumulated frame is discarded, so following segments start a new frame.
+    pub fn push_segment(
+        &mut self,
+        payload: &[u8],
+        self_contained: bool,
+    ) -> error::Result<Vec<RawFrame>> {
+        if self_contained {
+            if self.is_pending() {
+                self.buffer.clear();
+                return Err(error::Error::Protocol(
+                    "Self-contained segment received while a frame is incomplete".into(),
+                ));
+            }
+
+            return split_frames(payload);
+        }
+
+        self.buffer.extend_from_slice(payload);
+        if self.buffer.len() < FRAME_HEADER_LEN {
+            return Ok(vec![]);
+        }
+
+        let frame_len = match frame_body_len(&self.buffer) {
+            Ok(body_len) => FRAME_HEADER_LEN + body_len,
+            Err(error) => {
+                self.buffer.clear();
+                return Err(error);
+            }
+        };
+
+        if self.buffer.len() < frame_len {
+            return Ok(vec![]);
+        }
+
+        if self.buffer.len() > frame_len {
+            self.buffer.clear();
+            return Err(error::Error::Protocol(
+                "Non self-contained segments contain more than a single frame".into(),
+            ));
+        }
+
+        let frame = RawFrame::from_bytes(&self.buffer);
+        self.buffer.clear();
+        frame.map(|frame| vec![frame])
+    }
+}
+
+fn frame_body_len(bytes: &[u8]) -> error::Result<usize> {
+    let mut header = [0; FRAME_HEADER_LEN];
+    header.copy_from_slice(&bytes[..FRAME_HEADER_LEN]);
+    RawFrame::parse_header(&header)
+}
+
+fn split_frames(mut payload: &[u8]) -> error::Result<Vec<RawFrame>> {
+    let mut frames = vec![];
+    while !payload.is_empty() {
+        if payload.len() < FRAME_HEADER_LEN {
+            return Err(error::Error::Protocol(
+                "Self-contained segment ends with a partial frame".into(),
+            ));
+        }
+
+        let frame_len = FRAME_HEADER_LEN + frame_body_len(payload)?;
+        if payload.len() < frame_len {
+            return Err(error::Error::Protocol(
+                "Self-contained segment ends with a partial frame".into(),
+            ));
+        }
+
+        frames.push(RawFrame::from_bytes(&payload[..frame_len])?);
+        payload = &payload[frame_len..];
+    }
+
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame_bytes(stream: u8, body: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0x85, 0, 0, stream, 0x08, 0, 0, 0, body.len() as u8];
+        bytes.extend_from_slice(body);
+        bytes
+    }
+
+    #[test]
+    fn reassemble_frame_from_three_segments() {
+        let bytes = frame_bytes(1, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        let mut reassembler = FrameReassembler::new();
+
+        assert!(reassembler
+            .push_segment(&bytes[..5], false)
+            .unwrap()
+            .is_empty());
+        assert!(reassembler
+            .push_segment(&bytes[5..12], false)
+            .unwrap()
+            .is_empty());
+        assert!(reassembler.is_pending());
+
+        let frames = reassembler.push_segment(&bytes[12..], false).unwrap();
+        assert_eq!(frames, vec![RawFrame::from_bytes(&bytes).unwrap()]);
+        assert!(!reassembler.is_pending());
+    }
+
+    #[test]
+    fn split_self_contained_segment() {
+        let mut payload = frame_bytes(1, &[1, 2]);
+        payload.extend(frame_bytes(2, &[3]));
+
+        let frames = FrameReassembler::new()
+            .push_segment(&payload, true)
+            .unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].body, vec![1, 2]);
+        assert_eq!(frames[1].body, vec![3]);
+    }
+
+    #[test]
+    fn reject_self_contained_segment_while_pending() {
+        let bytes = frame_bytes(1, &[1, 2, 3]);
+        let mut reassembler = FrameReassembler::new();
+
+        reassembler.push_segment(&bytes[..4], false).unwrap();
+        assert!(reassembler.push_segment(&bytes, true).is_err());
+        assert!(!reassembler.is_pending());
+    }
+
+    #[test]
+    fn recover_after_invalid_header() {
+        let mut invalid = frame_bytes(1, &[1, 2, 3]);
+        invalid[0] = 0x7f;
+
+        let mut reassembler = FrameReassembler::new();
+        assert!(reassembler.push_segment(&invalid, false).is_err());
+        assert!(!reassembler.is_pending());
+
+        let bytes = frame_bytes(2, &[4, 5]);
+        let frames = reassembler.push_segment(&bytes, false).unwrap();
+        assert_eq!(frames, vec![RawFrame::from_bytes(&bytes).unwrap()]);
+    }
+}
@@ -1,6 +1,7 @@
 use std::io::Cursor;
 
 use crate::frame::events::SimpleServerEvent;
+use crate::frame::frame_request::{private, RequestMessage};
 use crate::frame::*;
 use crate::types::{CString, CStringList};
 
@@ -25,21 +26,21 @@ impl Serialize for BodyReqRegister {
 
 // Frame implementation related to BodyReqRegister
 
+impl private::Sealed for BodyReqRegister {}
+
+impl RequestMessage for BodyReqRegister {
+    const OPCODE: Opcode = Opcode::Register;
+
+    #[inline]
+    fn serialize_body(&self, _version: Version) -> Vec<u8> {
+        self.serialize_to_vec()
+    }
+}
+
 impl Frame {
     /// Creates new frame of type `REGISTER`.
     pub fn new_req_register(events: Vec<SimpleServerEvent>, version: Version) -> Frame {
-        let direction = Direction::Request;
-        let opcode = Opcode::Register;
         let register_body = BodyReqRegister { events };
-
-        Frame::new(
-            version,
-            direction,
-            Flags::empty(),
-            opcode,
-            register_body.serialize_to_vec(),
-            None,
-            vec![],
-        )
+        Frame::new_request(&register_body, Flags::empty(), version)
     }
 }
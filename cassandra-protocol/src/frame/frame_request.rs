@@ -2,7 +2,22 @@ use std::io::Cursor;
 
 use crate::error;
 use crate::frame::frame_query::BodyReqQuery;
-use crate::frame::{FromCursor, Opcode, Serialize};
+use crate::frame::{Opcode, Serialize, Version};
+
+pub(crate) mod private {
+    pub trait Sealed {}
+}
+
+/// Body of a request message, which determines the opcode of a frame carrying it. This makes it
+/// impossible to create a frame with a body not matching its opcode (see `Frame::new_request`).
+/// The trait is sealed, since only bodies defined by the protocol can be sent.
+pub trait RequestMessage: private::Sealed {
+    /// Opcode of frames carrying this body.
+    const OPCODE: Opcode;
+
+    /// Serializes the body according to given protocol version.
+    fn serialize_body(&self, version: Version) -> Vec<u8>;
+}
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[allow(clippy::large_enum_variant)]
@@ -30,18 +45,67 @@ impl Serialize for RequestBody {
 }
 
 impl RequestBody {
-    pub fn try_from(bytes: &[u8], response_type: Opcode) -> error::Result<RequestBody> {
+    pub fn try_from(
+        bytes: &[u8],
+        response_type: Opcode,
+        version: Version,
+    ) -> error::Result<RequestBody> {
         let mut cursor: Cursor<&[u8]> = Cursor::new(bytes);
         match response_type {
             Opcode::Startup => Ok(RequestBody::Startup),
             Opcode::Options => Ok(RequestBody::Options),
-            Opcode::Query => Ok(RequestBody::Query(BodyReqQuery::from_cursor(&mut cursor)?)),
+            Opcode::Query => Ok(RequestBody::Query(BodyReqQuery::from_cursor_with_version(
+                &mut cursor,
+                version,
+            )?)),
             Opcode::Prepare => Ok(RequestBody::Prepare),
             Opcode::Execute => Ok(RequestBody::Execute),
             Opcode::Register => Ok(RequestBody::Register),
             Opcode::Batch => Ok(RequestBody::Batch),
             Opcode::AuthResponse => Ok(RequestBody::AuthResponse),
-            _ => Err(format!("opcode {} is not a request", response_type).into()),
+            _ => Err(error::Error::Protocol(format!(
+                "opcode {} is not a request",
+                response_type
+            ))),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::frame_options::BodyReqOptions;
+    use crate::frame::{Flags, Frame};
+    use crate::query::QueryParams;
+    use crate::types::CStringLong;
+
+    // checked at compile time
+    const OPTIONS_OPCODE: Opcode = <BodyReqOptions as RequestMessage>::OPCODE;
+
+    #[test]
+    fn request_message_opcode() {
+        assert_eq!(OPTIONS_OPCODE, Opcode::Options);
+        assert_eq!(BodyReqQuery::OPCODE, Opcode::Query);
+
+        let frame = Frame::new_request(&BodyReqOptions, Flags::empty(), Version::V4);
+        assert_eq!(frame.opcode, Opcode::Options);
+
+        let frame = Frame::new_req_prepare("SELECT".into(), Flags::empty(), Version::V4);
+        assert_eq!(frame.opcode, Opcode::Prepare);
+    }
+
+    #[test]
+    fn request_body_query_v5() {
+        let body = BodyReqQuery {
+            query: CStringLong::new("SELECT".into()),
+            query_params: QueryParams {
+                keyspace: Some("ks".into()),
+                now_in_seconds: Some(10),
+                ..Default::default()
+            },
+        };
+
+        let frame = Frame::new_request(&body, Flags::empty(), Version::V5);
+        assert_eq!(frame.request_body().unwrap(), RequestBody::Query(body));
+    }
+}
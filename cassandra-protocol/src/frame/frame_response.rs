@@ -10,7 +10,7 @@ use crate::frame::frame_result::{
     BodyResResultPrepared, BodyResResultRows, BodyResResultSetKeyspace, ResResultBody, RowsMetadata,
 };
 use crate::frame::frame_supported::*;
-use crate::frame::{FromCursor, Opcode, Version};
+use crate::frame::{DecodeMode, FromCursor, Opcode, Version};
 use crate::types::rows::Row;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -43,10 +43,24 @@ impl ResponseBody {
         bytes: &[u8],
         response_type: Opcode,
         version: Version,
+    ) -> error::Result<ResponseBody> {
+        Self::try_from_with_mode(bytes, response_type, version, DecodeMode::default())
+    }
+
+    /// Decodes a response body, handling unknown enum values according to given mode.
+    pub fn try_from_with_mode(
+        bytes: &[u8],
+        response_type: Opcode,
+        version: Version,
+        mode: DecodeMode,
     ) -> error::Result<ResponseBody> {
         let mut cursor: Cursor<&[u8]> = Cursor::new(bytes);
         match response_type {
-            Opcode::Error => Ok(ResponseBody::Error(CdrsError::from_cursor(&mut cursor)?)),
+            Opcode::Error => Ok(ResponseBody::Error(CdrsError::from_cursor_with_mode(
+                &mut cursor,
+                version,
+                mode,
+            )?)),
             Opcode::Ready => Ok(ResponseBody::Ready),
             Opcode::Authenticate => Ok(ResponseBody::Authenticate(
                 BodyResAuthenticate::from_cursor(&mut cursor)?,
@@ -65,7 +79,10 @@ impl ResponseBody {
             Opcode::AuthSuccess => Ok(ResponseBody::AuthSuccess(BodyReqAuthSuccess::from_cursor(
                 &mut cursor,
             )?)),
-            _ => Err(format!("opcode {} is not a response", response_type).into()),
+            _ => Err(error::Error::Protocol(format!(
+                "opcode {} is not a response",
+                response_type
+            ))),
         }
     }
 
@@ -119,7 +136,7 @@ impl ResponseBody {
 
     pub fn authenticator(&self) -> Option<&str> {
         match *self {
-            ResponseBody::Authenticate(ref auth) => Some(auth.data.as_str()),
+            ResponseBody::Authenticate(ref auth) => Some(auth.authenticator()),
             _ => None,
         }
     }
@@ -6,7 +6,8 @@ use std::io::{Cursor, Error as IoError, Read};
 use crate::error;
 use crate::error::Error;
 use crate::frame::events::SchemaChange;
-use crate::frame::{FromBytes, FromCursor, Serialize, Version};
+use crate::frame::{Frame, FromBytes, FromCursor, Opcode, Serialize, Version};
+use crate::types::paging_state::PagingState;
 use crate::types::rows::Row;
 use crate::types::*;
 
@@ -62,7 +63,10 @@ impl TryFrom<i32> for ResultKind {
             0x0003 => Ok(ResultKind::SetKeyspace),
             0x0004 => Ok(ResultKind::Prepared),
             0x0005 => Ok(ResultKind::SchemaChange),
-            _ => Err(format!("Unexpected result kind: {}", value).into()),
+            _ => Err(Error::Protocol(format!(
+                "Unexpected result kind: {}",
+                value
+            ))),
         }
     }
 }
@@ -129,7 +133,9 @@ impl ResResultBody {
     ) -> error::Result<ResResultBody> {
         Ok(match result_kind {
             ResultKind::Void => ResResultBody::Void,
-            ResultKind::Rows => ResResultBody::Rows(BodyResResultRows::from_cursor(cursor)?),
+            ResultKind::Rows => ResResultBody::Rows(BodyResResultRows::from_cursor_with_version(
+                cursor, version,
+            )?),
             ResultKind::SetKeyspace => {
                 ResResultBody::SetKeyspace(BodyResResultSetKeyspace::from_cursor(cursor)?)
             }
@@ -207,6 +213,25 @@ impl FromCursor for BodyResResultSetKeyspace {
     }
 }
 
+impl Frame {
+    /// Returns the part of a `ROWS` result body which follows rows metadata, i.e. rows count and
+    /// rows content, without decoding it. Useful for custom row decoders. Returns `None` if this
+    /// is not a rows result or the metadata cannot be read.
+    pub fn rows_data(&self) -> Option<&[u8]> {
+        if self.opcode != Opcode::Result {
+            return None;
+        }
+
+        let mut cursor = Cursor::new(self.body.as_slice());
+        if ResultKind::from_cursor(&mut cursor).ok()? != ResultKind::Rows {
+            return None;
+        }
+
+        RowsMetadata::from_cursor(&mut cursor).ok()?;
+        self.body.get(cursor.position() as usize..)
+    }
+}
+
 /// Structure that represents result of type
 /// [rows](https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec#L533).
 #[derive(Debug, PartialEq, Ord, PartialOrd, Eq, Clone, Hash)]
@@ -217,6 +242,8 @@ pub struct BodyResResultRows {
     pub rows_count: CInt,
     /// From spec: it is composed of `rows_count` of rows.
     pub rows_content: Vec<Vec<CBytes>>,
+    /// Protocol version the rows were received with.
+    pub version: Version,
 }
 
 impl Serialize for BodyResResultRows {
@@ -231,24 +258,58 @@ impl Serialize for BodyResResultRows {
     }
 }
 
+/// Signals that result metadata of an executed prepared statement changed since it was
+/// prepared, e.g. due to a schema change. The caller should replace cached result metadata id
+/// and metadata with the new ones, since rows are encoded according to the new metadata.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct MetadataChanged {
+    pub new_id: CBytesShort,
+    pub new_metadata: RowsMetadata,
+}
+
 impl BodyResResultRows {
+    /// Returns new result metadata if the server signalled that it changed (protocol v5+).
+    pub fn metadata_changed(&self) -> Option<MetadataChanged> {
+        self.metadata
+            .new_metadata_id
+            .clone()
+            .map(|new_id| MetadataChanged {
+                new_id,
+                new_metadata: self.metadata.clone(),
+            })
+    }
+
     fn rows_content(
         cursor: &mut Cursor<&[u8]>,
         rows_count: i32,
         columns_count: i32,
     ) -> error::Result<Vec<Vec<CBytes>>> {
+        // every cell takes at least its length, so a larger count cannot be valid - checking it
+        // up front avoids allocating rows for a malicious count without reading any bytes
+        let remaining = cursor.get_ref().len() - cursor.position() as usize;
+        let min_row_len = (columns_count.max(0) as usize * INT_LEN).max(1);
+        if rows_count < 0 || rows_count as usize > remaining / min_row_len {
+            return Err(Error::Protocol(format!(
+                "Invalid rows count: {}",
+                rows_count
+            )));
+        }
+
         (0..rows_count)
             .map(|_| {
                 (0..columns_count)
-                    .map(|_| CBytes::from_cursor(cursor))
+                    .map(|_| CBytes::from_result_cursor(cursor))
                     .collect::<Result<_, _>>()
             })
             .collect::<Result<_, _>>()
     }
 }
 
-impl FromCursor for BodyResResultRows {
-    fn from_cursor(cursor: &mut Cursor<&[u8]>) -> error::Result<BodyResResultRows> {
+impl BodyResResultRows {
+    pub fn from_cursor_with_version(
+        cursor: &mut Cursor<&[u8]>,
+        version: Version,
+    ) -> error::Result<BodyResResultRows> {
         let metadata = RowsMetadata::from_cursor(cursor)?;
         let rows_count = CInt::from_cursor(cursor)?;
         let rows_content =
@@ -258,10 +319,17 @@ impl FromCursor for BodyResResultRows {
             metadata,
             rows_count,
             rows_content,
+            version,
         })
     }
 }
 
+impl FromCursor for BodyResResultRows {
+    fn from_cursor(cursor: &mut Cursor<&[u8]>) -> error::Result<BodyResResultRows> {
+        BodyResResultRows::from_cursor_with_version(cursor, Version::V4)
+    }
+}
+
 /// Rows metadata.
 #[derive(Debug, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
 pub struct RowsMetadata {
@@ -270,7 +338,10 @@ pub struct RowsMetadata {
     /// Number of columns.
     pub columns_count: i32,
     /// Paging state.
-    pub paging_state: Option<CBytes>,
+    pub paging_state: Option<PagingState>,
+    /// New result metadata id, present if result metadata of an executed prepared statement
+    /// changed since it was prepared (protocol v5+).
+    pub new_metadata_id: Option<CBytesShort>,
     // In fact by specification Vec should have only two elements representing the
     // (unique) keyspace name and table name the columns belong to
     /// `Option` that may contain global table space.
@@ -288,6 +359,10 @@ impl Serialize for RowsMetadata {
             self.flags.contains(RowsMetadataFlags::HAS_MORE_PAGES),
             self.paging_state.is_some()
         );
+        assert_eq!(
+            self.flags.contains(RowsMetadataFlags::METADATA_CHANGED),
+            self.new_metadata_id.is_some()
+        );
 
         match (
             self.flags.contains(RowsMetadataFlags::NO_METADATA),
@@ -314,6 +389,10 @@ impl Serialize for RowsMetadata {
             paging_state.serialize(cursor);
         }
 
+        if let Some(new_metadata_id) = &self.new_metadata_id {
+            new_metadata_id.serialize(cursor);
+        }
+
         if let Some(global_table_spec) = &self.global_table_spec {
             global_table_spec.serialize(cursor);
         }
@@ -328,7 +407,13 @@ impl FromCursor for RowsMetadata {
         let columns_count = CInt::from_cursor(cursor)?;
 
         let paging_state = if flags.contains(RowsMetadataFlags::HAS_MORE_PAGES) {
-            Some(CBytes::from_cursor(cursor)?)
+            PagingState::from_cursor_opt(cursor)?
+        } else {
+            None
+        };
+
+        let new_metadata_id = if flags.contains(RowsMetadataFlags::METADATA_CHANGED) {
+            Some(CBytesShort::from_cursor(cursor)?)
         } else {
             None
         };
@@ -336,12 +421,18 @@ impl FromCursor for RowsMetadata {
         let has_global_table_space = flags.contains(RowsMetadataFlags::GLOBAL_TABLE_SPACE);
         let global_table_spec = extract_global_table_space(cursor, has_global_table_space)?;
 
-        let col_specs = ColSpec::parse_colspecs(cursor, columns_count, has_global_table_space)?;
+        // with NO_METADATA, column specs are omitted and need to be supplied by the caller
+        let col_specs = if flags.contains(RowsMetadataFlags::NO_METADATA) {
+            vec![]
+        } else {
+            ColSpec::parse_colspecs(cursor, columns_count, has_global_table_space)?
+        };
 
         Ok(RowsMetadata {
             flags,
             columns_count,
             paging_state,
+            new_metadata_id,
             global_table_spec,
             col_specs,
         })
@@ -353,6 +444,7 @@ bitflags! {
         const GLOBAL_TABLE_SPACE = 0x0001;
         const HAS_MORE_PAGES = 0x0002;
         const NO_METADATA = 0x0004;
+        const METADATA_CHANGED = 0x0008;
     }
 }
 
@@ -373,7 +465,7 @@ impl FromBytes for RowsMetadataFlags {
     fn from_bytes(bytes: &[u8]) -> error::Result<RowsMetadataFlags> {
         try_u64_from_bytes(bytes).map_err(Into::into).and_then(|f| {
             RowsMetadataFlags::from_bits(f as i32)
-                .ok_or_else(|| "Unexpected rows metadata flag".into())
+                .ok_or_else(|| Error::Protocol("Unexpected rows metadata flag".into()))
         })
     }
 }
@@ -478,11 +570,15 @@ pub enum ColType {
     Time,
     Smallint,
     Tinyint,
+    /// Native type since protocol v5, sent as a custom type in earlier versions.
+    Duration,
     List,
     Map,
     Set,
     Udt,
     Tuple,
+    /// Fixed-size vector (Cassandra 5.0+), sent as a custom type.
+    Vector,
     Null,
 }
 
@@ -511,13 +607,14 @@ impl TryFrom<i16> for ColType {
             0x0012 => Ok(ColType::Time),
             0x0013 => Ok(ColType::Smallint),
             0x0014 => Ok(ColType::Tinyint),
+            0x0015 => Ok(ColType::Duration),
             0x0020 => Ok(ColType::List),
             0x0021 => Ok(ColType::Map),
             0x0022 => Ok(ColType::Set),
             0x0030 => Ok(ColType::Udt),
             0x0031 => Ok(ColType::Tuple),
             0x0080 => Ok(ColType::Varchar),
-            _ => Err("Unexpected column type".into()),
+            _ => Err(Error::Protocol("Unexpected column type".into())),
         }
     }
 }
@@ -534,7 +631,7 @@ impl Serialize for ColType {
     #[inline]
     fn serialize(&self, cursor: &mut Cursor<&mut Vec<u8>>) {
         (match self {
-            ColType::Custom => 0x0000,
+            ColType::Custom | ColType::Vector => 0x0000,
             ColType::Ascii => 0x0001,
             ColType::Bigint => 0x0002,
             ColType::Blob => 0x0003,
@@ -554,6 +651,7 @@ impl Serialize for ColType {
             ColType::Time => 0x0012,
             ColType::Smallint => 0x0013,
             ColType::Tinyint => 0x0014,
+            ColType::Duration => 0x0015,
             ColType::List => 0x0020,
             ColType::Map => 0x0021,
             ColType::Set => 0x0022,
@@ -598,7 +696,10 @@ impl FromCursor for ColTypeOption {
     fn from_cursor(cursor: &mut Cursor<&[u8]>) -> error::Result<ColTypeOption> {
         let id = ColType::from_cursor(cursor)?;
         let value = match id {
-            ColType::Custom => Some(ColTypeOptionValue::CString(CString::from_cursor(cursor)?)),
+            ColType::Custom => {
+                let class_name = CString::from_cursor(cursor)?;
+                return Ok(ColTypeOption::from_custom_class(class_name.as_str()));
+            }
             ColType::Set => {
                 let col_type = ColTypeOption::from_cursor(cursor)?;
                 Some(ColTypeOptionValue::CSet(Box::new(col_type)))
@@ -624,6 +725,106 @@ impl FromCursor for ColTypeOption {
     }
 }
 
+pub(crate) const MARSHAL_PACKAGE: &str = "org.apache.cassandra.db.marshal.";
+const VECTOR_TYPE: &str = "VectorType";
+
+// simple types which may appear as custom type parameters, e.g. vector elements
+const MARSHAL_TYPES: &[(&str, ColType)] = &[
+    ("AsciiType", ColType::Ascii),
+    ("LongType", ColType::Bigint),
+    ("BytesType", ColType::Blob),
+    ("BooleanType", ColType::Boolean),
+    ("CounterColumnType", ColType::Counter),
+    ("DecimalType", ColType::Decimal),
+    ("DoubleType", ColType::Double),
+    ("FloatType", ColType::Float),
+    ("Int32Type", ColType::Int),
+    ("TimestampType", ColType::Timestamp),
+    ("UUIDType", ColType::Uuid),
+    ("UTF8Type", ColType::Varchar),
+    ("IntegerType", ColType::Varint),
+    ("TimeUUIDType", ColType::Timeuuid),
+    ("InetAddressType", ColType::Inet),
+    ("SimpleDateType", ColType::Date),
+    ("TimeType", ColType::Time),
+    ("ShortType", ColType::Smallint),
+    ("ByteType", ColType::Tinyint),
+    ("DurationType", ColType::Duration),
+];
+
+impl ColTypeOption {
+    /// Creates type option from a custom type class name. Vector types are recognized and
+    /// represented as `ColType::Vector`, while other types stay `ColType::Custom`.
+    pub fn from_custom_class(class_name: &str) -> ColTypeOption {
+        ColTypeOption::parse_vector_class(class_name).unwrap_or_else(|| ColTypeOption {
+            id: ColType::Custom,
+            value: Some(ColTypeOptionValue::CString(CString::new(
+                class_name.to_string(),
+            ))),
+        })
+    }
+
+    fn parse_vector_class(class_name: &str) -> Option<ColTypeOption> {
+        let params = class_name
+            .strip_prefix(MARSHAL_PACKAGE)
+            .unwrap_or(class_name)
+            .strip_prefix(VECTOR_TYPE)?
+            .strip_prefix('(')?
+            .strip_suffix(')')?;
+
+        // the element type might contain commas itself, but dimension is always last
+        let (element, dimension) = params.rsplit_once(',')?;
+        let dimension = dimension.trim().parse().ok()?;
+
+        Some(ColTypeOption {
+            id: ColType::Vector,
+            value: Some(ColTypeOptionValue::CVector(
+                Box::new(ColTypeOption::from_element_class(element.trim())),
+                dimension,
+            )),
+        })
+    }
+
+    fn from_element_class(class_name: &str) -> ColTypeOption {
+        let short_name = class_name
+            .strip_prefix(MARSHAL_PACKAGE)
+            .unwrap_or(class_name);
+
+        MARSHAL_TYPES
+            .iter()
+            .find(|(name, _)| *name == short_name)
+            .map(|(_, id)| ColTypeOption {
+                id: *id,
+                value: None,
+            })
+            .unwrap_or_else(|| ColTypeOption::from_custom_class(class_name))
+    }
+
+    fn class_name(&self) -> String {
+        match &self.value {
+            Some(ColTypeOptionValue::CString(class_name)) => class_name.as_str().to_string(),
+            Some(ColTypeOptionValue::CVector(element, dimension)) => {
+                vector_class_name(element, *dimension)
+            }
+            _ => MARSHAL_TYPES
+                .iter()
+                .find(|(_, id)| *id == self.id)
+                .map(|(name, _)| format!("{}{}", MARSHAL_PACKAGE, name))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+fn vector_class_name(element: &ColTypeOption, dimension: usize) -> String {
+    format!(
+        "{}{}({}, {})",
+        MARSHAL_PACKAGE,
+        VECTOR_TYPE,
+        element.class_name(),
+        dimension
+    )
+}
+
 /// Enum that represents all possible types of `value` of `ColTypeOption`.
 #[derive(Debug, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
 pub enum ColTypeOptionValue {
@@ -634,6 +835,8 @@ pub enum ColTypeOptionValue {
     UdtType(CUdt),
     TupleType(CTuple),
     CMap(Box<ColTypeOption>, Box<ColTypeOption>),
+    /// Vector element type and dimension.
+    CVector(Box<ColTypeOption>, usize),
 }
 
 impl Serialize for ColTypeOptionValue {
@@ -650,6 +853,9 @@ impl Serialize for ColTypeOptionValue {
                 v1.serialize(cursor);
                 v2.serialize(cursor);
             }
+            Self::CVector(element, dimension) => {
+                CString::new(vector_class_name(element, *dimension)).serialize(cursor)
+            }
         }
     }
 }
@@ -686,7 +892,7 @@ impl FromCursor for CUdt {
         let mut buff = [0; SHORT_LEN];
         cursor.read_exact(&mut buff)?;
 
-        let n = i16::from_be_bytes(buff);
+        let n = u16::from_be_bytes(buff);
         let mut descriptions = Vec::with_capacity(n as usize);
         for _ in 0..n {
             let name = CString::from_cursor(cursor)?;
@@ -723,7 +929,7 @@ impl FromCursor for CTuple {
         let mut buff = [0; SHORT_LEN];
         cursor.read_exact(&mut buff)?;
 
-        let n = i16::from_be_bytes(buff);
+        let n = u16::from_be_bytes(buff);
         let mut types = Vec::with_capacity(n as usize);
         for _ in 0..n {
             let col_type = ColTypeOption::from_cursor(cursor)?;
@@ -739,6 +945,9 @@ impl FromCursor for CTuple {
 pub struct BodyResResultPrepared {
     /// id of prepared request
     pub id: CBytesShort,
+    /// Id of the result metadata, which needs to be sent back when executing. Only present with
+    /// protocol v5 and above.
+    pub result_metadata_id: Option<CBytesShort>,
     /// metadata
     pub metadata: PreparedMetadata,
     /// It is defined exactly the same as <metadata> in the Rows
@@ -750,22 +959,47 @@ impl Serialize for BodyResResultPrepared {
     #[inline]
     fn serialize(&self, cursor: &mut Cursor<&mut Vec<u8>>) {
         self.id.serialize(cursor);
+
+        if let Some(result_metadata_id) = &self.result_metadata_id {
+            result_metadata_id.serialize(cursor);
+        }
+
         self.metadata.serialize(cursor);
         self.result_metadata.serialize(cursor);
     }
 }
 
 impl BodyResResultPrepared {
+    /// Specifications of bound variables, i.e. what needs to be passed when executing.
+    #[inline]
+    pub fn variables(&self) -> &[ColSpec] {
+        &self.metadata.col_specs
+    }
+
+    /// Specifications of columns returned when executing, known without executing the
+    /// statement. Empty for statements not returning rows.
+    #[inline]
+    pub fn result_columns(&self) -> &[ColSpec] {
+        &self.result_metadata.col_specs
+    }
+
     fn from_cursor(
         cursor: &mut Cursor<&[u8]>,
         version: Version,
     ) -> error::Result<BodyResResultPrepared> {
         let id = CBytesShort::from_cursor(cursor)?;
+        let result_metadata_id = if version >= Version::V5 {
+            Some(CBytesShort::from_cursor(cursor)?)
+        } else {
+            None
+        };
+
         let metadata = PreparedMetadata::from_cursor(cursor, version)?;
         let result_metadata = RowsMetadata::from_cursor(cursor)?;
 
         Ok(BodyResResultPrepared {
             id,
+            result_metadata_id,
             metadata,
             result_metadata,
         })
@@ -1077,6 +1311,50 @@ mod col_type_option {
         }
     }
 
+    #[test]
+    fn col_type_options_custom() {
+        let bytes = &[0, 0, 0, 3, 102, 111, 111];
+        let expected = ColTypeOption {
+            id: ColType::Custom,
+            value: Some(ColTypeOptionValue::CString(CString::new("foo".into()))),
+        };
+
+        {
+            let mut cursor: Cursor<&[u8]> = Cursor::new(bytes);
+            let col_type_option = ColTypeOption::from_cursor(&mut cursor).unwrap();
+            assert_eq!(col_type_option, expected);
+        }
+
+        {
+            let mut buffer = Vec::new();
+            let mut cursor = Cursor::new(&mut buffer);
+            expected.serialize(&mut cursor);
+            assert_eq!(buffer, bytes);
+        }
+    }
+
+    #[test]
+    fn col_type_options_vector() {
+        let class_name = "org.apache.cassandra.db.marshal.VectorType(org.apache.cassandra.db.marshal.FloatType, 3)";
+        let mut bytes = vec![0, 0, 0, class_name.len() as u8];
+        bytes.extend_from_slice(class_name.as_bytes());
+
+        let expected = ColTypeOption {
+            id: ColType::Vector,
+            value: Some(ColTypeOptionValue::CVector(
+                Box::new(ColTypeOption {
+                    id: ColType::Float,
+                    value: None,
+                }),
+                3,
+            )),
+        };
+
+        let mut cursor: Cursor<&[u8]> = Cursor::new(&bytes);
+        assert_eq!(ColTypeOption::from_cursor(&mut cursor).unwrap(), expected);
+        assert_eq!(expected.serialize_to_vec(), bytes);
+    }
+
     #[test]
     fn col_type_options_map() {
         let bytes = &[0, 33, 0, 9, 0, 9];
@@ -1178,6 +1456,7 @@ mod rows_metadata {
             flags: RowsMetadataFlags::empty(),
             columns_count: 2,
             paging_state: None,
+            new_metadata_id: None,
             global_table_spec: None,
             col_specs: vec![
                 ColSpec {
@@ -1250,6 +1529,7 @@ mod rows {
                 flags: RowsMetadataFlags::empty(),
                 columns_count: 2,
                 paging_state: None,
+                new_metadata_id: None,
                 global_table_spec: None,
                 col_specs: vec![
                     ColSpec {
@@ -1278,11 +1558,120 @@ mod rows {
             },
             rows_count: 0,
             rows_content: vec![],
+            version: Version::V4,
         });
 
         test_encode_decode(bytes, expected);
     }
 
+    #[test]
+    fn test_rows_invalid_count() {
+        let bytes: &[u8] = &[
+            0, 0, 0, 4, // rows metadata flag - no metadata
+            0, 0, 0, 0, // columns count
+            127, 255, 255, 255, // rows count
+        ];
+
+        let mut cursor = Cursor::new(bytes);
+        assert!(matches!(
+            BodyResResultRows::from_cursor(&mut cursor),
+            Err(Error::Protocol(_))
+        ));
+
+        let bytes: &[u8] = &[
+            0, 0, 0, 4, // rows metadata flag - no metadata
+            0, 0, 0, 1, // columns count
+            255, 255, 255, 255, // rows count
+        ];
+
+        let mut cursor = Cursor::new(bytes);
+        assert!(matches!(
+            BodyResResultRows::from_cursor(&mut cursor),
+            Err(Error::Protocol(_))
+        ));
+    }
+
+    #[test]
+    fn test_rows_not_set_cell() {
+        let bytes: &[u8] = &[
+            0, 0, 0, 1, // rows metadata flag
+            0, 0, 0, 2, // columns count
+            0, 2, 107, 115, // ks
+            0, 1, 116, // t
+            0, 2, 105, 100, // id
+            0, 9, // col type
+            0, 3, 118, 97, 108, // val
+            0, 9, // col type
+            0, 0, 0, 1, // rows count
+            0, 0, 0, 4, 0, 0, 0, 7, // id
+            255, 255, 255, 254, // val - not set
+        ];
+
+        let mut cursor = Cursor::new(bytes);
+        let err = BodyResResultRows::from_cursor(&mut cursor).unwrap_err();
+        assert!(
+            matches!(err, Error::Protocol(ref message) if message.contains("not set")),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_rows_metadata_changed() {
+        let bytes: &[u8] = &[
+            0, 0, 0, 9, // rows metadata flag
+            0, 0, 0, 1, // columns count
+            0, 2, 1, 2, // new metadata id
+            0, 2, 107, 115, // ks
+            0, 1, 116, // t
+            0, 2, 105, 100, // id
+            0, 9, // col type
+            0, 0, 0, 1, // rows count
+            0, 0, 0, 4, 0, 0, 0, 7, // row 1
+        ];
+
+        let mut cursor = Cursor::new(bytes);
+        let rows = BodyResResultRows::from_cursor(&mut cursor).unwrap();
+        assert_eq!(rows.rows_content, vec![vec![CBytes::new(vec![0, 0, 0, 7])]]);
+
+        let changed = rows.metadata_changed().unwrap();
+        assert_eq!(changed.new_id, CBytesShort::new(vec![1, 2]));
+        assert_eq!(changed.new_metadata.col_specs.len(), 1);
+        assert_eq!(changed.new_metadata.col_specs[0].name.as_str(), "id");
+
+        assert_eq!(rows.serialize_to_vec(), bytes);
+    }
+
+    #[test]
+    fn test_rows_data() {
+        let body = vec![
+            0, 0, 0, 2, // rows flag
+            0, 0, 0, 4, // rows metadata flag
+            0, 0, 0, 1, // columns count
+            0, 0, 0, 2, // rows count
+            0, 0, 0, 1, 5, // row 1
+            255, 255, 255, 255, // row 2
+        ];
+
+        let frame = Frame {
+            version: Version::V4,
+            direction: crate::frame::Direction::Response,
+            flags: crate::frame::Flags::empty(),
+            opcode: Opcode::Result,
+            stream: 0,
+            body,
+            tracing_id: None,
+            warnings: vec![],
+            custom_payload: Default::default(),
+            compression_disabled: false,
+        };
+
+        assert_eq!(
+            frame.rows_data(),
+            Some(&[0, 0, 0, 2, 0, 0, 0, 1, 5, 255, 255, 255, 255][..])
+        );
+    }
+
     #[test]
     fn test_rows_no_metadata() {
         let bytes = &[
@@ -1297,11 +1686,13 @@ mod rows {
                 flags: RowsMetadataFlags::NO_METADATA,
                 columns_count: 0,
                 paging_state: None,
+                new_metadata_id: None,
                 global_table_spec: None,
                 col_specs: vec![],
             },
             rows_count: 0,
             rows_content: vec![],
+            version: Version::V4,
         });
 
         test_encode_decode(bytes, expected);
@@ -1443,6 +1834,7 @@ mod prepared {
 
         let expected = ResResultBody::Prepared(BodyResResultPrepared {
             id: CBytesShort::new(to_short(1)),
+            result_metadata_id: None,
             metadata: PreparedMetadata {
                 flags: PreparedMetadataFlags::empty(),
                 columns_count: 2,
@@ -1478,6 +1870,7 @@ mod prepared {
                 flags: RowsMetadataFlags::empty(),
                 columns_count: 2,
                 paging_state: None,
+                new_metadata_id: None,
                 global_table_spec: None,
                 col_specs: vec![
                     ColSpec {
@@ -1508,6 +1901,99 @@ mod prepared {
 
         test_encode_decode(bytes, expected);
     }
+
+    #[test]
+    fn test_prepared_variables_and_result_columns() {
+        let bytes = &[
+            0, 0, 0, 4, // prepared
+            0, 2, 0, 1, // id
+            //
+            // prepared metadata
+            0, 0, 0, 1, // global table space flag
+            0, 0, 0, 2, // columns count
+            0, 0, 0, 0, // pk_count
+            0, 2, 107, 115, // ks
+            0, 2, 116, 98, // tb
+            0, 1, 97, 0, 9, // a int
+            0, 1, 98, 0, 13, // b varchar
+            //
+            // rows metadata
+            0, 0, 0, 1, // global table space flag
+            0, 0, 0, 3, // columns count
+            0, 2, 107, 115, // ks
+            0, 2, 116, 98, // tb
+            0, 1, 120, 0, 9, // x int
+            0, 1, 121, 0, 2, // y bigint
+            0, 1, 122, 0, 13, // z varchar
+        ];
+
+        let mut cursor: Cursor<&[u8]> = Cursor::new(bytes);
+        let prepared = ResResultBody::from_cursor(&mut cursor, Version::V4)
+            .unwrap()
+            .into_prepared()
+            .unwrap();
+
+        let variables: Vec<_> = prepared
+            .variables()
+            .iter()
+            .map(|spec| (spec.name.as_str(), spec.col_type.id))
+            .collect();
+        assert_eq!(
+            variables,
+            vec![("a", ColType::Int), ("b", ColType::Varchar)]
+        );
+
+        let result_columns: Vec<_> = prepared
+            .result_columns()
+            .iter()
+            .map(|spec| (spec.name.as_str(), spec.col_type.id))
+            .collect();
+        assert_eq!(
+            result_columns,
+            vec![
+                ("x", ColType::Int),
+                ("y", ColType::Bigint),
+                ("z", ColType::Varchar)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prepared_v5_result_metadata_id() {
+        let bytes = &[
+            0, 0, 0, 4, // prepared
+            0, 2, 0, 1, // id
+            0, 2, 7, 8, // result metadata id
+            //
+            // prepared metadata
+            0, 0, 0, 0, // empty flags
+            0, 0, 0, 1, // columns count
+            0, 0, 0, 0, // pk_count
+            0, 2, 107, 115, // ks
+            0, 2, 116, 98, // tb
+            0, 1, 97, 0, 9, // a int
+            //
+            // rows metadata
+            0, 0, 0, 4, // no metadata flag
+            0, 0, 0, 0, // columns count
+        ];
+
+        let mut cursor: Cursor<&[u8]> = Cursor::new(bytes);
+        let body = ResResultBody::from_cursor(&mut cursor, Version::V5).unwrap();
+
+        let mut buffer = Vec::new();
+        body.serialize(&mut Cursor::new(&mut buffer));
+        assert_eq!(buffer, bytes);
+
+        let prepared = body.into_prepared().unwrap();
+        assert_eq!(prepared.id, CBytesShort::new(vec![0, 1]));
+        assert_eq!(
+            prepared.result_metadata_id,
+            Some(CBytesShort::new(vec![7, 8]))
+        );
+        assert_eq!(prepared.variables().len(), 1);
+        assert!(prepared.result_columns().is_empty());
+    }
 }
 
 #[cfg(test)]
@@ -1,12 +1,26 @@
 use std::collections::HashMap;
 use std::io::Cursor;
 
+use crate::frame::frame_request::{private, RequestMessage};
 use crate::frame::*;
-use crate::types::*;
+use crate::types::CIntShort;
 
 const CQL_VERSION: &str = "CQL_VERSION";
 const CQL_VERSION_VAL: &str = "3.0.0";
 const COMPRESSION: &str = "COMPRESSION";
+const NO_COMPACT: &str = "NO_COMPACT";
+const THROW_ON_OVERLOAD: &str = "THROW_ON_OVERLOAD";
+const TRUE_VAL: &str = "true";
+
+/// Optional STARTUP settings. The beta flag applies to all frames sent on a connection, not only
+/// STARTUP, so it's set when encoding, see `Frame::encode_with_extra_flags`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StartupOptions {
+    /// Requests an `Overloaded` error instead of backpressure when the server is overloaded.
+    pub throw_on_overload: bool,
+    /// Requests legacy compact storage tables to be treated as regular ones.
+    pub no_compact: bool,
+}
 
 #[derive(Debug)]
 pub struct BodyReqStartup<'a> {
@@ -22,6 +36,19 @@ impl<'a> BodyReqStartup<'a> {
         }
         BodyReqStartup { map }
     }
+
+    pub fn with_options(compression: Option<&str>, options: StartupOptions) -> BodyReqStartup<'_> {
+        let mut body = BodyReqStartup::new(compression);
+        if options.throw_on_overload {
+            body.map.insert(THROW_ON_OVERLOAD, TRUE_VAL);
+        }
+
+        if options.no_compact {
+            body.map.insert(NO_COMPACT, TRUE_VAL);
+        }
+
+        body
+    }
 }
 
 impl<'a> Serialize for BodyReqStartup<'a> {
@@ -43,22 +70,31 @@ impl<'a> Serialize for BodyReqStartup<'a> {
 
 // Frame implementation related to BodyReqStartup
 
+impl<'a> private::Sealed for BodyReqStartup<'a> {}
+
+impl<'a> RequestMessage for BodyReqStartup<'a> {
+    const OPCODE: Opcode = Opcode::Startup;
+
+    #[inline]
+    fn serialize_body(&self, _version: Version) -> Vec<u8> {
+        self.serialize_to_vec()
+    }
+}
+
 impl Frame {
     /// Creates new frame of type `startup`.
     pub fn new_req_startup(compression: Option<&str>, version: Version) -> Frame {
-        let direction = Direction::Request;
-        let opcode = Opcode::Startup;
-        let body = BodyReqStartup::new(compression);
-
-        Frame::new(
-            version,
-            direction,
-            Flags::empty(),
-            opcode,
-            body.serialize_to_vec(),
-            None,
-            vec![],
-        )
+        Frame::new_req_startup_with_options(compression, Default::default(), version)
+    }
+
+    /// Creates new frame of type `startup` with additional options.
+    pub fn new_req_startup_with_options(
+        compression: Option<&str>,
+        options: StartupOptions,
+        version: Version,
+    ) -> Frame {
+        let body = BodyReqStartup::with_options(compression, options);
+        Frame::new_request(&body, Flags::empty(), version)
     }
 }
 
@@ -93,4 +129,18 @@ mod test {
         assert_eq!(frame.tracing_id, None);
         assert_eq!(frame.warnings, vec![] as Vec<String>);
     }
+
+    #[test]
+    fn new_body_req_startup_with_options() {
+        let body = BodyReqStartup::with_options(
+            None,
+            StartupOptions {
+                throw_on_overload: true,
+                no_compact: true,
+            },
+        );
+        assert_eq!(body.map.get("THROW_ON_OVERLOAD"), Some(&"true"));
+        assert_eq!(body.map.get("NO_COMPACT"), Some(&"true"));
+        assert_eq!(body.map.len(), 3);
+    }
 }
@@ -1,21 +1,41 @@
 use std::collections::HashMap;
 use std::io::{Cursor, Read};
 
+use crate::compression::Compression;
 use crate::error;
 use crate::frame::FromCursor;
 use crate::types::{CString, CStringList, SHORT_LEN};
 
+const COMPRESSION: &str = "COMPRESSION";
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct BodyResSupported {
     pub data: HashMap<String, Vec<String>>,
 }
 
+impl BodyResSupported {
+    /// Returns `preferred` compression if the server offers it, or `Compression::None`
+    /// otherwise, including when the server doesn't offer any compression at all.
+    pub fn negotiate_compression(&self, preferred: Compression) -> Compression {
+        let offered = match (preferred.as_str(), self.data.get(COMPRESSION)) {
+            (Some(preferred), Some(offered)) => offered.iter().any(|name| name == preferred),
+            _ => false,
+        };
+
+        if offered {
+            preferred
+        } else {
+            Compression::None
+        }
+    }
+}
+
 impl FromCursor for BodyResSupported {
     fn from_cursor(cursor: &mut Cursor<&[u8]>) -> error::Result<BodyResSupported> {
         let mut buff = [0; SHORT_LEN];
         cursor.read_exact(&mut buff)?;
 
-        let l = i16::from_be_bytes(buff) as usize;
+        let l = u16::from_be_bytes(buff) as usize;
         let mut data: HashMap<String, Vec<String>> = HashMap::with_capacity(l);
         for _ in 0..l {
             let name = CString::from_cursor(cursor)?.into_plain();
@@ -48,4 +68,32 @@ mod tests {
         assert_eq!(option_ab[0], "a".to_string());
         assert_eq!(option_ab[1], "b".to_string());
     }
+
+    #[test]
+    fn test_empty() {
+        let bytes = [0, 0];
+        let mut cursor: Cursor<&[u8]> = Cursor::new(&bytes);
+        let supported = BodyResSupported::from_cursor(&mut cursor).unwrap();
+        assert!(supported.data.is_empty());
+        assert_eq!(
+            supported.negotiate_compression(Compression::Lz4),
+            Compression::None
+        );
+    }
+
+    #[test]
+    fn test_negotiate_compression() {
+        let mut data = HashMap::new();
+        data.insert(COMPRESSION.to_string(), vec!["snappy".to_string()]);
+        let supported = BodyResSupported { data };
+
+        assert_eq!(
+            supported.negotiate_compression(Compression::Snappy),
+            Compression::Snappy
+        );
+        assert_eq!(
+            supported.negotiate_compression(Compression::Lz4),
+            Compression::None
+        );
+    }
 }
@@ -0,0 +1,104 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::frame::Frame;
+
+/// Hook for observing and modifying frames passing through the frame layer, independently of
+/// the underlying connection. Useful for cross-cutting concerns like metrics, auditing or
+/// rewriting requests.
+pub trait Interceptor: Send + Sync {
+    /// Called with a request frame before it is sent. The frame can be modified in place.
+    fn on_request(&self, _frame: &mut Frame) {}
+
+    /// Called with a response frame after it has been received. `ERROR` responses are not passed
+    /// here - they are converted into errors when received, and only reach the caller as such.
+    fn on_response(&self, _frame: &Frame) {}
+}
+
+/// Ordered chain of interceptors. Requests and responses are passed to interceptors in the
+/// order they were added.
+#[derive(Default, Clone)]
+pub struct InterceptorChain {
+    interceptors: Vec<Arc<dyn Interceptor>>,
+}
+
+impl InterceptorChain {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Appends an interceptor to the end of the chain.
+    pub fn add(&mut self, interceptor: Arc<dyn Interceptor>) {
+        self.interceptors.push(interceptor);
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.interceptors.is_empty()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.interceptors.len()
+    }
+
+    /// Passes an outgoing frame through all interceptors.
+    pub fn on_request(&self, frame: &mut Frame) {
+        for interceptor in &self.interceptors {
+            interceptor.on_request(frame);
+        }
+    }
+
+    /// Passes an incoming frame through all interceptors. Not called for `ERROR` responses.
+    pub fn on_response(&self, frame: &Frame) {
+        for interceptor in &self.interceptors {
+            interceptor.on_response(frame);
+        }
+    }
+}
+
+impl fmt::Debug for InterceptorChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InterceptorChain")
+            .field("len", &self.interceptors.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::Version;
+    use std::sync::atomic::{AtomicI16, Ordering};
+
+    #[derive(Default)]
+    struct StreamRewriter {
+        seen_stream: AtomicI16,
+    }
+
+    impl Interceptor for StreamRewriter {
+        fn on_request(&self, frame: &mut Frame) {
+            self.seen_stream.store(frame.stream, Ordering::Relaxed);
+            frame.stream = 42;
+        }
+    }
+
+    #[test]
+    fn interceptor_mutates_stream_id() {
+        let rewriter = Arc::new(StreamRewriter::default());
+
+        let mut chain = InterceptorChain::new();
+        chain.add(rewriter.clone());
+
+        let mut frame = Frame::new_req_options(Version::V4);
+        let original_stream = frame.stream;
+
+        chain.on_request(&mut frame);
+
+        assert_eq!(
+            rewriter.seen_stream.load(Ordering::Relaxed),
+            original_stream
+        );
+        assert_eq!(frame.stream, 42);
+    }
+}
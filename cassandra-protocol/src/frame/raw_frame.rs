@@ -0,0 +1,492 @@
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::io::{Cursor, Read};
+
+use crate::compression::Compression;
+use crate::constants::{FRAME_HEADER_LEN, MAX_FRAME_BODY_LEN};
+use crate::error;
+use crate::frame::{Direction, Flags, Frame, FromCursor, Opcode, StreamId, Version};
+use crate::types::data_serialization_types::decode_timeuuid;
+use crate::types::{try_i32_from_bytes, CBytes, CString, CStringList, SHORT_LEN, UUID_LEN};
+
+const STREAM_POS: usize = 2;
+const OPCODE_POS: usize = 4;
+const LENGTH_POS: usize = 5;
+
+/// Decoded frame header. Can be used to learn the length of the body before reading it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrameHeader {
+    pub version: Version,
+    pub direction: Direction,
+    pub flags: Flags,
+    pub stream: StreamId,
+    pub opcode: Opcode,
+    pub body_length: usize,
+}
+
+impl FrameHeader {
+    /// Parses and validates a frame header. Body lengths above `MAX_FRAME_BODY_LEN` are rejected,
+    /// so the length can be safely used to allocate a buffer for the body.
+    pub fn parse(header: &[u8; FRAME_HEADER_LEN]) -> error::Result<FrameHeader> {
+        let version = Version::try_from(header[0])?;
+        let opcode = Opcode::try_from(header[OPCODE_POS])?;
+
+        let body_length = try_i32_from_bytes(&header[LENGTH_POS..])?;
+        if body_length < 0 || body_length as usize > MAX_FRAME_BODY_LEN {
+            return Err(error::Error::Protocol(format!(
+                "Invalid frame body length: {}",
+                body_length
+            )));
+        }
+
+        Ok(FrameHeader {
+            version,
+            direction: Direction::from(header[0]),
+            flags: Flags::from_bits_for_version(header[1], version),
+            stream: StreamId::from_be_bytes([header[STREAM_POS], header[STREAM_POS + 1]]),
+            opcode,
+            body_length: body_length as usize,
+        })
+    }
+}
+
+/// Frame with a validated header and an opaque body. Useful for forwarding frames (e.g. in
+/// proxies) without paying the cost of decoding the body.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RawFrame {
+    pub header: [u8; FRAME_HEADER_LEN],
+    pub body: Vec<u8>,
+}
+
+impl RawFrame {
+    /// Parses a header and returns the length of the body which follows it.
+    pub fn parse_header(header: &[u8; FRAME_HEADER_LEN]) -> error::Result<usize> {
+        FrameHeader::parse(header).map(|header| header.body_length)
+    }
+
+    /// Decodes a raw frame from given bytes. Only the header gets validated, the body is kept
+    /// as-is.
+    pub fn from_bytes(bytes: &[u8]) -> error::Result<RawFrame> {
+        if bytes.len() < FRAME_HEADER_LEN {
+            return Err(error::Error::Protocol(format!(
+                "Frame too short for a header: {} bytes",
+                bytes.len()
+            )));
+        }
+
+        let mut header = [0; FRAME_HEADER_LEN];
+        header.copy_from_slice(&bytes[..FRAME_HEADER_LEN]);
+
+        let length = Self::parse_header(&header)?;
+        let body = &bytes[FRAME_HEADER_LEN..];
+        if body.len() != length {
+            return Err(error::Error::Protocol(format!(
+                "Frame body length mismatch: header declares {}, got {}",
+                length,
+                body.len()
+            )));
+        }
+
+        Ok(RawFrame {
+            header,
+            body: body.to_vec(),
+        })
+    }
+
+    /// Encodes the frame back into bytes.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut v = Vec::with_capacity(FRAME_HEADER_LEN + self.body.len());
+        v.extend_from_slice(&self.header);
+        v.extend_from_slice(&self.body);
+        v
+    }
+
+    #[inline]
+    pub fn version(&self) -> error::Result<Version> {
+        Version::try_from(self.header[0])
+    }
+
+    #[inline]
+    pub fn direction(&self) -> Direction {
+        Direction::from(self.header[0])
+    }
+
+    /// Returns header flags. Flags not defined by the protocol version are ignored, which
+    /// includes all flags if the version is invalid.
+    #[inline]
+    pub fn flags(&self) -> Flags {
+        match self.version() {
+            Ok(version) => Flags::from_bits_for_version(self.header[1], version),
+            Err(_) => Flags::empty(),
+        }
+    }
+
+    #[inline]
+    pub fn stream(&self) -> StreamId {
+        StreamId::from_be_bytes([self.header[STREAM_POS], self.header[STREAM_POS + 1]])
+    }
+
+    #[inline]
+    pub fn opcode(&self) -> error::Result<Opcode> {
+        Opcode::try_from(self.header[OPCODE_POS])
+    }
+
+    /// Fully decodes the frame, decompressing the body with given compressor if needed.
+    pub fn into_frame(self, compressor: Compression) -> error::Result<Frame> {
+        let version = self.version()?;
+        let direction = self.direction();
+        let flags = self.flags();
+        let stream = self.stream();
+        let opcode = self.opcode()?;
+
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!(
+            "decode_frame",
+            opcode = %opcode,
+            stream,
+            body_length = self.body.len()
+        )
+        .entered();
+
+        let full_body = if flags.contains(Flags::COMPRESSION) {
+            compressor.decode(self.body)?
+        } else {
+            Compression::None.decode(self.body)?
+        };
+
+        // Use cursor to get tracing id, warnings, custom payload and actual body
+        let mut body_cursor = Cursor::new(full_body.as_slice());
+
+        let tracing_id = if flags.contains(Flags::TRACING) {
+            let mut tracing_bytes = vec![0; UUID_LEN];
+            body_cursor.read_exact(&mut tracing_bytes)?;
+
+            decode_timeuuid(tracing_bytes.as_slice()).ok()
+        } else {
+            None
+        };
+
+        let warnings = if flags.contains(Flags::WARNING) {
+            CStringList::from_cursor(&mut body_cursor)?.into_plain()
+        } else {
+            vec![]
+        };
+
+        let custom_payload = if flags.contains(Flags::CUSTOM_PAYLOAD) {
+            decode_bytes_map(&mut body_cursor)?
+        } else {
+            BTreeMap::new()
+        };
+
+        let mut body = vec![];
+        body_cursor.read_to_end(&mut body)?;
+
+        Ok(Frame {
+            version,
+            direction,
+            flags,
+            opcode,
+            stream,
+            body,
+            tracing_id,
+            warnings,
+            custom_payload,
+            compression_disabled: false,
+        })
+    }
+}
+
+fn decode_bytes_map(cursor: &mut Cursor<&[u8]>) -> error::Result<BTreeMap<String, Vec<u8>>> {
+    let mut buff = [0; SHORT_LEN];
+    cursor.read_exact(&mut buff)?;
+
+    (0..u16::from_be_bytes(buff))
+        .map(|_| {
+            let key = CString::from_cursor(cursor)?.into_plain();
+            let value = CBytes::from_cursor(cursor)?
+                .into_plain()
+                .unwrap_or_default();
+            Ok((key, value))
+        })
+        .collect()
+}
+
+/// Decodes a frame from the beginning of given bytes, which may contain more data after it.
+/// Returns the frame along with the number of bytes it occupied (header and body), so the caller
+/// can advance past it.
+pub fn decode_frame(bytes: &[u8], compressor: Compression) -> error::Result<(Frame, usize)> {
+    if bytes.len() < FRAME_HEADER_LEN {
+        return Err(error::Error::Protocol(format!(
+            "Frame too short for a header: {} bytes",
+            bytes.len()
+        )));
+    }
+
+    let mut header = [0; FRAME_HEADER_LEN];
+    header.copy_from_slice(&bytes[..FRAME_HEADER_LEN]);
+
+    let length = FRAME_HEADER_LEN + RawFrame::parse_header(&header)?;
+    if bytes.len() < length {
+        return Err(error::Error::Protocol(format!(
+            "Frame truncated: expected {} bytes, got {}",
+            length,
+            bytes.len()
+        )));
+    }
+
+    let frame = RawFrame {
+        header,
+        body: bytes[FRAME_HEADER_LEN..length].to_vec(),
+    }
+    .into_frame(compressor)?;
+
+    Ok((frame, length))
+}
+
+/// Fully decodes an uncompressed frame of given version, including its body. Any malformed input
+/// results in an error instead of a panic, which makes this function suitable as a fuzzing
+/// target.
+pub fn decode_any(version: Version, bytes: &[u8]) -> error::Result<Frame> {
+    let raw_frame = RawFrame::from_bytes(bytes)?;
+
+    let frame_version = raw_frame.version()?;
+    if frame_version != version {
+        return Err(error::Error::Protocol(format!(
+            "Unexpected frame version: {}, expected {}",
+            frame_version, version
+        )));
+    }
+
+    let frame = raw_frame.into_frame(Compression::None)?;
+    match frame.direction {
+        Direction::Request => {
+            frame.request_body()?;
+        }
+        Direction::Response => {
+            frame.response_body()?;
+        }
+    }
+
+    Ok(frame)
+}
+
+impl TryFrom<RawFrame> for Frame {
+    type Error = error::Error;
+
+    /// Decodes an uncompressed raw frame.
+    fn try_from(frame: RawFrame) -> Result<Self, Self::Error> {
+        frame.into_frame(Compression::None)
+    }
+}
+
+impl TryFrom<&Frame> for RawFrame {
+    type Error = error::Error;
+
+    /// Encodes a frame without compression.
+    fn try_from(frame: &Frame) -> Result<Self, Self::Error> {
+        RawFrame::from_bytes(&frame.encode_with(Compression::None)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_frame_roundtrip() {
+        let bytes = [
+            4, 0, 0, 5, 7, 0, 0, 0, 11, 0, 0, 0, 4, 98, 108, 97, 104, 0, 0, 64,
+        ];
+
+        let frame = RawFrame::from_bytes(&bytes).unwrap();
+        assert_eq!(frame.version().unwrap(), Version::V4);
+        assert_eq!(frame.direction(), Direction::Request);
+        assert_eq!(frame.stream(), 5);
+        assert_eq!(frame.opcode().unwrap(), Opcode::Query);
+        assert_eq!(frame.encode(), bytes);
+
+        let decoded = Frame::try_from(frame.clone()).unwrap();
+        assert_eq!(decoded.stream, 5);
+        assert_eq!(decoded.body, &bytes[9..]);
+        assert_eq!(RawFrame::try_from(&decoded).unwrap(), frame);
+    }
+
+    #[test]
+    fn frame_header_parse() {
+        let header = FrameHeader::parse(&[0x84, 0x02, 0, 7, 8, 0, 0, 0, 4]).unwrap();
+        assert_eq!(header.version, Version::V4);
+        assert_eq!(header.direction, Direction::Response);
+        assert_eq!(header.flags, Flags::TRACING);
+        assert_eq!(header.stream, 7);
+        assert_eq!(header.opcode, Opcode::Result);
+        assert_eq!(header.body_length, 4);
+    }
+
+    #[test]
+    fn frame_header_version_and_direction() {
+        let cases = [
+            (0x04, Version::V4, Direction::Request),
+            (0x84, Version::V4, Direction::Response),
+            (0x05, Version::V5, Direction::Request),
+            (0x85, Version::V5, Direction::Response),
+        ];
+
+        for (version_byte, version, direction) in cases.iter() {
+            let header = FrameHeader::parse(&[*version_byte, 0, 0, 1, 5, 0, 0, 0, 0]).unwrap();
+            assert_eq!(header.version, *version);
+            assert_eq!(header.direction, *direction);
+        }
+
+        for version_byte in [0x01, 0x06, 0x81, 0x86, 0xff].iter() {
+            assert!(matches!(
+                FrameHeader::parse(&[*version_byte, 0, 0, 1, 5, 0, 0, 0, 0]),
+                Err(error::Error::UnsupportedVersion(v)) if v == version_byte & 0x7f
+            ));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "tracing")]
+    fn into_frame_emits_span() {
+        use std::fmt::Debug;
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        type RecordedSpan = (&'static str, Vec<(&'static str, String)>);
+
+        #[derive(Clone, Default)]
+        struct SpanRecorder {
+            spans: Arc<Mutex<Vec<RecordedSpan>>>,
+        }
+
+        struct FieldRecorder(Vec<(&'static str, String)>);
+
+        impl Visit for FieldRecorder {
+            fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+                self.0.push((field.name(), format!("{:?}", value)));
+            }
+        }
+
+        impl Subscriber for SpanRecorder {
+            fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut fields = FieldRecorder(vec![]);
+                span.record(&mut fields);
+
+                let mut spans = self.spans.lock().unwrap();
+                spans.push((span.metadata().name(), fields.0));
+                Id::from_u64(spans.len() as u64)
+            }
+
+            fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+            fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+            fn event(&self, _event: &Event<'_>) {}
+
+            fn enter(&self, _span: &Id) {}
+
+            fn exit(&self, _span: &Id) {}
+        }
+
+        let bytes = [
+            4, 0, 0, 5, 7, 0, 0, 0, 11, 0, 0, 0, 4, 98, 108, 97, 104, 0, 0, 64,
+        ];
+
+        let recorder = SpanRecorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            RawFrame::from_bytes(&bytes)
+                .unwrap()
+                .into_frame(Compression::None)
+                .unwrap();
+        });
+
+        let spans = recorder.spans.lock().unwrap();
+        assert_eq!(
+            *spans,
+            vec![(
+                "decode_frame",
+                vec![
+                    ("opcode", "Query".to_string()),
+                    ("stream", "5".to_string()),
+                    ("body_length", "11".to_string()),
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn decode_back_to_back_frames() {
+        let mut bytes = vec![4, 0, 0, 1, 5, 0, 0, 0, 0];
+        bytes.extend_from_slice(&[4, 0, 0, 2, 7, 0, 0, 0, 2, 1, 2]);
+
+        let (first, offset) = decode_frame(&bytes, Compression::None).unwrap();
+        assert_eq!(offset, FRAME_HEADER_LEN);
+        assert_eq!(first.stream, 1);
+        assert_eq!(first.opcode, Opcode::Options);
+
+        let (second, length) = decode_frame(&bytes[offset..], Compression::None).unwrap();
+        assert_eq!(offset + length, bytes.len());
+        assert_eq!(second.stream, 2);
+        assert_eq!(second.body, vec![1, 2]);
+
+        assert!(decode_frame(&bytes[offset..bytes.len() - 1], Compression::None).is_err());
+    }
+
+    #[test]
+    fn raw_frame_invalid_header() {
+        assert!(RawFrame::from_bytes(&[4, 0, 0, 0]).is_err());
+        assert!(RawFrame::from_bytes(&[1, 0, 0, 0, 2, 0, 0, 0, 0]).is_err());
+        assert!(RawFrame::from_bytes(&[4, 0, 0, 0, 0x42, 0, 0, 0, 0]).is_err());
+        assert!(RawFrame::from_bytes(&[4, 0, 0, 0, 2, 0, 0, 0, 1]).is_err());
+    }
+
+    #[test]
+    fn decode_any_malformed() {
+        let inputs: &[&[u8]] = &[
+            &[],
+            &[0x84],
+            &[0x84, 0, 0, 0, 8, 0, 0, 0, 4, 0, 0],
+            &[0x84, 0, 0, 0, 8, 0, 0, 0, 4, 0, 0, 0, 2],
+            &[0x84, 0, 0, 0, 8, 0, 0, 0, 4, 0, 0, 0, 9],
+            &[0x84, 0, 0, 0, 0, 0, 0, 0, 6, 0, 0, 0, 0, 0xff, 0xff],
+            &[0x84, 0, 0, 0, 6, 0, 0, 0, 2, 0xff, 0xff],
+            &[0x84, 0x02, 0, 0, 2, 0, 0, 0, 3, 1, 2, 3],
+            &[0x04, 0, 0, 0, 7, 0, 0, 0, 4, 0x7f, 0xff, 0xff, 0xff],
+            &[0x05, 0, 0, 0, 7, 0, 0, 0, 0],
+            &[0xff; 64],
+        ];
+
+        for input in inputs {
+            assert!(decode_any(Version::V4, input).is_err());
+        }
+    }
+
+    #[test]
+    fn decode_any_valid() {
+        let bytes = [0x84, 0, 0, 1, 2, 0, 0, 0, 0];
+        let frame = decode_any(Version::V4, &bytes).unwrap();
+        assert_eq!(frame.opcode, Opcode::Ready);
+        assert_eq!(frame.stream, 1);
+    }
+
+    #[test]
+    fn frame_version_from_header() {
+        let frame = RawFrame::from_bytes(&[0x84, 0, 0, 1, 2, 0, 0, 0, 0])
+            .unwrap()
+            .into_frame(Compression::None)
+            .unwrap();
+        assert_eq!(frame.version(), Version::V4);
+
+        let frame = RawFrame::from_bytes(&[0x85, 0, 0, 1, 2, 0, 0, 0, 0])
+            .unwrap()
+            .into_frame(Compression::None)
+            .unwrap();
+        assert_eq!(frame.version(), Version::V5);
+    }
+}
@@ -2,6 +2,7 @@ use num::BigInt;
 use std::io::{Cursor, Write};
 
 use crate::error;
+use crate::frame::Version;
 use crate::query;
 
 /// Trait that should be implemented by all types that wish to be serialized to a buffer.
@@ -19,6 +20,19 @@ pub trait Serialize {
     }
 }
 
+/// Trait for types, which serialized form depends on the protocol version.
+pub trait SerializeWithVersion {
+    /// Serializes given value using the cursor, according to given protocol version.
+    fn serialize_with_version(&self, cursor: &mut Cursor<&mut Vec<u8>>, version: Version);
+
+    /// Wrapper for easily starting hierarchical serialization.
+    fn serialize_with_version_to_vec(&self, version: Version) -> Vec<u8> {
+        let mut buf = vec![];
+        self.serialize_with_version(&mut Cursor::new(&mut buf), version);
+        buf
+    }
+}
+
 /// `FromBytes` should be used to parse an array of bytes into a structure.
 pub trait FromBytes {
     /// It gets and array of bytes and should return an implementor struct.
@@ -0,0 +1,119 @@
+use std::fmt::{Display, Formatter};
+
+/// Server warning sent along with a response, classified by known message patterns. Unknown
+/// warnings are kept as `Other`. Every variant retains the original message, which is what
+/// gets displayed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Warning {
+    /// A read scanned more tombstones than `tombstone_warn_threshold`. The counts are parsed
+    /// from the message, if present in expected form.
+    TombstoneThreshold {
+        live_rows: Option<u64>,
+        tombstone_cells: Option<u64>,
+        message: String,
+    },
+    /// A batch is larger than `batch_size_warn_threshold`.
+    BatchSizeThreshold(String),
+    /// An unlogged batch spans more partitions than
+    /// `unlogged_batch_across_partitions_warn_threshold`.
+    UnloggedBatchAcrossPartitions(String),
+    /// Any other warning.
+    Other(String),
+}
+
+impl Warning {
+    /// Returns the original warning message.
+    pub fn message(&self) -> &str {
+        match self {
+            Warning::TombstoneThreshold { message, .. } => message,
+            Warning::BatchSizeThreshold(message)
+            | Warning::UnloggedBatchAcrossPartitions(message)
+            | Warning::Other(message) => message,
+        }
+    }
+
+    fn parse_tombstone_counts(message: &str) -> (Option<u64>, Option<u64>) {
+        // e.g. "Read 10 live rows and 1001 tombstone cells for query ..."
+        let counts = message
+            .strip_prefix("Read ")
+            .and_then(|rest| rest.split_once(" live rows and "))
+            .map(|(live_rows, rest)| {
+                let tombstone_cells = rest.split(' ').next().and_then(|n| n.parse().ok());
+                (live_rows.parse().ok(), tombstone_cells)
+            });
+
+        counts.unwrap_or((None, None))
+    }
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl From<String> for Warning {
+    fn from(message: String) -> Self {
+        if message.contains("tombstone cells") || message.contains("tombstone_warn_threshold") {
+            let (live_rows, tombstone_cells) = Warning::parse_tombstone_counts(&message);
+            Warning::TombstoneThreshold {
+                live_rows,
+                tombstone_cells,
+                message,
+            }
+        } else if message.starts_with("Unlogged batch covering") {
+            Warning::UnloggedBatchAcrossPartitions(message)
+        } else if message.starts_with("Batch ") && message.contains("exceeding specified threshold")
+        {
+            Warning::BatchSizeThreshold(message)
+        } else {
+            Warning::Other(message)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_tombstone_warning() {
+        let message = "Read 10 live rows and 1001 tombstone cells for query SELECT * FROM ks.t \
+                       WHERE id = 1 LIMIT 100 (see tombstone_warn_threshold)";
+
+        assert_eq!(
+            Warning::from(message.to_string()),
+            Warning::TombstoneThreshold {
+                live_rows: Some(10),
+                tombstone_cells: Some(1001),
+                message: message.to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn classify_batch_warnings() {
+        let batch_size = "Batch for [ks.t] is of size 6.129KiB, exceeding specified threshold of \
+                          5.000KiB by 1.129KiB.";
+        assert_eq!(
+            Warning::from(batch_size.to_string()),
+            Warning::BatchSizeThreshold(batch_size.to_string())
+        );
+
+        let unlogged = "Unlogged batch covering 12 partitions detected against table [ks.t]. You \
+                        should use a logged batch for atomicity, or asynchronous writes for \
+                        performance.";
+        assert_eq!(
+            Warning::from(unlogged.to_string()),
+            Warning::UnloggedBatchAcrossPartitions(unlogged.to_string())
+        );
+
+        let other = Warning::from("Aggregation query used without partition key".to_string());
+        assert_eq!(
+            other.message(),
+            "Aggregation query used without partition key"
+        );
+        assert!(matches!(other, Warning::Other(_)));
+        assert_eq!(other.to_string(), other.message());
+    }
+}
@@ -1,18 +1,41 @@
 //! A generic cassandra protocol crate.
 //! Built in coordination with cdrs-tokio but is flexible for many usecases.
+//!
+//! Without the default `std` feature, only the `constants` and `encoding` modules are available,
+//! which allows encoding values with just `alloc`.
+#![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+#[cfg(feature = "std")]
 #[macro_use]
 mod macros;
 
+#[cfg(feature = "std")]
 pub mod frame;
+#[cfg(feature = "std")]
 pub mod query;
+#[cfg(feature = "std")]
 pub mod types;
 
+#[cfg(feature = "std")]
 pub mod authenticators;
+#[cfg(feature = "std")]
 pub mod compression;
+#[cfg(feature = "std")]
 pub mod consistency;
+pub mod constants;
+pub mod encoding;
+#[cfg(feature = "std")]
 pub mod error;
+#[cfg(feature = "std")]
 pub mod events;
+#[cfg(feature = "std")]
+pub mod prelude;
+#[cfg(feature = "testing")]
+pub mod testing;
 
+#[cfg(feature = "std")]
 pub type Error = error::Error;
+#[cfg(feature = "std")]
 pub type Result<T> = error::Result<T>;
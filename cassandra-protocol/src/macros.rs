@@ -46,7 +46,7 @@ macro_rules! list_as_rust {
                         let type_option_ref = type_option.as_ref();
                         let convert = self
                             .map(|bytes| {
-                                as_rust_type!(type_option_ref, bytes, $($into_type)+)
+                                as_rust_type!(type_option_ref, bytes, self.version, $($into_type)+)
                                     .unwrap()
                                     // item in a list supposed to be a non-null value.
                                     // TODO: check if it's true
@@ -75,8 +75,8 @@ macro_rules! map_as_rust {
                         let val_type_option = val_type_option.as_ref();
 
                         for (key, val) in self.data.iter() {
-                            let key = as_rust_type!(key_type_option, key, $($key_type)+)?;
-                            let val = as_rust_type!(val_type_option, val, $($val_type)+)?;
+                            let key = as_rust_type!(key_type_option, key, self.version, $($key_type)+)?;
+                            let val = as_rust_type!(val_type_option, val, self.version, $($val_type)+)?;
                             if let (Some(key), Some(val)) = (key, val) {
                                 map.insert(key, val);
                             }
@@ -99,7 +99,7 @@ macro_rules! into_rust_by_name {
                     .ok_or(column_is_empty_err(name))
                     .and_then(|(col_spec, cbytes)| {
                         let col_type = &col_spec.col_type;
-                        as_rust_type!(col_type, cbytes, $($into_type)+)
+                        as_rust_type!(col_type, cbytes, self.version, $($into_type)+)
                     })
             }
         }
@@ -111,7 +111,7 @@ macro_rules! into_rust_by_name {
                     .ok_or(column_is_empty_err(name))
                     .and_then(|v| {
                         let &(ref col_type, ref bytes) = v;
-                        let converted = as_rust_type!(col_type, bytes, $($into_type)+);
+                        let converted = as_rust_type!(col_type, bytes, self.version, $($into_type)+);
                         converted.map_err(|err| err.into())
                     })
             }
@@ -128,7 +128,7 @@ macro_rules! into_rust_by_index {
                     .ok_or(column_is_empty_err(index))
                     .and_then(|v| {
                         let &(ref col_type, ref bytes) = v;
-                        let converted = as_rust_type!(col_type, bytes, $($into_type)+);
+                        let converted = as_rust_type!(col_type, bytes, self.version, $($into_type)+);
                         converted.map_err(|err| err.into())
                     })
             }
@@ -141,7 +141,7 @@ macro_rules! into_rust_by_index {
                     .ok_or(column_is_empty_err(index))
                     .and_then(|(col_spec, cbytes)| {
                         let col_type = &col_spec.col_type;
-                        as_rust_type!(col_type, cbytes, $($into_type)+)
+                        as_rust_type!(col_type, cbytes, self.version, $($into_type)+)
                     })
             }
         }
@@ -158,37 +158,21 @@ macro_rules! as_res_opt {
 }
 
 /// Decodes any Cassandra data type into the corresponding Rust type,
-/// given the column type as `ColTypeOption` and the value as `CBytes`
-/// plus the matching Rust type.
+/// given the column type as `ColTypeOption`, the value as `CBytes`, the protocol
+/// version the value was received with, plus the matching Rust type.
 macro_rules! as_rust_type {
-    ($data_type_option:ident, $data_value:ident, Blob) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, Blob) => {
         match $data_type_option.id {
-            ColType::Blob => as_res_opt!($data_value, decode_blob),
-            ColType::Custom => {
-                let unmarshal = || {
-                    if let Some(ColTypeOptionValue::CString(value)) = &$data_type_option.value {
-                        if value.as_str() == "org.apache.cassandra.db.marshal.BytesType" {
-                            return as_res_opt!($data_value, decode_blob);
-                        }
-                    }
-
-                    Err(Error::General(format!(
-                        "Invalid conversion. \
-                         Cannot convert marshaled type {:?} into Vec<u8> (valid types: org.apache.cassandra.db.marshal.BytesType).",
-                        $data_type_option
-                    )))
-                };
-
-                unmarshal()
-            }
+            // custom types are opaque to the driver, so their values are passed through as-is
+            ColType::Blob | ColType::Custom => as_res_opt!($data_value, decode_blob),
             _ => Err(Error::General(format!(
                 "Invalid conversion. \
-                 Cannot convert {:?} into Vec<u8> (valid types: Blob).",
+                 Cannot convert {:?} into Vec<u8> (valid types: Blob, Custom).",
                 $data_type_option.id
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, String) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, String) => {
         match $data_type_option.id {
             ColType::Custom => as_res_opt!($data_value, decode_custom),
             ColType::Ascii => as_res_opt!($data_value, decode_ascii),
@@ -204,7 +188,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, bool) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, bool) => {
         match $data_type_option.id {
             ColType::Boolean => as_res_opt!($data_value, decode_boolean),
             ColType::Custom => {
@@ -231,7 +215,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, i64) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, i64) => {
         match $data_type_option.id {
             ColType::Bigint => as_res_opt!($data_value, decode_bigint),
             ColType::Timestamp => as_res_opt!($data_value, decode_timestamp),
@@ -265,7 +249,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, i32) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, i32) => {
         match $data_type_option.id {
             ColType::Int => as_res_opt!($data_value, decode_int),
             ColType::Date => as_res_opt!($data_value, decode_date),
@@ -295,7 +279,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, i16) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, i16) => {
         match $data_type_option.id {
             ColType::Smallint => as_res_opt!($data_value, decode_smallint),
             ColType::Custom => {
@@ -322,7 +306,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, i8) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, i8) => {
         match $data_type_option.id {
             ColType::Tinyint => as_res_opt!($data_value, decode_tinyint),
             ColType::Custom => {
@@ -349,7 +333,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, NonZeroI64) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, NonZeroI64) => {
         match $data_type_option.id {
             ColType::Bigint => {
                 as_res_opt!($data_value, decode_bigint).map(|value| value.and_then(NonZeroI64::new))
@@ -390,7 +374,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, NonZeroI32) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, NonZeroI32) => {
         match $data_type_option.id {
             ColType::Int => {
                 as_res_opt!($data_value, decode_int).map(|value| value.and_then(NonZeroI32::new))
@@ -424,7 +408,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, NonZeroI16) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, NonZeroI16) => {
         match $data_type_option.id {
             ColType::Smallint => as_res_opt!($data_value, decode_smallint)
                 .map(|value| value.and_then(NonZeroI16::new)),
@@ -452,7 +436,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, NonZeroI8) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, NonZeroI8) => {
         match $data_type_option.id {
             ColType::Tinyint => {
                 as_res_opt!($data_value, decode_tinyint).map(|value| value.and_then(NonZeroI8::new))
@@ -481,7 +465,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, f64) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, f64) => {
         match $data_type_option.id {
             ColType::Double => as_res_opt!($data_value, decode_double),
             ColType::Custom => {
@@ -508,7 +492,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, f32) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, f32) => {
         match $data_type_option.id {
             ColType::Float => as_res_opt!($data_value, decode_float),
             ColType::Custom => {
@@ -535,7 +519,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, IpAddr) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, IpAddr) => {
         match $data_type_option.id {
             ColType::Inet => as_res_opt!($data_value, decode_inet),
             ColType::Custom => {
@@ -562,7 +546,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, Uuid) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, Uuid) => {
         match $data_type_option.id {
             ColType::Uuid | ColType::Timeuuid => as_res_opt!($data_value, decode_timeuuid),
             ColType::Custom => {
@@ -590,11 +574,11 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, List) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, List) => {
         match $data_type_option.id {
             ColType::List | ColType::Set => match $data_value.as_slice() {
-                Some(ref bytes) => decode_list(bytes)
-                    .map(|data| Some(List::new($data_type_option.clone(), data)))
+                Some(ref bytes) => decode_list(bytes, $version)
+                    .map(|data| Some(List::new($data_type_option.clone(), data, $version)))
                     .map_err(Into::into),
                 None => Ok(None),
             },
@@ -605,11 +589,11 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, Map) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, Map) => {
         match $data_type_option.id {
             ColType::Map => match $data_value.as_slice() {
-                Some(ref bytes) => decode_map(bytes)
-                    .map(|data| Some(Map::new(data, $data_type_option.clone())))
+                Some(ref bytes) => decode_map(bytes, $version)
+                    .map(|data| Some(Map::new(data, $data_type_option.clone(), $version)))
                     .map_err(Into::into),
                 None => Ok(None),
             },
@@ -620,14 +604,14 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, Udt) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, Udt) => {
         match *$data_type_option {
             ColTypeOption {
                 id: ColType::Udt,
                 value: Some(ColTypeOptionValue::UdtType(ref list_type_option)),
             } => match $data_value.as_slice() {
                 Some(ref bytes) => decode_udt(bytes, list_type_option.descriptions.len())
-                    .map(|data| Some(Udt::new(data, list_type_option)))
+                    .map(|data| Some(Udt::new(data, list_type_option, $version)))
                     .map_err(Into::into),
                 None => Ok(None),
             },
@@ -638,14 +622,14 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, Tuple) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, Tuple) => {
         match *$data_type_option {
             ColTypeOption {
                 id: ColType::Tuple,
                 value: Some(ColTypeOptionValue::TupleType(ref list_type_option)),
             } => match $data_value.as_slice() {
                 Some(ref bytes) => decode_tuple(bytes, list_type_option.types.len())
-                    .map(|data| Some(Tuple::new(data, list_type_option)))
+                    .map(|data| Some(Tuple::new(data, list_type_option, $version)))
                     .map_err(Into::into),
                 None => Ok(None),
             },
@@ -656,7 +640,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, PrimitiveDateTime) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, PrimitiveDateTime) => {
         match $data_type_option.id {
             ColType::Timestamp => match $data_value.as_slice() {
                 Some(ref bytes) => decode_timestamp(bytes)
@@ -676,7 +660,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, Decimal) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, Decimal) => {
         match $data_type_option.id {
             ColType::Decimal => match $data_value.as_slice() {
                 Some(ref bytes) => decode_decimal(bytes).map(Some).map_err(Into::into),
@@ -689,7 +673,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, NaiveDateTime) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, NaiveDateTime) => {
         match $data_type_option.id {
             ColType::Timestamp => match $data_value.as_slice() {
                 Some(ref bytes) => decode_timestamp(bytes)
@@ -706,7 +690,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, DateTime<Utc>) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, DateTime<Utc>) => {
         match $data_type_option.id {
             ColType::Timestamp => match $data_value.as_slice() {
                 Some(ref bytes) => decode_timestamp(bytes)
@@ -729,7 +713,7 @@ macro_rules! as_rust_type {
             ))),
         }
     };
-    ($data_type_option:ident, $data_value:ident, BigInt) => {
+    ($data_type_option:ident, $data_value:ident, $version:expr, BigInt) => {
         match $data_type_option.id {
             ColType::Custom => {
                 let unmarshal = || {
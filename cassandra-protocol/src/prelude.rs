@@ -0,0 +1,35 @@
+//! Re-exports of the most commonly used types and traits.
+//!
+//! ```
+//! use cassandra_protocol::prelude::*;
+//!
+//! let params = QueryParamsBuilder::new()
+//!     .consistency(Consistency::LocalQuorum)
+//!     .values(QueryValues::SimpleValues(vec![Value::new(1_i32)]))
+//!     .finalize();
+//!
+//! let frame = Frame::new_query(
+//!     Query {
+//!         query: "SELECT * FROM ks.tbl WHERE id = ?".into(),
+//!         params,
+//!     },
+//!     Flags::empty(),
+//!     Version::V4,
+//! );
+//!
+//! let encoded = Consistency::LocalQuorum.serialize_to_vec();
+//! let decoded = Consistency::from_cursor(&mut std::io::Cursor::new(encoded.as_slice())).unwrap();
+//! assert_eq!(decoded, Consistency::LocalQuorum);
+//! assert_eq!(frame.opcode, Opcode::Query);
+//! ```
+
+pub use crate::consistency::Consistency;
+pub use crate::frame::{
+    Direction, Flags, Frame, FromBytes, FromCursor, IntoQueryValues, Opcode, Serialize,
+    SerializeWithVersion, Version,
+};
+pub use crate::query::{
+    BatchQueryBuilder, PreparedQuery, Query, QueryBatch, QueryFlags, QueryParams,
+    QueryParamsBuilder, QueryValues,
+};
+pub use crate::types::prelude::*;
@@ -11,7 +11,7 @@ pub use crate::query::prepared_query::PreparedQuery;
 pub use crate::query::query_flags::QueryFlags;
 pub use crate::query::query_params::QueryParams;
 pub use crate::query::query_params_builder::QueryParamsBuilder;
-pub use crate::query::query_values::QueryValues;
+pub use crate::query::query_values::{QueryValues, TupleValues};
 
 /// Structure that represents CQL query and parameters which will be applied during
 /// its execution
@@ -1,6 +1,7 @@
 use crate::consistency::Consistency;
 use crate::error::{Error as CError, Result as CResult};
 use crate::frame::frame_batch::{BatchQuery, BatchQuerySubj, BatchType, BodyReqBatch};
+use crate::query::query_values::MAX_VALUES_COUNT;
 use crate::query::{PreparedQuery, QueryFlags, QueryValues};
 use crate::types::CStringLong;
 
@@ -15,6 +16,9 @@ pub struct BatchQueryBuilder {
     timestamp: Option<i64>,
     is_idempotent: bool,
     keyspace: Option<String>,
+    now_in_seconds: Option<i32>,
+    compression_disabled: bool,
+    max_values_bytes: Option<usize>,
 }
 
 impl Default for BatchQueryBuilder {
@@ -27,6 +31,9 @@ impl Default for BatchQueryBuilder {
             timestamp: None,
             is_idempotent: false,
             keyspace: None,
+            now_in_seconds: None,
+            compression_disabled: false,
+            max_values_bytes: None,
         }
     }
 }
@@ -44,7 +51,6 @@ impl BatchQueryBuilder {
     /// Add a query (non-prepared one)
     pub fn add_query<T: Into<String>>(mut self, query: T, values: QueryValues) -> Self {
         self.queries.push(BatchQuery {
-            is_prepared: false,
             subject: BatchQuerySubj::QueryString(CStringLong::new(query.into())),
             values,
         });
@@ -54,7 +60,6 @@ impl BatchQueryBuilder {
     /// Add a query (prepared one)
     pub fn add_query_prepared(mut self, query: PreparedQuery, values: QueryValues) -> Self {
         self.queries.push(BatchQuery {
-            is_prepared: true,
             subject: BatchQuerySubj::PreparedId(query),
             values,
         });
@@ -72,25 +77,61 @@ impl BatchQueryBuilder {
     }
 
     builder_opt_field!(serial_consistency, Consistency);
+    // Sets default timestamp in microseconds.
     builder_opt_field!(timestamp, i64);
     builder_opt_field!(keyspace, String);
+    // Sets the reference time in seconds, sent with protocol v5 and above.
+    builder_opt_field!(now_in_seconds, i32);
+    // Sets maximum total size of serialized values of all batch queries.
+    builder_opt_field!(max_values_bytes, usize);
 
     pub fn idempotent(mut self, value: bool) -> Self {
         self.is_idempotent = value;
         self
     }
 
+    /// Forces sending the batch uncompressed, even if the connection uses compression.
+    pub fn disable_compression(mut self) -> Self {
+        self.compression_disabled = true;
+        self
+    }
+
     pub fn finalize(self) -> CResult<BodyReqBatch> {
-        let mut flags = QueryFlags::empty();
+        if self.queries.len() > MAX_VALUES_COUNT {
+            return Err(CError::General(format!(
+                "Too many batch queries: {}, maximum is {}",
+                self.queries.len(),
+                MAX_VALUES_COUNT
+            )));
+        }
 
-        if self.serial_consistency.is_some() {
-            flags.insert(QueryFlags::WITH_SERIAL_CONSISTENCY);
+        for query in &self.queries {
+            match query.subject {
+                BatchQuerySubj::QueryString(_) => query.values.validate_for_query()?,
+                BatchQuerySubj::PreparedId(_) => query.values.validate()?,
+            }
         }
 
-        if self.timestamp.is_some() {
-            flags.insert(QueryFlags::WITH_DEFAULT_TIMESTAMP);
+        if let Some(max_values_bytes) = self.max_values_bytes {
+            let size: usize = self
+                .queries
+                .iter()
+                .map(|query| query.values.serialized_size())
+                .sum();
+
+            if size > max_values_bytes {
+                return Err(CError::General(format!(
+                    "Batch values take {} bytes, maximum is {}",
+                    size, max_values_bytes
+                )));
+            }
         }
 
+        Consistency::validate_levels(self.consistency, self.serial_consistency)?;
+
+        // serial consistency and timestamp flags are derived from the values when serializing
+        let mut flags = QueryFlags::empty();
+
         let with_names_for_values = self.queries.iter().all(|q| q.values.has_names());
 
         if !with_names_for_values {
@@ -117,6 +158,68 @@ impl BatchQueryBuilder {
             timestamp: self.timestamp,
             is_idempotent: self.is_idempotent,
             keyspace: self.keyspace,
+            now_in_seconds: self.now_in_seconds,
+            compression_disabled: self.compression_disabled,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::value::Value;
+    use crate::types::CBytesShort;
+
+    #[test]
+    fn batch_validate_consistency() {
+        assert!(BatchQueryBuilder::new()
+            .consistency(Consistency::Serial)
+            .finalize()
+            .is_err());
+        assert!(BatchQueryBuilder::new()
+            .serial_consistency(Consistency::Quorum)
+            .finalize()
+            .is_err());
+        assert!(BatchQueryBuilder::new()
+            .serial_consistency(Consistency::Serial)
+            .finalize()
+            .is_ok());
+    }
+
+    #[test]
+    fn batch_validate_not_set() {
+        let values = QueryValues::SimpleValues(vec![Value::NotSet]);
+
+        assert!(BatchQueryBuilder::new()
+            .add_query("INSERT", values.clone())
+            .finalize()
+            .is_err());
+        assert!(BatchQueryBuilder::new()
+            .add_query_prepared(
+                PreparedQuery {
+                    id: CBytesShort::new(vec![1]),
+                    result_metadata_id: None,
+                    query: "INSERT".into(),
+                    keyspace: None,
+                    pk_indexes: vec![],
+                },
+                values
+            )
+            .finalize()
+            .is_ok());
+    }
+
+    #[test]
+    fn batch_validate_max_values_bytes() {
+        let values = || QueryValues::SimpleValues(vec![Value::Some(vec![0; 100])]);
+        let batch = || {
+            BatchQueryBuilder::new()
+                .add_query("INSERT", values())
+                .add_query("INSERT", values())
+        };
+
+        assert!(batch().max_values_bytes(207).finalize().is_err());
+        assert!(batch().max_values_bytes(208).finalize().is_ok());
+        assert!(batch().finalize().is_ok());
+    }
+}
@@ -1,9 +1,49 @@
+use crate::error::{Error, Result};
 use crate::types::CBytesShort;
 
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct PreparedQuery {
     pub id: CBytesShort,
+    /// Id of the result metadata, sent when executing with protocol v5 and above.
+    pub result_metadata_id: Option<CBytesShort>,
     pub query: String,
     pub keyspace: Option<String>,
     pub pk_indexes: Vec<i16>,
 }
+
+impl PreparedQuery {
+    /// Checks if given per-query keyspace override is compatible with the keyspace this query
+    /// was prepared against. The server rejects executing a prepared statement in a different
+    /// keyspace, so it's better to fail early with a clear message.
+    pub fn validate_keyspace(&self, keyspace: Option<&str>) -> Result<()> {
+        match (self.keyspace.as_deref(), keyspace) {
+            (Some(prepared_keyspace), Some(keyspace)) if prepared_keyspace != keyspace => {
+                Err(Error::General(format!(
+                    "Query keyspace '{}' differs from prepared statement keyspace '{}'",
+                    keyspace, prepared_keyspace
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_keyspace_mismatch() {
+        let query = PreparedQuery {
+            id: CBytesShort::new(vec![1]),
+            result_metadata_id: None,
+            query: "SELECT * FROM t".into(),
+            keyspace: Some("ks".into()),
+            pk_indexes: vec![],
+        };
+
+        assert!(query.validate_keyspace(None).is_ok());
+        assert!(query.validate_keyspace(Some("ks")).is_ok());
+        assert!(query.validate_keyspace(Some("other")).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use bitflags::bitflags;
 
 bitflags! {
-    pub struct QueryFlags: u8 {
+    pub struct QueryFlags: u32 {
         /// If set indicates that Query Params contains value.
         const VALUE = 0x01;
         /// If set indicates that Query Params does not contain metadata.
@@ -16,6 +16,10 @@ bitflags! {
         const WITH_DEFAULT_TIMESTAMP = 0x20;
         /// If set indicates that Query Params values are named ones.
         const WITH_NAMES_FOR_VALUES = 0x40;
+        /// If set indicates that Query Params contains keyspace name (v5+).
+        const WITH_KEYSPACE = 0x80;
+        /// If set indicates that Query Params contains "now in seconds" value (v5+).
+        const WITH_NOW_IN_SECONDS = 0x100;
     }
 }
 
@@ -1,16 +1,19 @@
 use derive_more::Constructor;
+use num::bigint::Sign;
+use num::BigInt;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::io::{Cursor, Read};
 
 use crate::consistency::Consistency;
 use crate::frame::traits::FromCursor;
-use crate::frame::Serialize;
+use crate::frame::{Serialize, SerializeWithVersion, Version};
 use crate::query::query_flags::QueryFlags;
 use crate::query::query_values::QueryValues;
+use crate::types::paging_state::PagingState;
 use crate::types::value::Value;
-use crate::types::CIntShort;
-use crate::types::{CBytes, CString};
+use crate::types::{serialize_str, CString};
+use crate::types::{CInt, CIntShort};
 use crate::Error;
 
 /// Parameters of Query for query operation.
@@ -25,16 +28,18 @@ pub struct QueryParams {
     /// Page size.
     pub page_size: Option<i32>,
     /// Array of bytes which represents paging state.
-    pub paging_state: Option<CBytes>,
+    pub paging_state: Option<PagingState>,
     /// Serial `Consistency`.
     pub serial_consistency: Option<Consistency>,
-    /// Timestamp.
+    /// Default timestamp in microseconds since the Unix epoch (see `utils::now_micros`). Note
+    /// that `timestamp` column values use milliseconds instead.
     pub timestamp: Option<i64>,
     /// Is the query idempotent.
     pub is_idempotent: bool,
     /// Query keyspace. If not using a global one, setting it explicitly might help the load
     /// balancer use more appropriate nodes. Note: prepared statements with keyspace information
-    /// take precedence over this field.
+    /// take precedence over this field. With protocol v5 and above, it is also sent to the
+    /// server, which executes the query in given keyspace instead of the current one.
     pub keyspace: Option<String>,
     /// The token to use for token-aware routing. A load balancer may use this information to
     /// determine which nodes to contact. Takes precedence over `routing_key`.
@@ -43,13 +48,55 @@ pub struct QueryParams {
     /// to determine which nodes to contact. Alternative to `token`. Note: prepared statements
     /// with bound primary key values take precedence over this field.
     pub routing_key: Option<Vec<Value>>,
+    /// Reference time in seconds for TTL and tombstone evaluation, used instead of the current
+    /// server time. Only sent with protocol v5 and above.
+    pub now_in_seconds: Option<i32>,
+    /// Forces sending the request uncompressed, regardless of connection compression settings.
+    pub compression_disabled: bool,
+    /// Maximum number of bytes the serialized values can take. Requests with larger values are
+    /// rejected before being serialized.
+    pub max_values_bytes: Option<usize>,
 }
 
 impl QueryParams {
-    fn flags(&self) -> QueryFlags {
+    /// Returns the params with given page size, leaving everything else unchanged. Fails for
+    /// non-positive sizes, which the server would reject.
+    pub fn with_page_size(self, page_size: i32) -> Result<Self, Error> {
+        if page_size <= 0 {
+            return Err(Error::General(format!(
+                "Page size must be positive, got {}",
+                page_size
+            )));
+        }
+
+        Ok(QueryParams {
+            page_size: Some(page_size),
+            ..self
+        })
+    }
+
+    /// Checks consistency levels for QUERY and EXECUTE. Unlike batches, these accept a serial
+    /// main consistency, which is used for linearizable reads.
+    pub fn validate_consistency(&self) -> Result<(), Error> {
+        Consistency::validate_serial_consistency(self.serial_consistency)
+    }
+
+    // empty values are treated as no values at all, since the count would be redundant
+    #[inline]
+    fn non_empty_values(&self) -> Option<&QueryValues> {
+        self.values.as_ref().filter(|values| !values.is_empty())
+    }
+
+    // an empty paging state points nowhere, so it's omitted like an absent one
+    #[inline]
+    fn non_empty_paging_state(&self) -> Option<&PagingState> {
+        self.paging_state.as_ref().filter(|state| !state.is_empty())
+    }
+
+    fn flags(&self, version: Version) -> QueryFlags {
         let mut flags = QueryFlags::empty();
 
-        if self.values.is_some() {
+        if self.non_empty_values().is_some() {
             flags.insert(QueryFlags::VALUE);
         }
 
@@ -61,7 +108,7 @@ impl QueryParams {
             flags.insert(QueryFlags::PAGE_SIZE);
         }
 
-        if self.paging_state.is_some() {
+        if self.non_empty_paging_state().is_some() {
             flags.insert(QueryFlags::WITH_PAGING_STATE);
         }
 
@@ -73,19 +120,40 @@ impl QueryParams {
             flags.insert(QueryFlags::WITH_DEFAULT_TIMESTAMP);
         }
 
+        if version >= Version::V5 {
+            if self.keyspace.is_some() {
+                flags.insert(QueryFlags::WITH_KEYSPACE);
+            }
+
+            if self.now_in_seconds.is_some() {
+                flags.insert(QueryFlags::WITH_NOW_IN_SECONDS);
+            }
+        }
+
         flags
     }
 }
 
 impl Serialize for QueryParams {
+    #[inline]
     fn serialize(&self, cursor: &mut Cursor<&mut Vec<u8>>) {
+        self.serialize_with_version(cursor, Version::V4);
+    }
+}
+
+impl SerializeWithVersion for QueryParams {
+    fn serialize_with_version(&self, cursor: &mut Cursor<&mut Vec<u8>>, version: Version) {
         let consistency: CIntShort = self.consistency.into();
         consistency.serialize(cursor);
 
-        let flag_bits = self.flags().bits();
-        flag_bits.serialize(cursor);
+        let flag_bits = self.flags(version).bits();
+        if version >= Version::V5 {
+            flag_bits.serialize(cursor);
+        } else {
+            (flag_bits as u8).serialize(cursor);
+        }
 
-        if let Some(values) = &self.values {
+        if let Some(values) = self.non_empty_values() {
             let len = values.len() as CIntShort;
             len.serialize(cursor);
             values.serialize(cursor);
@@ -95,7 +163,7 @@ impl Serialize for QueryParams {
             page_size.serialize(cursor);
         }
 
-        if let Some(paging_state) = &self.paging_state {
+        if let Some(paging_state) = self.non_empty_paging_state() {
             paging_state.serialize(cursor);
         }
 
@@ -107,23 +175,47 @@ impl Serialize for QueryParams {
         if let Some(timestamp) = self.timestamp {
             timestamp.serialize(cursor);
         }
+
+        if version >= Version::V5 {
+            if let Some(keyspace) = &self.keyspace {
+                serialize_str(cursor, keyspace);
+            }
+
+            if let Some(now_in_seconds) = self.now_in_seconds {
+                now_in_seconds.serialize(cursor);
+            }
+        }
     }
 }
 
 impl FromCursor for QueryParams {
+    #[inline]
     fn from_cursor(cursor: &mut Cursor<&[u8]>) -> Result<QueryParams, Error> {
+        Self::from_cursor_with_version(cursor, Version::V4)
+    }
+}
+
+impl QueryParams {
+    /// Decodes query parameters according to given protocol version, mirroring
+    /// `serialize_with_version`.
+    pub fn from_cursor_with_version(
+        cursor: &mut Cursor<&[u8]>,
+        version: Version,
+    ) -> Result<QueryParams, Error> {
         let consistency = Consistency::from_cursor(cursor)?;
-        let flags = {
+        let flags = if version >= Version::V5 {
+            QueryFlags::from_bits_truncate(CInt::from_cursor(cursor)? as u32)
+        } else {
             let mut buff = [0];
             cursor.read_exact(&mut buff)?;
-            QueryFlags::from_bits_truncate(buff[0])
+            QueryFlags::from_bits_truncate(buff[0] as u32)
         };
 
         let values = if flags.contains(QueryFlags::VALUE) {
             let number_of_values = {
                 let mut buff = [0; 2];
                 cursor.read_exact(&mut buff)?;
-                i16::from_be_bytes(buff)
+                u16::from_be_bytes(buff)
             };
             if flags.contains(QueryFlags::WITH_NAMES_FOR_VALUES) {
                 let mut map = HashMap::with_capacity(number_of_values as usize);
@@ -156,7 +248,7 @@ impl FromCursor for QueryParams {
         };
 
         let paging_state = if flags.contains(QueryFlags::WITH_PAGING_STATE) {
-            Some(CBytes::from_cursor(cursor)?)
+            PagingState::from_cursor_opt(cursor)?
         } else {
             None
         };
@@ -177,13 +269,26 @@ impl FromCursor for QueryParams {
             None
         };
 
+        let keyspace = if flags.contains(QueryFlags::WITH_KEYSPACE) {
+            Some(CString::from_cursor(cursor)?.into_plain())
+        } else {
+            None
+        };
+
+        let now_in_seconds = if flags.contains(QueryFlags::WITH_NOW_IN_SECONDS) {
+            Some(CInt::from_cursor(cursor)?)
+        } else {
+            None
+        };
+
         let with_names = flags.contains(QueryFlags::WITH_NAMES_FOR_VALUES);
 
         // We set these to default values as they arent actually part of the cassandra protocol
         let is_idempotent = false;
-        let keyspace = None;
         let token = None;
         let routing_key = None;
+        let compression_disabled = false;
+        let max_values_bytes = None;
 
         Ok(QueryParams {
             consistency,
@@ -197,6 +302,9 @@ impl FromCursor for QueryParams {
             keyspace,
             token,
             routing_key,
+            now_in_seconds,
+            compression_disabled,
+            max_values_bytes,
         })
     }
 }
@@ -217,3 +325,220 @@ impl TryFrom<String> for Murmur3Token {
             .map(Murmur3Token::new)
     }
 }
+
+/// A token on the ring, depending on the partitioner in use. Murmur3 tokens are signed 64-bit
+/// values, while random partitioner tokens are unsigned 127-bit values, which need to be
+/// compared as big integers. Tokens are only meaningfully comparable within the same
+/// partitioner - Murmur3 tokens are always ordered before random ones.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Debug, Hash)]
+pub enum Token {
+    Murmur3(i64),
+    Random(BigInt),
+}
+
+impl Token {
+    /// Parses a decimal Murmur3 token, as stored in the `tokens` column of system tables.
+    pub fn parse_murmur3(value: &str) -> Result<Token, Error> {
+        value
+            .parse()
+            .map(Token::Murmur3)
+            .map_err(|error| format!("Error parsing Murmur3 token {}: {}", value, error).into())
+    }
+
+    /// Parses a decimal random partitioner token, as stored in the `tokens` column of system
+    /// tables.
+    pub fn parse_random(value: &str) -> Result<Token, Error> {
+        let token: BigInt = value
+            .parse()
+            .map_err(|error| format!("Error parsing random token {}: {}", value, error))?;
+
+        if token.sign() == Sign::Minus {
+            return Err(format!("Negative random token: {}", value).into());
+        }
+
+        Ok(Token::Random(token))
+    }
+}
+
+impl From<Murmur3Token> for Token {
+    #[inline]
+    fn from(token: Murmur3Token) -> Self {
+        Token::Murmur3(token.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_params_validate_consistency() {
+        let serial_read = QueryParams {
+            consistency: Consistency::Serial,
+            ..Default::default()
+        };
+        assert!(serial_read.validate_consistency().is_ok());
+
+        let invalid_serial = QueryParams {
+            consistency: Consistency::One,
+            serial_consistency: Some(Consistency::Quorum),
+            ..Default::default()
+        };
+        assert!(invalid_serial.validate_consistency().is_err());
+    }
+
+    #[test]
+    fn query_params_now_in_seconds_v5() {
+        let params = QueryParams {
+            consistency: Consistency::One,
+            now_in_seconds: Some(10),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            params.serialize_with_version_to_vec(Version::V5),
+            vec![0, 1, 0, 0, 1, 0, 0, 0, 0, 10]
+        );
+        assert_eq!(
+            params.serialize_with_version_to_vec(Version::V4),
+            vec![0, 1, 0]
+        );
+    }
+
+    #[test]
+    fn query_params_v5_roundtrip() {
+        let params = QueryParams {
+            consistency: Consistency::Quorum,
+            values: Some(QueryValues::SimpleValues(vec![Value::new(1)])),
+            page_size: Some(100),
+            timestamp: Some(5),
+            keyspace: Some("ks".into()),
+            now_in_seconds: Some(10),
+            ..Default::default()
+        };
+
+        let encoded = params.serialize_with_version_to_vec(Version::V5);
+        let decoded =
+            QueryParams::from_cursor_with_version(&mut Cursor::new(&encoded), Version::V5).unwrap();
+        assert_eq!(decoded, params);
+
+        let encoded = params.serialize_with_version_to_vec(Version::V4);
+        let decoded = QueryParams::from_cursor(&mut Cursor::new(&encoded)).unwrap();
+        assert_eq!(decoded.keyspace, None);
+        assert_eq!(decoded.now_in_seconds, None);
+        assert_eq!(decoded.timestamp, Some(5));
+    }
+
+    #[test]
+    fn query_params_with_page_size() {
+        assert!(QueryParams::default().with_page_size(0).is_err());
+        assert!(QueryParams::default().with_page_size(-1).is_err());
+
+        let params = QueryParams::default().with_page_size(5000).unwrap();
+        assert!(params.flags(Version::V4).contains(QueryFlags::PAGE_SIZE));
+        assert_eq!(
+            params.serialize_with_version_to_vec(Version::V4),
+            vec![0, 1, 4, 0, 0, 0x13, 0x88]
+        );
+    }
+
+    #[test]
+    fn query_params_default_timestamp() {
+        let params = QueryParams {
+            consistency: Consistency::One,
+            ..Default::default()
+        };
+        assert!(!params
+            .flags(Version::V4)
+            .contains(QueryFlags::WITH_DEFAULT_TIMESTAMP));
+
+        let params = QueryParams {
+            consistency: Consistency::One,
+            timestamp: Some(0x0102030405060708),
+            ..Default::default()
+        };
+        assert!(params
+            .flags(Version::V4)
+            .contains(QueryFlags::WITH_DEFAULT_TIMESTAMP));
+        assert_eq!(
+            params.serialize_to_vec(),
+            vec![0, 1, 0x20, 1, 2, 3, 4, 5, 6, 7, 8]
+        );
+    }
+
+    #[test]
+    fn query_params_empty_values() {
+        let params = QueryParams {
+            consistency: Consistency::One,
+            values: Some(QueryValues::SimpleValues(vec![])),
+            ..Default::default()
+        };
+
+        assert_eq!(params.serialize_to_vec(), vec![0, 1, 0]);
+
+        let params = QueryParams {
+            consistency: Consistency::One,
+            values: Some(QueryValues::SimpleValues(vec![Value::Null])),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            params.serialize_to_vec(),
+            vec![0, 1, 1, 0, 1, 255, 255, 255, 255]
+        );
+    }
+
+    #[test]
+    fn query_params_keyspace() {
+        let params = QueryParams {
+            consistency: Consistency::One,
+            keyspace: Some("ks".into()),
+            now_in_seconds: Some(10),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            params.serialize_with_version_to_vec(Version::V5),
+            vec![0, 1, 0, 0, 1, 0x80, 0, 2, 107, 115, 0, 0, 0, 10]
+        );
+        assert_eq!(
+            params.serialize_with_version_to_vec(Version::V4),
+            vec![0, 1, 0]
+        );
+    }
+
+    #[test]
+    fn random_tokens_order() {
+        let max: BigInt = BigInt::from(2).pow(127) - 1;
+        let mut tokens = vec![
+            Token::Random(max.clone()),
+            Token::Random(BigInt::from(u64::MAX)),
+            Token::Random(BigInt::from(0)),
+            Token::Random(BigInt::from(i64::MAX)),
+        ];
+        tokens.sort();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Random(BigInt::from(0)),
+                Token::Random(BigInt::from(i64::MAX)),
+                Token::Random(BigInt::from(u64::MAX)),
+                Token::Random(max),
+            ]
+        );
+        assert!(Token::from(Murmur3Token::new(-1)) < Token::Murmur3(0));
+    }
+
+    #[test]
+    fn parse_tokens() {
+        assert_eq!(Token::parse_murmur3("-12").unwrap(), Token::Murmur3(-12));
+        assert!(Token::parse_murmur3("abc").is_err());
+
+        assert_eq!(
+            Token::parse_random("170141183460469231731687303715884105727").unwrap(),
+            Token::Random(BigInt::from(2).pow(127) - 1)
+        );
+        assert!(Token::parse_random("-1").is_err());
+    }
+}
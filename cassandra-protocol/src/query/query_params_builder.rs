@@ -1,8 +1,8 @@
 use super::{QueryFlags, QueryParams, QueryValues};
 use crate::consistency::Consistency;
 use crate::query::query_params::Murmur3Token;
+use crate::types::paging_state::PagingState;
 use crate::types::value::Value;
-use crate::types::CBytes;
 
 #[derive(Debug, Default)]
 pub struct QueryParamsBuilder {
@@ -11,13 +11,16 @@ pub struct QueryParamsBuilder {
     values: Option<QueryValues>,
     with_names: bool,
     page_size: Option<i32>,
-    paging_state: Option<CBytes>,
+    paging_state: Option<PagingState>,
     serial_consistency: Option<Consistency>,
     timestamp: Option<i64>,
     is_idempotent: bool,
     keyspace: Option<String>,
     token: Option<Murmur3Token>,
     routing_key: Option<Vec<Value>>,
+    now_in_seconds: Option<i32>,
+    compression_disabled: bool,
+    max_values_bytes: Option<usize>,
 }
 
 impl QueryParamsBuilder {
@@ -50,6 +53,26 @@ impl QueryParamsBuilder {
         self
     }
 
+    /// Appends a positional value. Binding `Value::Null` (or `None`) writes a null, which
+    /// creates a tombstone when used in an insert or update. Positional values cannot be mixed
+    /// with named ones - binding a positional value replaces any named values set before.
+    pub fn bind<V: Into<Value>>(mut self, value: V) -> Self {
+        match &mut self.values {
+            Some(QueryValues::SimpleValues(values)) => values.push(value.into()),
+            _ => {
+                self = self.values(QueryValues::SimpleValues(vec![value.into()]));
+            }
+        }
+
+        self
+    }
+
+    /// Appends a positional value which is left unset. Unlike null, an unset value leaves the
+    /// column unchanged and doesn't create a tombstone. Requires protocol v4 or newer.
+    pub fn bind_unset(self) -> Self {
+        self.bind(Value::NotSet)
+    }
+
     /// Sets the "with names for values" flag
     pub fn with_names(mut self, with_names: bool) -> Self {
         self.with_names = with_names;
@@ -64,8 +87,8 @@ impl QueryParamsBuilder {
         self
     }
 
-    /// Sets new query consistency
-    pub fn paging_state(mut self, state: CBytes) -> Self {
+    /// Sets paging state returned by a previous page of results
+    pub fn paging_state(mut self, state: PagingState) -> Self {
         self.paging_state = Some(state);
         self.flags = self.flags.or(Some(QueryFlags::WITH_PAGING_STATE));
 
@@ -73,10 +96,14 @@ impl QueryParamsBuilder {
     }
 
     builder_opt_field!(serial_consistency, Consistency);
+    // Sets default timestamp in microseconds.
     builder_opt_field!(timestamp, i64);
     builder_opt_field!(keyspace, String);
     builder_opt_field!(token, Murmur3Token);
     builder_opt_field!(routing_key, Vec<Value>);
+    builder_opt_field!(now_in_seconds, i32);
+    // Sets maximum size of serialized values, checked before sending the request.
+    builder_opt_field!(max_values_bytes, usize);
 
     /// Marks the query as idempotent or not
     pub fn idempotent(mut self, value: bool) -> Self {
@@ -84,6 +111,13 @@ impl QueryParamsBuilder {
         self
     }
 
+    /// Forces sending the request uncompressed, even if the connection uses compression. Useful
+    /// for requests known to be incompressible.
+    pub fn disable_compression(mut self) -> Self {
+        self.compression_disabled = true;
+        self
+    }
+
     /// Finalizes query building process and returns query itself
     pub fn finalize(self) -> QueryParams {
         QueryParams {
@@ -98,6 +132,40 @@ impl QueryParamsBuilder {
             keyspace: self.keyspace,
             token: self.token,
             routing_key: self.routing_key,
+            now_in_seconds: self.now_in_seconds,
+            compression_disabled: self.compression_disabled,
+            max_values_bytes: self.max_values_bytes,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::{Flags, Frame, Version};
+    use crate::types::CBytesShort;
+
+    #[test]
+    fn bind_null_and_unset() {
+        let params = QueryParamsBuilder::new()
+            .bind(Value::Null)
+            .bind_unset()
+            .finalize();
+
+        assert_eq!(
+            params.values,
+            Some(QueryValues::SimpleValues(vec![Value::Null, Value::NotSet]))
+        );
+
+        let frame = Frame::new_req_execute(
+            &CBytesShort::new(vec![1]),
+            &params,
+            Flags::empty(),
+            Version::V4,
+        );
+        assert_eq!(
+            frame.body,
+            vec![0, 1, 1, 0, 1, 1, 0, 2, 255, 255, 255, 255, 255, 255, 255, 254]
+        );
+    }
+}
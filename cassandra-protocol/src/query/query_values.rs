@@ -2,24 +2,36 @@ use std::collections::HashMap;
 use std::hash::Hash;
 use std::io::Cursor;
 
-use crate::frame::Serialize;
+use crate::constants::{NOT_SET_LENGTH, NULL_LENGTH};
+use crate::encoding::Decode;
+use crate::error::{Error, Result};
+use crate::frame::{Serialize, Version};
 use crate::types::value::Value;
-use crate::types::CIntShort;
+use crate::types::{CInt, CIntShort, SHORT_LEN};
 
-/// Enum that represents two types of query values:
+/// Maximum number of values which can be bound to a single query, since their count is sent as
+/// a `[short]`.
+pub const MAX_VALUES_COUNT: usize = u16::MAX as usize;
+
+/// Enum that represents three types of query values:
 /// * values without name
 /// * values with names
+/// * already serialized values without name
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum QueryValues {
     SimpleValues(Vec<Value>),
     NamedValues(HashMap<String, Value>),
+    /// Concatenated `[value]`s along with their count, written as-is. Useful for replaying
+    /// queries without re-encoding their values. Validation only walks the length prefixes, so
+    /// value contents are the caller's responsibility.
+    RawValues(Vec<u8>, u16),
 }
 
 impl QueryValues {
     /// Returns `true` if query values is with names and `false` otherwise.
     #[inline]
     pub fn has_names(&self) -> bool {
-        !matches!(*self, QueryValues::SimpleValues(_))
+        matches!(*self, QueryValues::NamedValues(_))
     }
 
     /// Returns the number of values.
@@ -27,6 +39,7 @@ impl QueryValues {
         match *self {
             QueryValues::SimpleValues(ref v) => v.len(),
             QueryValues::NamedValues(ref m) => m.len(),
+            QueryValues::RawValues(_, count) => count as usize,
         }
     }
 
@@ -34,8 +47,189 @@ impl QueryValues {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Checks if the values can be encoded in a frame, i.e. their count fits in `[short]` and
+    /// each value's length fits in `[int]`.
+    pub fn validate(&self) -> Result<()> {
+        if self.len() > MAX_VALUES_COUNT {
+            return Err(Error::General(format!(
+                "Too many query values: {}, maximum is {}",
+                self.len(),
+                MAX_VALUES_COUNT
+            )));
+        }
+
+        match self {
+            QueryValues::RawValues(bytes, count) => raw_value_lengths(bytes, *count).map(|_| ()),
+            _ => self.values().try_for_each(Value::validate),
+        }
+    }
+
+    /// Checks if the values can be sent using given protocol version. Apart from the checks
+    /// done by `validate`, rejects unset values in protocol v3, which doesn't support them.
+    pub fn validate_for_version(&self, version: Version) -> Result<()> {
+        self.validate()?;
+
+        if version < Version::V4 && self.has_not_set()? {
+            return Err(Error::General(format!(
+                "Unset values are not supported in protocol {}",
+                version
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Checks if the values can be sent with a simple (non-prepared) query. Apart from the checks
+    /// done by `validate`, rejects unset values, which are only valid for prepared statements.
+    pub fn validate_for_query(&self) -> Result<()> {
+        self.validate()?;
+
+        if self.has_not_set()? {
+            return Err(Error::General(
+                "Unset values are only allowed when executing prepared statements".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Number of bytes the values take when serialized, including value names.
+    pub fn serialized_size(&self) -> usize {
+        match self {
+            QueryValues::SimpleValues(values) => values.iter().map(Value::serialized_size).sum(),
+            QueryValues::NamedValues(values) => values
+                .iter()
+                .map(|(name, value)| SHORT_LEN + name.len() + value.serialized_size())
+                .sum(),
+            QueryValues::RawValues(bytes, _) => bytes.len(),
+        }
+    }
+
+    /// Checks if the serialized values don't exceed `max_bytes`. Useful for guarding against
+    /// accidentally huge values, e.g. pathological `IN` lists, before they get serialized.
+    pub fn validate_size(&self, max_bytes: usize) -> Result<()> {
+        let size = self.serialized_size();
+        if size > max_bytes {
+            return Err(Error::General(format!(
+                "Query values take {} bytes, maximum is {}",
+                size, max_bytes
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn has_not_set(&self) -> Result<bool> {
+        Ok(match self {
+            QueryValues::RawValues(bytes, count) => {
+                raw_value_lengths(bytes, *count)?.contains(&NOT_SET_LENGTH)
+            }
+            _ => self.values().any(|value| *value == Value::NotSet),
+        })
+    }
+
+    // raw values are checked by walking their length prefixes instead, see `raw_value_lengths`
+    fn values(&self) -> Box<dyn Iterator<Item = &Value> + '_> {
+        match self {
+            QueryValues::SimpleValues(values) => Box::new(values.iter()),
+            QueryValues::NamedValues(values) => Box::new(values.values()),
+            QueryValues::RawValues(..) => Box::new(std::iter::empty()),
+        }
+    }
+}
+
+// Walks the `[int]` length prefixes of concatenated `[value]`s, checking they describe exactly
+// `count` values taking the whole buffer.
+fn raw_value_lengths(mut bytes: &[u8], count: u16) -> Result<Vec<CInt>> {
+    let mut lengths = Vec::with_capacity(count as usize);
+
+    while !bytes.is_empty() {
+        let length = CInt::decode(&mut bytes)
+            .map_err(|error| Error::General(format!("Malformed raw values: {}", error)))?;
+
+        bytes = match length {
+            NULL_LENGTH | NOT_SET_LENGTH => bytes,
+            length if length >= 0 => bytes.get(length as usize..).ok_or_else(|| {
+                Error::General(format!(
+                    "Malformed raw values: value of {} bytes exceeds the buffer",
+                    length
+                ))
+            })?,
+            length => {
+                return Err(Error::General(format!(
+                    "Malformed raw values: invalid value length {}",
+                    length
+                )))
+            }
+        };
+
+        lengths.push(length);
+    }
+
+    if lengths.len() != count as usize {
+        return Err(Error::General(format!(
+            "Raw values contain {} values, but their count is {}",
+            lengths.len(),
+            count
+        )));
+    }
+
+    Ok(lengths)
 }
 
+/// Tuples of values which can be serialized directly into a buffer, skipping building a
+/// `Vec<Value>` first. Useful for performance-sensitive inserts with a fixed number of values.
+pub trait TupleValues: Sized {
+    /// Number of values in the tuple.
+    const LEN: u16;
+
+    /// Appends the `[value]`s to given buffer, without their count.
+    fn write_values(self, buffer: &mut Vec<u8>);
+
+    /// Appends the `[short]` count followed by the `[value]`s to given buffer, as they appear in
+    /// query parameters.
+    fn write_counted_values(self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(&Self::LEN.to_be_bytes());
+        self.write_values(buffer);
+    }
+
+    /// Serializes the values into `QueryValues::RawValues`.
+    fn into_raw_values(self) -> QueryValues {
+        let mut buffer = vec![];
+        self.write_values(&mut buffer);
+        QueryValues::RawValues(buffer, Self::LEN)
+    }
+}
+
+macro_rules! impl_tuple_values {
+    ($len:expr, $($name:ident),+) => {
+        impl<$($name: Into<Value>),+> TupleValues for ($($name,)+) {
+            const LEN: u16 = $len;
+
+            #[allow(non_snake_case)]
+            fn write_values(self, buffer: &mut Vec<u8>) {
+                let ($($name,)+) = self;
+
+                let position = buffer.len() as u64;
+                let mut cursor = Cursor::new(buffer);
+                cursor.set_position(position);
+                $(
+                    let value: Value = $name.into();
+                    value.serialize(&mut cursor);
+                )+
+            }
+        }
+    };
+}
+
+impl_tuple_values!(1, A);
+impl_tuple_values!(2, A, B);
+impl_tuple_values!(3, A, B, C);
+impl_tuple_values!(4, A, B, C, D);
+impl_tuple_values!(5, A, B, C, D, E);
+impl_tuple_values!(6, A, B, C, D, E, F);
+
 impl<T: Into<Value> + Clone> From<Vec<T>> for QueryValues {
     /// It converts values from `Vec` to query values without names `QueryValues::SimpleValues`.
     fn from(values: Vec<T>) -> QueryValues {
@@ -82,6 +276,121 @@ impl Serialize for QueryValues {
                     value.serialize(cursor);
                 }
             }
+            QueryValues::RawValues(bytes, _) => bytes.serialize(cursor),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::SerializeWithVersion;
+    use crate::query::query_params::QueryParams;
+
+    #[test]
+    fn raw_values_serialize_as_simple_values() {
+        let values = vec![Value::new(1), Value::Null, Value::NotSet, Value::new("abc")];
+
+        let simple = QueryValues::SimpleValues(values.clone());
+        let raw = QueryValues::RawValues(simple.serialize_to_vec(), values.len() as u16);
+        assert_eq!(raw.len(), values.len());
+        assert!(!raw.has_names());
+        assert_eq!(raw.serialize_to_vec(), simple.serialize_to_vec());
+
+        let params = |values| QueryParams {
+            values: Some(values),
+            ..Default::default()
+        };
+        assert_eq!(
+            params(raw).serialize_with_version_to_vec(Version::V4),
+            params(simple).serialize_with_version_to_vec(Version::V4)
+        );
+    }
+
+    #[test]
+    fn query_values_validate_count() {
+        assert!(QueryValues::SimpleValues(vec![Value::Null; 70000])
+            .validate()
+            .is_err());
+        assert!(
+            QueryValues::SimpleValues(vec![Value::Null; MAX_VALUES_COUNT])
+                .validate()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn tuple_values_match_simple_values() {
+        let values = || (1i32, "abc", None::<i64>);
+        let simple =
+            QueryValues::SimpleValues(vec![Value::new(1i32), Value::new("abc"), Value::Null]);
+
+        let raw = values().into_raw_values();
+        assert_eq!(raw.len(), 3);
+        assert_eq!(raw.serialize_to_vec(), simple.serialize_to_vec());
+
+        let mut buffer = vec![42];
+        values().write_counted_values(&mut buffer);
+
+        let mut expected = vec![42, 0, 3];
+        expected.extend(simple.serialize_to_vec());
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn query_values_validate_size() {
+        let values = QueryValues::SimpleValues(vec![Value::Some(vec![0; 10]), Value::Null]);
+        assert_eq!(values.serialized_size(), 18);
+        assert!(values.validate_size(18).is_ok());
+        assert!(values.validate_size(17).is_err());
+
+        let named =
+            QueryValues::NamedValues(vec![("a".to_string(), Value::Null)].into_iter().collect());
+        assert_eq!(named.serialized_size(), named.serialize_to_vec().len());
+    }
+
+    #[test]
+    fn query_values_validate_not_set() {
+        let values = QueryValues::SimpleValues(vec![Value::new(1), Value::NotSet]);
+
+        assert!(values.validate_for_version(Version::V3).is_err());
+        assert!(values.validate_for_version(Version::V4).is_ok());
+    }
+
+    #[test]
+    fn raw_values_validate() {
+        let raw = |values: Vec<Value>, count| {
+            QueryValues::RawValues(QueryValues::SimpleValues(values).serialize_to_vec(), count)
+        };
+
+        let values = raw(vec![Value::new(1), Value::Null, Value::NotSet], 3);
+        assert!(values.validate().is_ok());
+        assert!(values.validate_for_version(Version::V4).is_ok());
+        assert!(values.validate_for_version(Version::V3).is_err());
+        assert!(values.validate_for_query().is_err());
+
+        let values = raw(vec![Value::new(1), Value::Null], 2);
+        assert!(values.validate_for_version(Version::V3).is_ok());
+        assert!(values.validate_for_query().is_ok());
+
+        assert!(raw(vec![Value::new(1), Value::Null], 3).validate().is_err());
+        assert!(raw(vec![Value::new(1), Value::Null], 1).validate().is_err());
+        assert!(QueryValues::RawValues(vec![0, 0, 0, 2, 1], 1)
+            .validate()
+            .is_err());
+        assert!(QueryValues::RawValues(vec![0, 0, 0], 1).validate().is_err());
+        assert!(QueryValues::RawValues(vec![255, 255, 255, 253], 1)
+            .validate()
+            .is_err());
+        assert!(QueryValues::RawValues(vec![], 0).validate().is_ok());
+    }
+
+    #[test]
+    fn query_values_validate_not_set_for_query() {
+        let values = QueryValues::SimpleValues(vec![Value::new(1), Value::NotSet]);
+        assert!(values.validate_for_query().is_err());
+
+        let values = QueryValues::SimpleValues(vec![Value::new(1), Value::Null]);
+        assert!(values.validate_for_query().is_ok());
+    }
+}
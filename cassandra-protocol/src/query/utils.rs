@@ -1,3 +1,5 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use crate::frame::Flags;
 
 pub fn prepare_flags(with_tracing: bool, with_warnings: bool) -> Flags {
@@ -14,6 +16,16 @@ pub fn prepare_flags(with_tracing: bool, with_warnings: bool) -> Flags {
     flags
 }
 
+/// Returns current time in microseconds since the Unix epoch, suitable as a query or batch
+/// default timestamp. Not to be confused with `timestamp` column values, which are encoded in
+/// milliseconds.
+pub fn now_micros() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros() as i64
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -27,4 +39,16 @@ mod test {
         assert!(both.contains(Flags::TRACING));
         assert!(both.contains(Flags::WARNING));
     }
+
+    #[test]
+    fn now_micros_unit() {
+        let before = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_micros() as i64;
+        let now = now_micros();
+
+        assert!(now >= before);
+        assert!(now - before < 60_000_000);
+    }
 }
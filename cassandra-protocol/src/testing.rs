@@ -0,0 +1,255 @@
+//! Factories of server response frames, useful for unit testing code which consumes them
+//! without a running Cassandra instance.
+
+use crate::frame::frame_batch::{BatchQuery, BatchQuerySubj, BodyReqBatch};
+use crate::frame::frame_error::{AdditionalErrorInfo, CdrsError};
+use crate::frame::frame_result::{
+    BodyResResultRows, ColSpec, ColType, ColTypeOption, ResResultBody, RowsMetadata,
+    RowsMetadataFlags, TableSpec,
+};
+use crate::frame::{Direction, Flags, Frame, Opcode, Serialize, Version};
+use crate::query::QueryValues;
+use crate::types::rows::Row;
+use crate::types::value::Value;
+use crate::types::{CBytes, CInt, CString, CStringLong};
+use std::sync::Arc;
+
+/// Creates an encoded `RESULT` frame containing given rows.
+pub fn mock_rows_result(version: Version, metadata: RowsMetadata, rows: Vec<Vec<CBytes>>) -> Frame {
+    let body = ResResultBody::Rows(BodyResResultRows {
+        metadata,
+        rows_count: rows.len() as CInt,
+        rows_content: rows,
+        version,
+    });
+
+    mock_response(version, Opcode::Result, body.serialize_to_vec())
+}
+
+/// Creates an encoded `ERROR` frame. `additional_info` must match the error code.
+pub fn mock_error(
+    version: Version,
+    error_code: CInt,
+    message: &str,
+    additional_info: AdditionalErrorInfo,
+) -> Frame {
+    let error = CdrsError {
+        error_code,
+        message: CString::new(message.into()),
+        additional_info,
+        retry_after: None,
+    };
+
+    mock_response(version, Opcode::Error, error.serialize_to_vec())
+}
+
+/// Creates a `RESULT` frame with `rows_count` rows of `columns_count` bigint columns named
+/// `c0`, `c1` etc., representative of a typical query result. Used as a fixture in tests and
+/// benchmarks.
+pub fn fixture_rows_result(version: Version, rows_count: usize, columns_count: usize) -> Frame {
+    let metadata = RowsMetadata {
+        flags: RowsMetadataFlags::GLOBAL_TABLE_SPACE,
+        columns_count: columns_count as CInt,
+        paging_state: None,
+        new_metadata_id: None,
+        global_table_spec: Some(TableSpec {
+            ks_name: CString::new("ks".into()),
+            table_name: CString::new("tbl".into()),
+        }),
+        col_specs: (0..columns_count)
+            .map(|column| ColSpec {
+                table_spec: None,
+                name: CString::new(format!("c{}", column)),
+                col_type: ColTypeOption {
+                    id: ColType::Bigint,
+                    value: None,
+                },
+            })
+            .collect(),
+    };
+
+    let rows = (0..rows_count)
+        .map(|row| {
+            (0..columns_count)
+                .map(|column| {
+                    CBytes::new(((row * columns_count + column) as i64).serialize_to_vec())
+                })
+                .collect()
+        })
+        .collect();
+
+    mock_rows_result(version, metadata, rows)
+}
+
+/// Creates a batch of `queries_count` inserts with two bound values each. Used as a fixture in
+/// tests and benchmarks.
+pub fn fixture_batch(queries_count: usize) -> BodyReqBatch {
+    (0..queries_count)
+        .map(|index| BatchQuery {
+            subject: BatchQuerySubj::QueryString(CStringLong::new(
+                "INSERT INTO ks.tbl (id, value) VALUES (?, ?)".into(),
+            )),
+            values: QueryValues::SimpleValues(vec![
+                Value::from(index as i64),
+                Value::from(format!("value {}", index)),
+            ]),
+        })
+        .collect()
+}
+
+/// Creates a single row with given columns, described by their names, types and values, e.g. to
+/// test code reading system tables. Rows cannot contain not set values.
+pub fn mock_row(version: Version, columns: Vec<(&str, ColTypeOption, Value)>) -> Row {
+    let (col_specs, row_content): (Vec<_>, Vec<_>) = columns
+        .into_iter()
+        .map(|(name, col_type, value)| {
+            let col_spec = ColSpec {
+                table_spec: None,
+                name: CString::new(name.into()),
+                col_type,
+            };
+
+            let value = match value {
+                Value::Some(bytes) => CBytes::new(bytes),
+                Value::Null => CBytes::new_empty(),
+                Value::NotSet => panic!("Column {} cannot be not set", name),
+            };
+
+            (col_spec, value)
+        })
+        .unzip();
+
+    let metadata = RowsMetadata {
+        flags: RowsMetadataFlags::empty(),
+        columns_count: col_specs.len() as CInt,
+        paging_state: None,
+        new_metadata_id: None,
+        global_table_spec: None,
+        col_specs,
+    };
+
+    Row::new(Arc::new(metadata), row_content, version)
+}
+
+fn mock_response(version: Version, opcode: Opcode, body: Vec<u8>) -> Frame {
+    Frame::new(
+        version,
+        Direction::Response,
+        Flags::empty(),
+        opcode,
+        body,
+        None,
+        vec![],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consistency::Consistency;
+    use crate::frame::frame_error::UnavailableError;
+    use crate::frame::frame_response::ResponseBody;
+
+    #[test]
+    fn mock_rows_result_decodes() {
+        let metadata = RowsMetadata {
+            flags: RowsMetadataFlags::GLOBAL_TABLE_SPACE,
+            columns_count: 1,
+            paging_state: None,
+            new_metadata_id: None,
+            global_table_spec: Some(TableSpec {
+                ks_name: CString::new("ks".into()),
+                table_name: CString::new("tbl".into()),
+            }),
+            col_specs: vec![ColSpec {
+                table_spec: None,
+                name: CString::new("id".into()),
+                col_type: ColTypeOption {
+                    id: ColType::Int,
+                    value: None,
+                },
+            }],
+        };
+        let rows = vec![
+            vec![CBytes::new(vec![0, 0, 0, 1])],
+            vec![CBytes::new(vec![0, 0, 0, 2])],
+        ];
+
+        let frame = mock_rows_result(Version::V4, metadata.clone(), rows.clone());
+        assert_eq!(
+            frame.response_body().unwrap(),
+            ResponseBody::Result(ResResultBody::Rows(BodyResResultRows {
+                metadata,
+                rows_count: 2,
+                rows_content: rows,
+                version: Version::V4,
+            }))
+        );
+    }
+
+    #[test]
+    fn fixture_rows_result_decodes() {
+        let frame = fixture_rows_result(Version::V4, 3, 2);
+        let rows = frame.response_body().unwrap().into_rows().unwrap();
+
+        assert_eq!(rows.len(), 3);
+
+        let value: i64 = rows[2].get("c1").unwrap();
+        assert_eq!(value, 5);
+    }
+
+    #[test]
+    fn mock_row_decodes() {
+        let int_type = ColTypeOption {
+            id: ColType::Int,
+            value: None,
+        };
+
+        let row = mock_row(
+            Version::V4,
+            vec![
+                ("a", int_type.clone(), Value::new(1)),
+                ("b", int_type, Value::Null),
+            ],
+        );
+
+        assert_eq!(row.get::<i32>("a").unwrap(), 1);
+        assert_eq!(row.get_opt::<i32>("b").unwrap(), None);
+    }
+
+    #[test]
+    fn fixture_batch_encodes() {
+        let batch = fixture_batch(2);
+        assert_eq!(batch.queries.len(), 2);
+
+        // logged batch with 2 queries
+        let frame = Frame::new_req_batch(batch, Flags::empty(), Version::V4);
+        assert_eq!(frame.opcode, Opcode::Batch);
+        assert_eq!(&frame.body[..3], &[0, 0, 2]);
+    }
+
+    #[test]
+    fn mock_error_decodes() {
+        let additional_info = AdditionalErrorInfo::Unavailable(UnavailableError {
+            cl: Consistency::Quorum,
+            required: 2,
+            alive: 1,
+        });
+
+        let frame = mock_error(
+            Version::V4,
+            0x1000,
+            "Cannot achieve consistency",
+            additional_info.clone(),
+        );
+        assert_eq!(
+            frame.response_body().unwrap(),
+            ResponseBody::Error(CdrsError {
+                error_code: 0x1000,
+                message: CString::new("Cannot achieve consistency".into()),
+                additional_info,
+                retry_after: None,
+            })
+        );
+    }
+}
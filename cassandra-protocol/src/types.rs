@@ -4,6 +4,8 @@ use std::io::{self, Write};
 use std::io::{Cursor, Read};
 use std::net::{IpAddr, SocketAddr};
 
+use crate::constants::{NOT_SET_LENGTH, NULL_LENGTH};
+use crate::encoding::Encode;
 use crate::error::{column_is_empty_err, Error as CdrsError, Result as CDRSResult};
 use crate::frame::traits::FromCursor;
 use crate::frame::Serialize;
@@ -11,34 +13,45 @@ use crate::types::data_serialization_types::decode_inet;
 
 pub const SHORT_LEN: usize = 2;
 pub const INT_LEN: usize = 4;
+pub const LONG_LEN: usize = 8;
 pub const UUID_LEN: usize = 16;
 
-const NULL_INT_LEN: CInt = -1;
 const NULL_SHORT_LEN: CIntShort = -1;
 
 #[macro_use]
 pub mod blob;
 pub mod data_serialization_types;
 pub mod decimal;
+pub mod decoded_value;
+pub mod duration;
 pub mod from_cdrs;
+#[cfg(feature = "dse")]
+pub mod geo;
 pub mod list;
 pub mod map;
+pub mod paging_state;
 pub mod rows;
+pub mod temporal;
 pub mod tuple;
 pub mod udt;
+pub mod uuid_gen;
 pub mod value;
+pub mod vector;
 
 pub mod prelude {
     pub use crate::error::{Error, Result};
     pub use crate::frame::{TryFromRow, TryFromUdt};
     pub use crate::types::blob::Blob;
     pub use crate::types::decimal::Decimal;
+    pub use crate::types::decoded_value::DecodedValue;
+    pub use crate::types::duration::CqlDuration;
     pub use crate::types::list::List;
     pub use crate::types::map::Map;
     pub use crate::types::rows::Row;
     pub use crate::types::tuple::Tuple;
     pub use crate::types::udt::Udt;
     pub use crate::types::value::{Bytes, Value};
+    pub use crate::types::vector::CqlVector;
     pub use crate::types::AsRustType;
 }
 
@@ -150,6 +163,11 @@ pub fn try_i16_from_bytes(bytes: &[u8]) -> Result<i16, io::Error> {
     Ok(i16::from_be_bytes(convert_to_array(bytes)?))
 }
 
+#[inline]
+pub fn try_i8_from_bytes(bytes: &[u8]) -> Result<i8, io::Error> {
+    Ok(i8::from_be_bytes(convert_to_array(bytes)?))
+}
+
 #[inline]
 pub fn try_f32_from_bytes(bytes: &[u8]) -> Result<f32, io::Error> {
     Ok(f32::from_be_bytes(convert_to_array(bytes)?))
@@ -205,12 +223,66 @@ pub fn to_float_big(f: f64) -> Vec<u8> {
     f.to_be_bytes().into()
 }
 
+/// Appends `[short]` to `buf`, avoiding the allocation of `to_short`.
+#[inline]
+pub fn write_short(buf: &mut Vec<u8>, int: i16) {
+    int.encode(buf);
+}
+
+/// Appends `[int]` to `buf`, avoiding the allocation of `to_int`.
+#[inline]
+pub fn write_int(buf: &mut Vec<u8>, int: i32) {
+    int.encode(buf);
+}
+
+/// Appends `bigint` to `buf`, avoiding the allocation of `to_bigint`.
+#[inline]
+pub fn write_bigint(buf: &mut Vec<u8>, int: i64) {
+    int.encode(buf);
+}
+
+/// Appends unsigned `[short]` to `buf`, avoiding the allocation of `to_u_short`.
+#[inline]
+pub fn write_u_short(buf: &mut Vec<u8>, int: u16) {
+    int.encode(buf);
+}
+
+/// Appends unsigned `[int]` to `buf`, avoiding the allocation of `to_u_int`.
+#[inline]
+pub fn write_u_int(buf: &mut Vec<u8>, int: u32) {
+    int.encode(buf);
+}
+
+/// Appends unsigned 64 bit integer to `buf`, avoiding the allocation of `to_u_big`.
+#[inline]
+pub fn write_u_big(buf: &mut Vec<u8>, int: u64) {
+    int.encode(buf);
+}
+
+/// Appends `float` to `buf`, avoiding the allocation of `to_float`.
+#[inline]
+pub fn write_float(buf: &mut Vec<u8>, f: f32) {
+    f.encode(buf);
+}
+
+/// Appends `double` to `buf`, avoiding the allocation of `to_float_big`.
+#[inline]
+pub fn write_float_big(buf: &mut Vec<u8>, f: f64) {
+    f.encode(buf);
+}
+
 pub fn serialize_str(cursor: &mut Cursor<&mut Vec<u8>>, value: &str) {
     let len = value.len() as CIntShort;
     len.serialize(cursor);
     let _ = cursor.write(value.as_bytes());
 }
 
+fn decode_utf8(bytes: Vec<u8>) -> CDRSResult<String> {
+    String::from_utf8(bytes).map_err(|error| CdrsError::InvalidUtf8 {
+        offset: error.utf8_error().valid_up_to(),
+    })
+}
+
 #[derive(Debug, Clone, Constructor, PartialEq, Ord, PartialOrd, Eq, Hash)]
 pub struct CString {
     string: String,
@@ -249,12 +321,10 @@ impl FromCursor for CString {
         let mut buff = [0; SHORT_LEN];
         cursor.read_exact(&mut buff)?;
 
-        let len = i16::from_be_bytes(buff);
+        let len = u16::from_be_bytes(buff);
         let body_bytes = cursor_next_value(cursor, len as usize)?;
 
-        String::from_utf8(body_bytes)
-            .map_err(Into::into)
-            .map(CString::new)
+        decode_utf8(body_bytes).map(CString::new)
     }
 }
 
@@ -293,9 +363,7 @@ impl FromCursor for CStringLong {
         let len = i32::from_be_bytes(buff);
         let body_bytes = cursor_next_value(cursor, len as usize)?;
 
-        String::from_utf8(body_bytes)
-            .map_err(Into::into)
-            .map(CStringLong::new)
+        decode_utf8(body_bytes).map(CStringLong::new)
     }
 }
 
@@ -329,8 +397,8 @@ impl FromCursor for CStringList {
         let mut buff = [0; SHORT_LEN];
         cursor.read_exact(&mut buff)?;
 
-        let len = i16::from_be_bytes(buff);
-        let mut list = Vec::with_capacity(len as usize * SHORT_LEN);
+        let len = u16::from_be_bytes(buff);
+        let mut list = Vec::with_capacity(len as usize);
         for _ in 0..len {
             list.push(CString::from_cursor(cursor)?);
         }
@@ -382,6 +450,24 @@ impl CBytes {
     pub fn into_bytes(self) -> Option<Vec<u8>> {
         self.bytes
     }
+
+    /// Decodes a value sent in a result, i.e. a row cell or a part of a collection, UDT or tuple.
+    /// "Not set" is only valid in requests, so, unlike `from_cursor`, it's rejected as a protocol
+    /// violation rather than treated as null.
+    pub fn from_result_cursor(cursor: &mut Cursor<&[u8]>) -> CDRSResult<CBytes> {
+        let len = CInt::from_cursor(cursor)?;
+        if len == NOT_SET_LENGTH {
+            return Err(CdrsError::Protocol(
+                "Unexpected not set value in result".into(),
+            ));
+        }
+
+        if len < 0 {
+            return Ok(CBytes { bytes: None });
+        }
+
+        cursor_next_value(cursor, len as usize).map(CBytes::new)
+    }
 }
 
 impl FromCursor for CBytes {
@@ -406,7 +492,7 @@ impl Serialize for CBytes {
                 len.serialize(cursor);
                 bytes.serialize(cursor);
             }
-            None => NULL_INT_LEN.serialize(cursor),
+            None => NULL_LENGTH.serialize(cursor),
         }
     }
 }
@@ -532,7 +618,68 @@ impl FromCursor for CInet {
     }
 }
 
+/// The structure which represents a value of Cassandra inet column, serialized as `[bytes]`
+/// holding 4 (IPv4) or 16 (IPv6) bytes of the address.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Constructor)]
+pub struct Inet {
+    pub addr: IpAddr,
+}
+
+impl Serialize for Inet {
+    fn serialize(&self, cursor: &mut Cursor<&mut Vec<u8>>) {
+        match self.addr {
+            IpAddr::V4(v4) => {
+                to_int(4).serialize(cursor);
+                v4.octets().serialize(cursor);
+            }
+            IpAddr::V6(v6) => {
+                to_int(16).serialize(cursor);
+                v6.octets().serialize(cursor);
+            }
+        }
+    }
+}
+
+impl FromCursor for Inet {
+    fn from_cursor(cursor: &mut Cursor<&[u8]>) -> CDRSResult<Inet> {
+        let len = CInt::from_cursor(cursor)?;
+        if len != 4 && len != 16 {
+            return Err(CdrsError::Protocol(format!(
+                "Invalid inet length: {}, expected 4 or 16",
+                len
+            )));
+        }
+
+        let addr = decode_inet(cursor_next_value(cursor, len as usize)?.as_slice())?;
+        Ok(Inet { addr })
+    }
+}
+
+impl From<IpAddr> for Inet {
+    #[inline]
+    fn from(addr: IpAddr) -> Self {
+        Inet { addr }
+    }
+}
+
+impl From<Inet> for IpAddr {
+    #[inline]
+    fn from(value: Inet) -> Self {
+        value.addr
+    }
+}
+
 pub fn cursor_next_value(cursor: &mut Cursor<&[u8]>, len: usize) -> CDRSResult<Vec<u8>> {
+    // don't trust the length before allocating - it might come from malformed data
+    let remaining = (cursor.get_ref().len() as u64).saturating_sub(cursor.position());
+    if len as u64 > remaining {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("Expected {} bytes, but only {} remaining", len, remaining),
+        )
+        .into());
+    }
+
     let mut buff = vec![0u8; len];
     cursor.read_exact(&mut buff)?;
     Ok(buff)
@@ -541,6 +688,7 @@ pub fn cursor_next_value(cursor: &mut Cursor<&[u8]>, len: usize) -> CDRSResult<V
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::encoding::Bytes;
     use crate::frame::traits::FromCursor;
     use num::BigInt;
     use std::io::Cursor;
@@ -557,6 +705,33 @@ mod tests {
         int.to_signed_bytes_be()
     }
 
+    #[test]
+    fn test_inet_v4_roundtrip() {
+        let inet = Inet::new("127.0.0.1".parse().unwrap());
+        let bytes = inet.serialize_to_vec();
+        assert_eq!(bytes, vec![0, 0, 0, 4, 127, 0, 0, 1]);
+
+        let decoded = Inet::from_cursor(&mut Cursor::new(bytes.as_slice())).unwrap();
+        assert_eq!(decoded, inet);
+    }
+
+    #[test]
+    fn test_inet_v6_roundtrip() {
+        let inet = Inet::new("::1".parse().unwrap());
+        let bytes = inet.serialize_to_vec();
+        assert_eq!(bytes.len(), 4 + 16);
+        assert_eq!(&bytes[..4], &[0, 0, 0, 16]);
+
+        let decoded = Inet::from_cursor(&mut Cursor::new(bytes.as_slice())).unwrap();
+        assert_eq!(decoded, inet);
+    }
+
+    #[test]
+    fn test_inet_invalid_length() {
+        let bytes = [0, 0, 0, 5, 1, 2, 3, 4, 5];
+        assert!(Inet::from_cursor(&mut Cursor::new(&bytes[..])).is_err());
+    }
+
     // CString
     #[test]
     fn test_cstring_new() {
@@ -596,6 +771,14 @@ mod tests {
         assert_eq!(cstring.as_str(), "foo");
     }
 
+    #[test]
+    fn test_cstring_from_cursor_invalid_utf8() {
+        let a = &[0, 3, 102, 0xff, 111];
+        let mut cursor: Cursor<&[u8]> = Cursor::new(a);
+        let result = CString::from_cursor(&mut cursor);
+        assert!(matches!(result, Err(CdrsError::InvalidUtf8 { offset: 1 })));
+    }
+
     // CStringLong
     #[test]
     fn test_cstringlong_new() {
@@ -757,4 +940,57 @@ mod tests {
         assert_eq!(to_varint(BigInt::from(-128)), vec![0x80]);
         assert_eq!(to_varint(BigInt::from(-129)), vec![0xFF, 0x7F]);
     }
+
+    #[test]
+    fn test_write_matches_to() {
+        let mut buf = vec![];
+        for value in [0, 1, -1, i64::MIN, i64::MAX] {
+            write_bigint(&mut buf, value);
+        }
+
+        let expected: Vec<u8> = [0, 1, -1, i64::MIN, i64::MAX]
+            .iter()
+            .flat_map(|value| value.to_be_bytes())
+            .collect();
+        assert_eq!(buf, expected);
+
+        fn written<T>(write: fn(&mut Vec<u8>, T), value: T) -> Vec<u8> {
+            let mut buf = vec![];
+            write(&mut buf, value);
+            buf
+        }
+
+        assert_eq!(written(write_short, -2), to_short(-2));
+        assert_eq!(written(write_int, -2), to_int(-2));
+        assert_eq!(written(write_bigint, -2), to_bigint(-2));
+        assert_eq!(written(write_u_short, 2), to_u_short(2));
+        assert_eq!(written(write_u_int, 2), to_u_int(2));
+        assert_eq!(written(write_u_big, 2), to_u_big(2));
+        assert_eq!(written(write_float, 1.5), to_float(1.5));
+        assert_eq!(written(write_float_big, 1.5), to_float_big(1.5));
+
+        assert_eq!(written(write_short, -2), Bytes::from(-2_i16).0);
+        assert_eq!(written(write_float_big, 1.5), Bytes::from(1.5_f64).0);
+
+        assert_eq!(to_short(-2), (-2_i16).to_be_bytes());
+        assert_eq!(to_int(-2), (-2_i32).to_be_bytes());
+        assert_eq!(to_u_short(2), 2_u16.to_be_bytes());
+        assert_eq!(to_u_int(2), 2_u32.to_be_bytes());
+        assert_eq!(to_u_big(2), 2_u64.to_be_bytes());
+        assert_eq!(to_float(1.5), 1.5_f32.to_be_bytes());
+        assert_eq!(to_float_big(1.5), 1.5_f64.to_be_bytes());
+    }
+
+    #[test]
+    fn test_float_big_endian_bytes() {
+        assert_eq!(to_float(1.0), [0x3f, 0x80, 0, 0]);
+        assert_eq!(to_float(-0.0), [0x80, 0, 0, 0]);
+        assert_eq!(to_float(f32::NAN), [0x7f, 0xc0, 0, 0]);
+        assert_eq!(to_float(f32::INFINITY), [0x7f, 0x80, 0, 0]);
+
+        assert_eq!(to_float_big(1.0), [0x3f, 0xf0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(to_float_big(-0.0), [0x80, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(to_float_big(f64::NAN), [0x7f, 0xf8, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(to_float_big(f64::INFINITY), [0x7f, 0xf0, 0, 0, 0, 0, 0, 0]);
+    }
 }
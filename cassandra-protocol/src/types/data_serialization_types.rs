@@ -1,16 +1,19 @@
 use arrayref::array_ref;
+use chrono::{DateTime, TimeZone, Utc};
 use num::BigInt;
 use std::io;
 use std::net;
 use std::string::FromUtf8Error;
+use time::PrimitiveDateTime;
 
 use super::blob::Blob;
 use super::decimal::Decimal;
+use super::duration::CqlDuration;
 use crate::error;
-use crate::frame::FromCursor;
+use crate::frame::{FromCursor, Version};
 use crate::types::{
     try_f32_from_bytes, try_f64_from_bytes, try_i16_from_bytes, try_i32_from_bytes,
-    try_i64_from_bytes, u16_from_bytes, CBytes, CInt, INT_LEN,
+    try_i64_from_bytes, try_i8_from_bytes, u16_from_bytes, CBytes, CInt, INT_LEN,
 };
 
 // https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec#L813
@@ -48,16 +51,25 @@ pub fn decode_blob(bytes: &[u8]) -> Result<Blob, io::Error> {
     Ok(bytes.into())
 }
 
+// Borrows Cassandra `blob` data from a cell without copying, or returns `None` for null
+#[inline]
+pub fn decode_blob_ref(cell: &CBytes) -> Option<&[u8]> {
+    cell.as_slice()
+}
+
 // Decodes Cassandra `boolean` data (bytes)
 #[inline]
 pub fn decode_boolean(bytes: &[u8]) -> Result<bool, io::Error> {
-    if bytes.is_empty() {
-        Err(io::Error::new(
+    match bytes {
+        [value] => Ok(*value != FALSE_BYTE),
+        [] => Err(io::Error::new(
             io::ErrorKind::UnexpectedEof,
             "no bytes were found",
-        ))
-    } else {
-        Ok(bytes[0] != FALSE_BYTE)
+        )),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid boolean length: {}", bytes.len()),
+        )),
     }
 }
 
@@ -76,6 +88,11 @@ pub fn decode_date(bytes: &[u8]) -> Result<i32, io::Error> {
     try_i32_from_bytes(bytes)
 }
 
+// Decodes Cassandra `duration` data (bytes)
+pub fn decode_duration(bytes: &[u8]) -> Result<CqlDuration, error::Error> {
+    CqlDuration::from_cursor(&mut io::Cursor::new(bytes))
+}
+
 // Decodes Cassandra `decimal` data (bytes)
 pub fn decode_decimal(bytes: &[u8]) -> Result<Decimal, io::Error> {
     let lr = bytes.split_at(INT_LEN);
@@ -134,37 +151,108 @@ pub fn decode_timestamp(bytes: &[u8]) -> Result<i64, io::Error> {
     try_i64_from_bytes(bytes)
 }
 
+// Decodes Cassandra `timestamp` data (bytes) into Rust's `Result<DateTime<Utc>, io::Error>`
+pub fn decode_timestamp_utc(bytes: &[u8]) -> Result<DateTime<Utc>, io::Error> {
+    let ts = decode_timestamp(bytes)?;
+    Utc.timestamp_opt(
+        ts.div_euclid(1_000),
+        (ts.rem_euclid(1_000) * 1_000_000) as u32,
+    )
+    .single()
+    .ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Timestamp out of range: {}", ts),
+        )
+    })
+}
+
+// Decodes Cassandra `timestamp` data (bytes) into Rust's `Result<PrimitiveDateTime, io::Error>`
+pub fn decode_timestamp_primitive(bytes: &[u8]) -> Result<PrimitiveDateTime, io::Error> {
+    let ts = decode_timestamp(bytes)?;
+    let unix_epoch = time::macros::date!(1970 - 01 - 01).midnight();
+    unix_epoch
+        .checked_add(time::Duration::milliseconds(ts))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Timestamp out of range: {}", ts),
+            )
+        })
+}
+
+// Decodes the number of elements of a collection. Since protocol v3, it is an `[int]`, while
+// previous versions used a `[short]`. Pre-v3 protocols are not supported.
+fn decode_collection_len(
+    cursor: &mut io::Cursor<&[u8]>,
+    version: Version,
+) -> Result<usize, io::Error> {
+    let l = match version {
+        Version::V3 | Version::V4 | Version::V5 => CInt::from_cursor(cursor),
+    }
+    .map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Cannot decode collection length: {}", err),
+        )
+    })?;
+    if l < 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid collection length: {}", l),
+        ));
+    }
+
+    Ok(l as usize)
+}
+
+// Initial capacity for a collection of given length. Every element takes at least `[int]` bytes,
+// so a length exceeding what the remaining bytes can hold is not trusted when allocating.
+#[inline]
+fn collection_capacity(cursor: &io::Cursor<&[u8]>, l: usize, element_len: usize) -> usize {
+    let remaining = cursor.get_ref().len() - cursor.position() as usize;
+    l.min(remaining / element_len)
+}
+
+// Decodes a single part of a collection element, adding its position to a potential error.
+fn decode_collection_element(
+    cursor: &mut io::Cursor<&[u8]>,
+    index: usize,
+    part: &str,
+) -> Result<CBytes, io::Error> {
+    CBytes::from_result_cursor(cursor).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Cannot decode {} of element {}: {}", part, index, err),
+        )
+    })
+}
+
 // Decodes Cassandra `list` data (bytes)
-pub fn decode_list(bytes: &[u8]) -> Result<Vec<CBytes>, io::Error> {
+pub fn decode_list(bytes: &[u8], version: Version) -> Result<Vec<CBytes>, io::Error> {
     let mut cursor = io::Cursor::new(bytes);
-    let l = CInt::from_cursor(&mut cursor)
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-    let mut list = Vec::with_capacity(l as usize);
-    for _ in 0..l {
-        let b = CBytes::from_cursor(&mut cursor)
-            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
-        list.push(b);
+    let l = decode_collection_len(&mut cursor, version)?;
+    let mut list = Vec::with_capacity(collection_capacity(&cursor, l, INT_LEN));
+    for index in 0..l {
+        list.push(decode_collection_element(&mut cursor, index, "value")?);
     }
     Ok(list)
 }
 
 // Decodes Cassandra `set` data (bytes)
 #[inline]
-pub fn decode_set(bytes: &[u8]) -> Result<Vec<CBytes>, io::Error> {
-    decode_list(bytes)
+pub fn decode_set(bytes: &[u8], version: Version) -> Result<Vec<CBytes>, io::Error> {
+    decode_list(bytes, version)
 }
 
 // Decodes Cassandra `map` data (bytes)
-pub fn decode_map(bytes: &[u8]) -> Result<Vec<(CBytes, CBytes)>, io::Error> {
+pub fn decode_map(bytes: &[u8], version: Version) -> Result<Vec<(CBytes, CBytes)>, io::Error> {
     let mut cursor = io::Cursor::new(bytes);
-    let l = CInt::from_cursor(&mut cursor)
-        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
-    let mut map = Vec::with_capacity(l as usize);
-    for _ in 0..l {
-        let k = CBytes::from_cursor(&mut cursor)
-            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
-        let v = CBytes::from_cursor(&mut cursor)
-            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    let l = decode_collection_len(&mut cursor, version)?;
+    let mut map = Vec::with_capacity(collection_capacity(&cursor, l, 2 * INT_LEN));
+    for index in 0..l {
+        let k = decode_collection_element(&mut cursor, index, "key")?;
+        let v = decode_collection_element(&mut cursor, index, "value")?;
         map.push((k, v));
     }
     Ok(map)
@@ -179,7 +267,7 @@ pub fn decode_smallint(bytes: &[u8]) -> Result<i16, io::Error> {
 // Decodes Cassandra `tinyint` data (bytes)
 #[inline]
 pub fn decode_tinyint(bytes: &[u8]) -> Result<i8, io::Error> {
-    Ok(bytes[0] as i8)
+    try_i8_from_bytes(bytes)
 }
 
 // Decodes Cassandra `text` data (bytes)
@@ -211,7 +299,7 @@ pub fn decode_udt(bytes: &[u8], l: usize) -> Result<Vec<CBytes>, io::Error> {
     let mut cursor = io::Cursor::new(bytes);
     let mut udt = Vec::with_capacity(l);
     for _ in 0..l {
-        let v = CBytes::from_cursor(&mut cursor)
+        let v = CBytes::from_result_cursor(&mut cursor)
             .or_else(|err| match err {
                 error::Error::Io(io_err) => {
                     if io_err.kind() == io::ErrorKind::UnexpectedEof {
@@ -233,7 +321,7 @@ pub fn decode_tuple(bytes: &[u8], l: usize) -> Result<Vec<CBytes>, io::Error> {
     let mut cursor = io::Cursor::new(bytes);
     let mut tuple = Vec::with_capacity(l);
     for _ in 0..l {
-        let v = CBytes::from_cursor(&mut cursor)
+        let v = CBytes::from_result_cursor(&mut cursor)
             .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
         tuple.push(v);
     }
@@ -245,7 +333,8 @@ mod tests {
     use super::super::super::error::*;
     use super::super::super::frame::frame_result::*;
     use super::*;
-    use crate::types::{to_float, to_float_big, CString};
+    use crate::types::{to_bigint, to_float, to_float_big, CString};
+    use chrono::SecondsFormat;
     use float_eq::*;
     use std::net::IpAddr;
 
@@ -277,11 +366,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn decode_blob_ref_test() {
+        let cell = CBytes::new(vec![1, 2, 3]);
+        let blob = decode_blob_ref(&cell).unwrap();
+        assert_eq!(blob, &[1, 2, 3]);
+        assert_eq!(blob.as_ptr(), cell.as_slice().unwrap().as_ptr());
+
+        assert_eq!(decode_blob_ref(&CBytes::new_empty()), None);
+    }
+
     #[test]
     fn decode_boolean_test() {
         assert!(!decode_boolean(&[0]).unwrap());
         assert!(decode_boolean(&[1]).unwrap());
         assert!(decode_boolean(&[]).is_err());
+        assert!(decode_boolean(&[0, 1]).is_err());
     }
 
     #[test]
@@ -334,36 +434,118 @@ mod tests {
         assert_eq!(decode_timestamp(&[0, 0, 0, 0, 0, 0, 0, 3]).unwrap(), 3);
     }
 
+    #[test]
+    fn decode_timestamp_utc_test() {
+        // 2021-05-04T12:30:00.250Z
+        let bytes = to_bigint(1_620_131_400_250);
+        assert_eq!(
+            decode_timestamp_utc(&bytes)
+                .unwrap()
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+            "2021-05-04T12:30:00.250Z"
+        );
+
+        let bytes = to_bigint(-1);
+        assert_eq!(
+            decode_timestamp_utc(&bytes)
+                .unwrap()
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+            "1969-12-31T23:59:59.999Z"
+        );
+    }
+
+    #[test]
+    fn decode_timestamp_primitive_test() {
+        let bytes = to_bigint(1_620_131_400_250);
+        assert_eq!(
+            decode_timestamp_primitive(&bytes).unwrap(),
+            time::macros::datetime!(2021-05-04 12:30:00.250)
+        );
+    }
+
     #[test]
     fn decode_list_test() {
-        let results = decode_list(&[0, 0, 0, 1, 0, 0, 0, 2, 1, 2]).unwrap();
+        let results = decode_list(&[0, 0, 0, 1, 0, 0, 0, 2, 1, 2], Version::V4).unwrap();
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].as_slice().unwrap(), &[1, 2]);
     }
 
+    #[test]
+    fn decode_list_int_count_test() {
+        for version in [Version::V3, Version::V4, Version::V5] {
+            // a [short] count would read 0 elements here
+            let results =
+                decode_list(&[0, 0, 0, 2, 0, 0, 0, 1, 1, 0, 0, 0, 1, 2], version).unwrap();
+            assert_eq!(results.len(), 2);
+            assert_eq!(results[1].as_slice().unwrap(), &[2]);
+
+            assert!(decode_list(&[255, 255, 255, 255], version).is_err());
+            assert!(decode_map(&[255, 255, 255, 255], version).is_err());
+        }
+    }
+
+    #[test]
+    fn decode_collection_huge_count_test() {
+        let bytes = [127, 255, 255, 255, 0, 0, 0, 1, 1];
+        let cursor = io::Cursor::new(&bytes[4..]);
+        assert_eq!(collection_capacity(&cursor, i32::MAX as usize, INT_LEN), 1);
+        assert_eq!(
+            collection_capacity(&cursor, i32::MAX as usize, 2 * INT_LEN),
+            0
+        );
+
+        assert!(decode_list(&bytes, Version::V4).is_err());
+        assert!(decode_map(&bytes, Version::V4).is_err());
+    }
+
     #[test]
     fn decode_set_test() {
-        let results = decode_set(&[0, 0, 0, 1, 0, 0, 0, 2, 1, 2]).unwrap();
+        let results = decode_set(&[0, 0, 0, 1, 0, 0, 0, 2, 1, 2], Version::V4).unwrap();
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].as_slice().unwrap(), &[1, 2]);
     }
 
     #[test]
     fn decode_map_test() {
-        let results = decode_map(&[0, 0, 0, 1, 0, 0, 0, 2, 1, 2, 0, 0, 0, 2, 2, 1]).unwrap();
+        let results = decode_map(
+            &[0, 0, 0, 1, 0, 0, 0, 2, 1, 2, 0, 0, 0, 2, 2, 1],
+            Version::V4,
+        )
+        .unwrap();
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].0.as_slice().unwrap(), &[1, 2]);
         assert_eq!(results[0].1.as_slice().unwrap(), &[2, 1]);
     }
 
+    #[test]
+    fn decode_map_truncated_test() {
+        let error = decode_map(
+            &[
+                0, 0, 0, 2, // 2 elements
+                0, 0, 0, 1, 1, 0, 0, 0, 1, 2, // element 0
+                0, 0, 0, 1, 3, 0, 0, 0, 2, 4, // element 1, value truncated
+            ],
+            Version::V4,
+        )
+        .unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("value of element 1"));
+    }
+
     #[test]
     fn decode_smallint_test() {
         assert_eq!(decode_smallint(&[0, 10]).unwrap(), 10);
+        assert_eq!(decode_smallint(&[0xff, 0xfe]).unwrap(), -2);
+        assert!(decode_smallint(&[0, 0, 0, 10]).is_err());
     }
 
     #[test]
     fn decode_tinyint_test() {
         assert_eq!(decode_tinyint(&[10]).unwrap(), 10);
+        assert_eq!(decode_tinyint(&[0xfe]).unwrap(), -2);
+        assert!(decode_tinyint(&[]).is_err());
+        assert!(decode_tinyint(&[0, 10]).is_err());
     }
 
     #[test]
@@ -433,6 +615,27 @@ mod tests {
         assert_eq!(udt[0].as_slice().unwrap(), &[1, 2]);
     }
 
+    #[test]
+    fn decode_not_set_element_test() {
+        let not_set = [255, 255, 255, 254];
+
+        let mut list = vec![0, 0, 0, 1];
+        list.extend_from_slice(&not_set);
+        let error = decode_list(&list, Version::V4).unwrap_err();
+        assert!(error.to_string().contains("not set"), "{}", error);
+
+        let mut map = vec![0, 0, 0, 1, 0, 0, 0, 1, 1];
+        map.extend_from_slice(&not_set);
+        assert!(decode_map(&map, Version::V4).is_err());
+
+        assert!(decode_udt(&not_set, 1).is_err());
+        assert!(decode_tuple(&not_set, 1).is_err());
+
+        // null is still fine
+        let udt = decode_udt(&[255, 255, 255, 255], 1).unwrap();
+        assert_eq!(udt[0].as_slice(), None);
+    }
+
     #[test]
     fn as_rust_blob_test() {
         let d_type = ColTypeOption {
@@ -441,7 +644,7 @@ mod tests {
         };
         let data = CBytes::new(vec![1, 2, 3]);
         assert_eq!(
-            as_rust_type!(d_type, data, Blob)
+            as_rust_type!(d_type, data, Version::V4, Blob)
                 .unwrap()
                 .unwrap()
                 .into_vec(),
@@ -451,7 +654,7 @@ mod tests {
             id: ColType::Map,
             value: None,
         };
-        assert!(as_rust_type!(wrong_type, data, Blob).is_err());
+        assert!(as_rust_type!(wrong_type, data, Version::V4, Blob).is_err());
     }
 
     #[test]
@@ -464,7 +667,25 @@ mod tests {
         };
         let data = CBytes::new(vec![1, 2, 3]);
         assert_eq!(
-            as_rust_type!(d_type, data, Blob)
+            as_rust_type!(d_type, data, Version::V4, Blob)
+                .unwrap()
+                .unwrap()
+                .into_vec(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn as_rust_custom_blob_test() {
+        let d_type = ColTypeOption {
+            id: ColType::Custom,
+            value: Some(ColTypeOptionValue::CString(CString::new(
+                "com.example.CustomType".into(),
+            ))),
+        };
+        let data = CBytes::new(vec![1, 2, 3]);
+        assert_eq!(
+            as_rust_type!(d_type, data, Version::V4, Blob)
                 .unwrap()
                 .unwrap()
                 .into_vec(),
@@ -488,22 +709,28 @@ mod tests {
         };
         let data = CBytes::new(b"abc".to_vec());
         assert_eq!(
-            as_rust_type!(type_custom, data, String).unwrap().unwrap(),
+            as_rust_type!(type_custom, data, Version::V4, String)
+                .unwrap()
+                .unwrap(),
             "abc"
         );
         assert_eq!(
-            as_rust_type!(type_ascii, data, String).unwrap().unwrap(),
+            as_rust_type!(type_ascii, data, Version::V4, String)
+                .unwrap()
+                .unwrap(),
             "abc"
         );
         assert_eq!(
-            as_rust_type!(type_varchar, data, String).unwrap().unwrap(),
+            as_rust_type!(type_varchar, data, Version::V4, String)
+                .unwrap()
+                .unwrap(),
             "abc"
         );
         let wrong_type = ColTypeOption {
             id: ColType::Map,
             value: None,
         };
-        assert!(as_rust_type!(wrong_type, data, String).is_err());
+        assert!(as_rust_type!(wrong_type, data, Version::V4, String).is_err());
     }
 
     #[test]
@@ -514,17 +741,17 @@ mod tests {
         };
         let data_true = CBytes::new(vec![1]);
         let data_false = CBytes::new(vec![0]);
-        assert!(as_rust_type!(type_boolean, data_true, bool)
+        assert!(as_rust_type!(type_boolean, data_true, Version::V4, bool)
             .unwrap()
             .unwrap());
-        assert!(!as_rust_type!(type_boolean, data_false, bool)
+        assert!(!as_rust_type!(type_boolean, data_false, Version::V4, bool)
             .unwrap()
             .unwrap());
         let wrong_type = ColTypeOption {
             id: ColType::Map,
             value: None,
         };
-        assert!(as_rust_type!(wrong_type, data_false, bool).is_err());
+        assert!(as_rust_type!(wrong_type, data_false, Version::V4, bool).is_err());
     }
 
     #[test]
@@ -537,10 +764,10 @@ mod tests {
         };
         let data_true = CBytes::new(vec![1]);
         let data_false = CBytes::new(vec![0]);
-        assert!(as_rust_type!(type_boolean, data_true, bool)
+        assert!(as_rust_type!(type_boolean, data_true, Version::V4, bool)
             .unwrap()
             .unwrap());
-        assert!(!as_rust_type!(type_boolean, data_false, bool)
+        assert!(!as_rust_type!(type_boolean, data_false, Version::V4, bool)
             .unwrap()
             .unwrap());
     }
@@ -560,17 +787,29 @@ mod tests {
             value: None,
         };
         let data = CBytes::new(vec![0, 0, 0, 0, 0, 0, 0, 100]);
-        assert_eq!(as_rust_type!(type_bigint, data, i64).unwrap().unwrap(), 100);
         assert_eq!(
-            as_rust_type!(type_timestamp, data, i64).unwrap().unwrap(),
+            as_rust_type!(type_bigint, data, Version::V4, i64)
+                .unwrap()
+                .unwrap(),
+            100
+        );
+        assert_eq!(
+            as_rust_type!(type_timestamp, data, Version::V4, i64)
+                .unwrap()
+                .unwrap(),
+            100
+        );
+        assert_eq!(
+            as_rust_type!(type_time, data, Version::V4, i64)
+                .unwrap()
+                .unwrap(),
             100
         );
-        assert_eq!(as_rust_type!(type_time, data, i64).unwrap().unwrap(), 100);
         let wrong_type = ColTypeOption {
             id: ColType::Map,
             value: None,
         };
-        assert!(as_rust_type!(wrong_type, data, i64).is_err());
+        assert!(as_rust_type!(wrong_type, data, Version::V4, i64).is_err());
     }
 
     #[test]
@@ -594,12 +833,24 @@ mod tests {
             ))),
         };
         let data = CBytes::new(vec![0, 0, 0, 0, 0, 0, 0, 100]);
-        assert_eq!(as_rust_type!(type_bigint, data, i64).unwrap().unwrap(), 100);
         assert_eq!(
-            as_rust_type!(type_timestamp, data, i64).unwrap().unwrap(),
+            as_rust_type!(type_bigint, data, Version::V4, i64)
+                .unwrap()
+                .unwrap(),
+            100
+        );
+        assert_eq!(
+            as_rust_type!(type_timestamp, data, Version::V4, i64)
+                .unwrap()
+                .unwrap(),
+            100
+        );
+        assert_eq!(
+            as_rust_type!(type_time, data, Version::V4, i64)
+                .unwrap()
+                .unwrap(),
             100
         );
-        assert_eq!(as_rust_type!(type_time, data, i64).unwrap().unwrap(), 100);
     }
 
     #[test]
@@ -613,13 +864,23 @@ mod tests {
             value: None,
         };
         let data = CBytes::new(vec![0, 0, 0, 100]);
-        assert_eq!(as_rust_type!(type_int, data, i32).unwrap().unwrap(), 100);
-        assert_eq!(as_rust_type!(type_date, data, i32).unwrap().unwrap(), 100);
+        assert_eq!(
+            as_rust_type!(type_int, data, Version::V4, i32)
+                .unwrap()
+                .unwrap(),
+            100
+        );
+        assert_eq!(
+            as_rust_type!(type_date, data, Version::V4, i32)
+                .unwrap()
+                .unwrap(),
+            100
+        );
         let wrong_type = ColTypeOption {
             id: ColType::Map,
             value: None,
         };
-        assert!(as_rust_type!(wrong_type, data, i32).is_err());
+        assert!(as_rust_type!(wrong_type, data, Version::V4, i32).is_err());
     }
 
     #[test]
@@ -637,8 +898,18 @@ mod tests {
             ))),
         };
         let data = CBytes::new(vec![0, 0, 0, 100]);
-        assert_eq!(as_rust_type!(type_int, data, i32).unwrap().unwrap(), 100);
-        assert_eq!(as_rust_type!(type_date, data, i32).unwrap().unwrap(), 100);
+        assert_eq!(
+            as_rust_type!(type_int, data, Version::V4, i32)
+                .unwrap()
+                .unwrap(),
+            100
+        );
+        assert_eq!(
+            as_rust_type!(type_date, data, Version::V4, i32)
+                .unwrap()
+                .unwrap(),
+            100
+        );
     }
 
     #[test]
@@ -649,14 +920,16 @@ mod tests {
         };
         let data = CBytes::new(vec![0, 100]);
         assert_eq!(
-            as_rust_type!(type_smallint, data, i16).unwrap().unwrap(),
+            as_rust_type!(type_smallint, data, Version::V4, i16)
+                .unwrap()
+                .unwrap(),
             100
         );
         let wrong_type = ColTypeOption {
             id: ColType::Map,
             value: None,
         };
-        assert!(as_rust_type!(wrong_type, data, i16).is_err());
+        assert!(as_rust_type!(wrong_type, data, Version::V4, i16).is_err());
     }
 
     #[test]
@@ -669,7 +942,9 @@ mod tests {
         };
         let data = CBytes::new(vec![0, 100]);
         assert_eq!(
-            as_rust_type!(type_smallint, data, i16).unwrap().unwrap(),
+            as_rust_type!(type_smallint, data, Version::V4, i16)
+                .unwrap()
+                .unwrap(),
             100
         );
     }
@@ -681,12 +956,17 @@ mod tests {
             value: None,
         };
         let data = CBytes::new(vec![100]);
-        assert_eq!(as_rust_type!(type_tinyint, data, i8).unwrap().unwrap(), 100);
+        assert_eq!(
+            as_rust_type!(type_tinyint, data, Version::V4, i8)
+                .unwrap()
+                .unwrap(),
+            100
+        );
         let wrong_type = ColTypeOption {
             id: ColType::Map,
             value: None,
         };
-        assert!(as_rust_type!(wrong_type, data, i8).is_err());
+        assert!(as_rust_type!(wrong_type, data, Version::V4, i8).is_err());
     }
 
     #[test]
@@ -698,7 +978,12 @@ mod tests {
             ))),
         };
         let data = CBytes::new(vec![100]);
-        assert_eq!(as_rust_type!(type_tinyint, data, i8).unwrap().unwrap(), 100);
+        assert_eq!(
+            as_rust_type!(type_tinyint, data, Version::V4, i8)
+                .unwrap()
+                .unwrap(),
+            100
+        );
     }
 
     #[test]
@@ -709,7 +994,9 @@ mod tests {
         };
         let data = CBytes::new(to_float_big(0.1_f64));
         assert_float_eq!(
-            as_rust_type!(type_double, data, f64).unwrap().unwrap(),
+            as_rust_type!(type_double, data, Version::V4, f64)
+                .unwrap()
+                .unwrap(),
             0.1,
             abs <= f64::EPSILON
         );
@@ -717,7 +1004,7 @@ mod tests {
             id: ColType::Map,
             value: None,
         };
-        assert!(as_rust_type!(wrong_type, data, f64).is_err());
+        assert!(as_rust_type!(wrong_type, data, Version::V4, f64).is_err());
     }
 
     #[test]
@@ -730,7 +1017,9 @@ mod tests {
         };
         let data = CBytes::new(to_float_big(0.1_f64));
         assert_float_eq!(
-            as_rust_type!(type_double, data, f64).unwrap().unwrap(),
+            as_rust_type!(type_double, data, Version::V4, f64)
+                .unwrap()
+                .unwrap(),
             0.1,
             abs <= f64::EPSILON
         );
@@ -744,9 +1033,11 @@ mod tests {
             value: None,
         };
         let data = CBytes::new(to_float(0.1_f32));
-        // assert_eq!(as_rust_type!(type_decimal, data, f32).unwrap(), 100.0);
+        // assert_eq!(as_rust_type!(type_decimal, data, Version::V4, f32).unwrap(), 100.0);
         assert_float_eq!(
-            as_rust_type!(type_float, data, f32).unwrap().unwrap(),
+            as_rust_type!(type_float, data, Version::V4, f32)
+                .unwrap()
+                .unwrap(),
             0.1,
             abs <= f32::EPSILON
         );
@@ -754,7 +1045,7 @@ mod tests {
             id: ColType::Map,
             value: None,
         };
-        assert!(as_rust_type!(wrong_type, data, f32).is_err());
+        assert!(as_rust_type!(wrong_type, data, Version::V4, f32).is_err());
     }
 
     #[test]
@@ -767,9 +1058,11 @@ mod tests {
             ))),
         };
         let data = CBytes::new(to_float(0.1_f32));
-        // assert_eq!(as_rust_type!(type_decimal, data, f32).unwrap(), 100.0);
+        // assert_eq!(as_rust_type!(type_decimal, data, Version::V4, f32).unwrap(), 100.0);
         assert_float_eq!(
-            as_rust_type!(type_float, data, f32).unwrap().unwrap(),
+            as_rust_type!(type_float, data, Version::V4, f32)
+                .unwrap()
+                .unwrap(),
             0.1,
             abs <= f32::EPSILON
         );
@@ -783,7 +1076,7 @@ mod tests {
         };
         let data = CBytes::new(vec![0, 0, 0, 0]);
 
-        match as_rust_type!(type_inet, data, IpAddr) {
+        match as_rust_type!(type_inet, data, Version::V4, IpAddr) {
             Ok(Some(IpAddr::V4(ref ip))) => assert_eq!(ip.octets(), [0, 0, 0, 0]),
             _ => panic!("wrong ip v4 address"),
         }
@@ -791,7 +1084,7 @@ mod tests {
             id: ColType::Map,
             value: None,
         };
-        assert!(as_rust_type!(wrong_type, data, f32).is_err());
+        assert!(as_rust_type!(wrong_type, data, Version::V4, f32).is_err());
     }
 
     #[test]
@@ -804,7 +1097,7 @@ mod tests {
         };
         let data = CBytes::new(vec![0, 0, 0, 0]);
 
-        match as_rust_type!(type_inet, data, IpAddr) {
+        match as_rust_type!(type_inet, data, Version::V4, IpAddr) {
             Ok(Some(IpAddr::V4(ref ip))) => assert_eq!(ip.octets(), [0, 0, 0, 0]),
             _ => panic!("wrong ip v4 address"),
         }
@@ -1,6 +1,9 @@
 use derive_more::Constructor;
 use float_eq::*;
-use num::BigInt;
+use num::bigint::Sign;
+use num::{BigInt, FromPrimitive};
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
 use std::io::Cursor;
 
 use crate::frame::Serialize;
@@ -17,6 +20,65 @@ impl Decimal {
     pub fn as_plain(&self) -> BigInt {
         self.unscaled.clone() / 10i64.pow(self.scale as u32)
     }
+
+    /// Returns the unscaled integer value.
+    #[inline]
+    pub fn unscaled(&self) -> &BigInt {
+        &self.unscaled
+    }
+
+    /// Returns the scale, i.e. the number of digits after the decimal point.
+    #[inline]
+    pub fn scale(&self) -> i32 {
+        self.scale
+    }
+
+    /// Creates a decimal with given scale from a float, rounding half away from zero. Note that
+    /// floats cannot represent most decimal fractions exactly, so values lying (seemingly)
+    /// exactly halfway between two results can be rounded either way, e.g. `2.675` becomes
+    /// `2.67` at scale 2, since it's actually stored as `2.67499999...`.
+    ///
+    /// Returns `None` if the value is not finite, or is too large to be scaled.
+    pub fn from_f64(value: f64, scale: u32) -> Option<Decimal> {
+        let scale = i32::try_from(scale).ok()?;
+
+        // large scales overflow the multiplier to infinity, which would turn zero into NaN
+        if value == 0.0 {
+            return Some(Decimal::new(BigInt::from(0), scale));
+        }
+
+        let unscaled = BigInt::from_f64((value * 10f64.powi(scale)).round())?;
+
+        Some(Decimal::new(unscaled, scale))
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.scale <= 0 {
+            return write!(
+                f,
+                "{}{}",
+                self.unscaled,
+                "0".repeat(self.scale.unsigned_abs() as usize)
+            );
+        }
+
+        let scale = self.scale as usize;
+        let sign = if self.unscaled.sign() == Sign::Minus {
+            "-"
+        } else {
+            ""
+        };
+
+        let mut digits = self.unscaled.magnitude().to_string();
+        if digits.len() <= scale {
+            digits = format!("{}{}", "0".repeat(scale - digits.len() + 1), digits);
+        }
+
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, integer, fraction)
+    }
 }
 
 impl Serialize for Decimal {
@@ -81,6 +143,7 @@ impl From<f64> for Decimal {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::types::data_serialization_types::decode_decimal;
 
     #[test]
     fn serialize_test() {
@@ -104,6 +167,25 @@ mod test {
         );
     }
 
+    #[test]
+    fn accessors_test() {
+        let decimal = Decimal::new(12345.into(), 2);
+        assert_eq!(decimal.unscaled(), &BigInt::from(12345));
+        assert_eq!(decimal.scale(), 2);
+
+        let decoded = decode_decimal(&decimal.serialize_to_vec()).unwrap();
+        assert_eq!(decoded, decimal);
+        assert_eq!(decoded.to_string(), "123.45");
+    }
+
+    #[test]
+    fn display_test() {
+        assert_eq!(Decimal::new(BigInt::from(-12345), 2).to_string(), "-123.45");
+        assert_eq!(Decimal::new(5.into(), 3).to_string(), "0.005");
+        assert_eq!(Decimal::new(5.into(), -2).to_string(), "500");
+        assert_eq!(Decimal::new(129.into(), 0).to_string(), "129");
+    }
+
     #[test]
     fn from_f32() {
         assert_eq!(
@@ -120,6 +202,41 @@ mod test {
         );
     }
 
+    #[test]
+    #[allow(clippy::approx_constant)]
+    fn from_f64_with_scale() {
+        let decimal = Decimal::from_f64(3.14159, 2).unwrap();
+        assert_eq!(decimal, Decimal::new(314.into(), 2));
+        assert_eq!(decimal.to_string(), "3.14");
+
+        assert_eq!(
+            Decimal::from_f64(-2.5, 0),
+            Some(Decimal::new((-3).into(), 0))
+        );
+        assert_eq!(
+            Decimal::from_f64(1.0, 3),
+            Some(Decimal::new(1000.into(), 3))
+        );
+    }
+
+    #[test]
+    fn from_f64_not_finite() {
+        assert_eq!(Decimal::from_f64(f64::NAN, 2), None);
+        assert_eq!(Decimal::from_f64(f64::INFINITY, 2), None);
+        assert_eq!(Decimal::from_f64(f64::NEG_INFINITY, 0), None);
+        assert_eq!(Decimal::from_f64(f64::MAX, 400), None);
+        assert_eq!(Decimal::from_f64(1.0, u32::MAX), None);
+
+        assert_eq!(
+            Decimal::from_f64(0.0, 400),
+            Some(Decimal::new(0.into(), 400))
+        );
+        assert_eq!(
+            Decimal::from_f64(-0.0, 400),
+            Some(Decimal::new(0.into(), 400))
+        );
+    }
+
     #[test]
     fn from_f64() {
         assert_eq!(
@@ -0,0 +1,282 @@
+use std::convert::TryFrom;
+use std::net::IpAddr;
+
+use num::BigInt;
+use uuid::Uuid;
+
+use crate::error::{Error, Result};
+use crate::frame::frame_result::{
+    CUdt, ColType, ColTypeOption, ColTypeOptionValue, MARSHAL_PACKAGE,
+};
+use crate::types::data_serialization_types;
+use crate::types::data_serialization_types::*;
+use crate::types::duration::CqlDuration;
+use crate::types::temporal::{CqlDate, CqlTime};
+use crate::types::CBytes;
+
+const DURATION_TYPE: &str = "DurationType";
+
+/// A decoded scalar cell value. Can be converted into a matching Rust type with `TryInto`,
+/// which fails with `Error::TypeMismatch` if the value holds a different type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodedValue {
+    Null,
+    Boolean(bool),
+    Tinyint(i8),
+    Smallint(i16),
+    Int(i32),
+    Bigint(i64),
+    Float(f32),
+    Double(f64),
+    Varint(BigInt),
+    Text(String),
+    Blob(Vec<u8>),
+    Uuid(Uuid),
+    Inet(IpAddr),
+    Date(CqlDate),
+    Time(CqlTime),
+    Duration(CqlDuration),
+}
+
+impl DecodedValue {
+    /// Decodes raw cell bytes of given type. Collections, UDTs and tuples are not scalar values
+    /// and result in an error.
+    pub fn decode(bytes: &CBytes, col_type: &ColTypeOption) -> Result<DecodedValue> {
+        let bytes = match bytes.as_slice() {
+            Some(bytes) => bytes,
+            None => return Ok(DecodedValue::Null),
+        };
+
+        let value = match col_type.id {
+            ColType::Boolean => DecodedValue::Boolean(decode_boolean(bytes)?),
+            ColType::Tinyint => DecodedValue::Tinyint(decode_tinyint(bytes)?),
+            ColType::Smallint => DecodedValue::Smallint(decode_smallint(bytes)?),
+            ColType::Int => DecodedValue::Int(decode_int(bytes)?),
+            ColType::Bigint | ColType::Counter | ColType::Timestamp => {
+                DecodedValue::Bigint(decode_bigint(bytes)?)
+            }
+            ColType::Date => DecodedValue::Date(CqlDate::new(decode_date(bytes)? as u32)),
+            ColType::Time => DecodedValue::Time(CqlTime::new(decode_time(bytes)?)),
+            ColType::Duration => DecodedValue::Duration(decode_duration(bytes)?),
+            ColType::Custom if is_duration_class(col_type) => {
+                DecodedValue::Duration(decode_duration(bytes)?)
+            }
+            ColType::Float => DecodedValue::Float(decode_float(bytes)?),
+            ColType::Double => DecodedValue::Double(decode_double(bytes)?),
+            ColType::Varint => DecodedValue::Varint(decode_varint(bytes)?),
+            ColType::Ascii | ColType::Varchar => DecodedValue::Text(decode_text(bytes)?),
+            ColType::Blob | ColType::Custom => DecodedValue::Blob(bytes.to_vec()),
+            ColType::Uuid | ColType::Timeuuid => DecodedValue::Uuid(decode_timeuuid(bytes)?),
+            ColType::Inet => DecodedValue::Inet(decode_inet(bytes)?),
+            _ => {
+                return Err(Error::General(format!(
+                    "Cannot decode {:?} as a scalar value",
+                    col_type.id
+                )))
+            }
+        };
+
+        Ok(value)
+    }
+
+    /// Name of the held type, as used in conversion errors.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            DecodedValue::Null => "null",
+            DecodedValue::Boolean(_) => "boolean",
+            DecodedValue::Tinyint(_) => "tinyint",
+            DecodedValue::Smallint(_) => "smallint",
+            DecodedValue::Int(_) => "int",
+            DecodedValue::Bigint(_) => "bigint",
+            DecodedValue::Float(_) => "float",
+            DecodedValue::Double(_) => "double",
+            DecodedValue::Varint(_) => "varint",
+            DecodedValue::Text(_) => "text",
+            DecodedValue::Blob(_) => "blob",
+            DecodedValue::Uuid(_) => "uuid",
+            DecodedValue::Inet(_) => "inet",
+            DecodedValue::Date(_) => "date",
+            DecodedValue::Time(_) => "time",
+            DecodedValue::Duration(_) => "duration",
+        }
+    }
+
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        matches!(self, DecodedValue::Null)
+    }
+}
+
+// durations are sent as a custom type before protocol v5
+fn is_duration_class(col_type: &ColTypeOption) -> bool {
+    match &col_type.value {
+        Some(ColTypeOptionValue::CString(class_name)) => {
+            let class_name = class_name.as_str();
+            class_name
+                .strip_prefix(MARSHAL_PACKAGE)
+                .unwrap_or(class_name)
+                == DURATION_TYPE
+        }
+        _ => false,
+    }
+}
+
+/// Decodes UDT value bytes into fields paired with names declared in given UDT spec. Values
+/// written before the UDT was extended with new fields lack the trailing ones, which are
+/// decoded as `DecodedValue::Null`.
+pub fn decode_udt(bytes: &[u8], udt_spec: &CUdt) -> Result<Vec<(String, DecodedValue)>> {
+    let values = data_serialization_types::decode_udt(bytes, udt_spec.descriptions.len())?;
+
+    udt_spec
+        .descriptions
+        .iter()
+        .zip(values.iter())
+        .map(|((name, col_type), value)| {
+            Ok((
+                name.as_str().to_string(),
+                DecodedValue::decode(value, col_type)?,
+            ))
+        })
+        .collect()
+}
+
+macro_rules! decoded_value_try_from {
+    ($t:ty, $expected:expr, $($variant:ident),+) => {
+        impl TryFrom<DecodedValue> for $t {
+            type Error = Error;
+
+            #[allow(clippy::useless_conversion)]
+            fn try_from(value: DecodedValue) -> Result<Self> {
+                match value {
+                    $(DecodedValue::$variant(value) => Ok(value.into()),)+
+                    value => Err(Error::TypeMismatch {
+                        expected: $expected,
+                        actual: value.type_name(),
+                    }),
+                }
+            }
+        }
+    };
+}
+
+decoded_value_try_from!(bool, "boolean", Boolean);
+decoded_value_try_from!(i8, "tinyint", Tinyint);
+decoded_value_try_from!(i16, "smallint", Smallint, Tinyint);
+decoded_value_try_from!(i32, "int", Int, Smallint, Tinyint);
+decoded_value_try_from!(i64, "bigint", Bigint, Int, Smallint, Tinyint);
+decoded_value_try_from!(f32, "float", Float);
+decoded_value_try_from!(f64, "double", Double, Float);
+decoded_value_try_from!(BigInt, "varint", Varint, Bigint, Int, Smallint, Tinyint);
+decoded_value_try_from!(String, "text", Text);
+decoded_value_try_from!(Vec<u8>, "blob", Blob);
+decoded_value_try_from!(Uuid, "uuid", Uuid);
+decoded_value_try_from!(IpAddr, "inet", Inet);
+decoded_value_try_from!(CqlDate, "date", Date);
+decoded_value_try_from!(CqlTime, "time", Time);
+decoded_value_try_from!(CqlDuration, "duration", Duration);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::CString;
+    use std::convert::TryInto;
+
+    fn col_type(id: ColType) -> ColTypeOption {
+        ColTypeOption { id, value: None }
+    }
+
+    #[test]
+    fn decoded_value_into_i64() {
+        let bytes = CBytes::new(vec![0, 0, 0, 0, 0, 0, 0, 42]);
+        let value = DecodedValue::decode(&bytes, &col_type(ColType::Bigint)).unwrap();
+
+        let value: i64 = value.try_into().unwrap();
+        assert_eq!(value, 42);
+    }
+
+    #[test]
+    fn decoded_value_into_string() {
+        let bytes = CBytes::new(b"abc".to_vec());
+        let value = DecodedValue::decode(&bytes, &col_type(ColType::Varchar)).unwrap();
+
+        let value: String = value.try_into().unwrap();
+        assert_eq!(value, "abc");
+    }
+
+    #[test]
+    fn decoded_value_mismatch() {
+        let bytes = CBytes::new(b"abc".to_vec());
+        let value = DecodedValue::decode(&bytes, &col_type(ColType::Varchar)).unwrap();
+
+        let result: Result<i64> = value.try_into();
+        assert!(matches!(
+            result,
+            Err(Error::TypeMismatch {
+                expected: "bigint",
+                actual: "text"
+            })
+        ));
+    }
+
+    #[test]
+    fn decode_udt_missing_trailing_field() {
+        let udt_spec = CUdt {
+            ks: CString::new("ks".into()),
+            udt_name: CString::new("udt".into()),
+            descriptions: vec![
+                (CString::new("id".into()), col_type(ColType::Int)),
+                (CString::new("name".into()), col_type(ColType::Varchar)),
+            ],
+        };
+
+        let fields = decode_udt(&[0, 0, 0, 4, 0, 0, 0, 7], &udt_spec).unwrap();
+        assert_eq!(
+            fields,
+            vec![
+                ("id".to_string(), DecodedValue::Int(7)),
+                ("name".to_string(), DecodedValue::Null),
+            ]
+        );
+    }
+
+    #[test]
+    fn decoded_value_date() {
+        let bytes = CBytes::new(vec![0x80, 0, 0, 1]);
+        let value = DecodedValue::decode(&bytes, &col_type(ColType::Date)).unwrap();
+
+        let value: CqlDate = value.try_into().unwrap();
+        assert_eq!(value.days_since_epoch(), 1);
+    }
+
+    #[test]
+    fn decoded_value_time() {
+        let bytes = CBytes::new(vec![0, 0, 0, 0, 0, 0, 0x03, 0xe8]);
+        let value = DecodedValue::decode(&bytes, &col_type(ColType::Time)).unwrap();
+
+        assert_eq!(value, DecodedValue::Time(CqlTime::new(1000)));
+    }
+
+    #[test]
+    fn decoded_value_duration() {
+        let bytes = CBytes::new(vec![2, 4, 0x87, 0xd0]);
+        let expected = DecodedValue::Duration(CqlDuration::new(1, 2, 1000));
+
+        let value = DecodedValue::decode(&bytes, &col_type(ColType::Duration)).unwrap();
+        assert_eq!(value, expected);
+
+        let custom = ColTypeOption {
+            id: ColType::Custom,
+            value: Some(ColTypeOptionValue::CString(CString::new(
+                "org.apache.cassandra.db.marshal.DurationType".into(),
+            ))),
+        };
+        let value = DecodedValue::decode(&bytes, &custom).unwrap();
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn decoded_value_null() {
+        let value = DecodedValue::decode(&CBytes::new_empty(), &col_type(ColType::Int)).unwrap();
+        assert!(value.is_null());
+    }
+}
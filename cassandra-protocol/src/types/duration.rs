@@ -0,0 +1,165 @@
+use derive_more::Constructor;
+use std::convert::TryFrom;
+use std::io::{self, Cursor, Read, Write};
+
+use crate::error;
+use crate::frame::{FromCursor, Serialize};
+
+/// Cassandra Duration type. A duration stores separately months, days, and nanoseconds due to
+/// the fact that the number of days in a month varies, and a day can have 23 or 25 hours if a
+/// daylight saving is involved.
+#[derive(Debug, Clone, Copy, PartialEq, Constructor, Ord, PartialOrd, Eq, Hash, Default)]
+pub struct CqlDuration {
+    pub months: i32,
+    pub days: i32,
+    pub nanoseconds: i64,
+}
+
+impl Serialize for CqlDuration {
+    fn serialize(&self, cursor: &mut Cursor<&mut Vec<u8>>) {
+        serialize_vint(self.months as i64, cursor);
+        serialize_vint(self.days as i64, cursor);
+        serialize_vint(self.nanoseconds, cursor);
+    }
+}
+
+impl FromCursor for CqlDuration {
+    fn from_cursor(cursor: &mut Cursor<&[u8]>) -> error::Result<CqlDuration> {
+        let months = deserialize_i32_vint(cursor, "months")?;
+        let days = deserialize_i32_vint(cursor, "days")?;
+        let nanoseconds = deserialize_vint(cursor)?;
+
+        Ok(CqlDuration::new(months, days, nanoseconds))
+    }
+}
+
+impl TryFrom<chrono::Duration> for CqlDuration {
+    type Error = error::Error;
+
+    /// Converts given span into days and nanoseconds. Since the length of a month is not fixed,
+    /// months cannot be derived from a plain span and are always left at zero, e.g. a 45-day
+    /// span becomes 45 days and not 1 month and 15 days. Fails if the number of days doesn't fit
+    /// in an `i32`.
+    fn try_from(duration: chrono::Duration) -> Result<Self, Self::Error> {
+        let days = duration.num_days();
+        let nanoseconds = (duration - chrono::Duration::days(days))
+            .num_nanoseconds()
+            .unwrap_or_default();
+
+        let days = i32::try_from(days).map_err(|_| {
+            error::Error::General(format!("Duration of {} days is out of range", days))
+        })?;
+
+        Ok(CqlDuration {
+            months: 0,
+            days,
+            nanoseconds,
+        })
+    }
+}
+
+fn serialize_vint(value: i64, cursor: &mut Cursor<&mut Vec<u8>>) {
+    // zig-zag encoding
+    let value = ((value >> 63) ^ (value << 1)) as u64;
+
+    let magnitude = (value | 1).leading_zeros() as usize;
+    let size = (639 - magnitude * 9) >> 6;
+    if size == 1 {
+        let _ = cursor.write(&[value as u8]);
+        return;
+    }
+
+    let mut buffer = [0u8; 9];
+    buffer[1..].copy_from_slice(&value.to_be_bytes());
+
+    let encoded = &mut buffer[9 - size..];
+    encoded[0] |= !((0xff_u16 >> (size - 1)) as u8);
+
+    let _ = cursor.write(encoded);
+}
+
+fn deserialize_i32_vint(cursor: &mut Cursor<&[u8]>, field: &str) -> error::Result<i32> {
+    let value = deserialize_vint(cursor)?;
+    i32::try_from(value)
+        .map_err(|_| error::Error::Protocol(format!("Duration {} out of range: {}", field, value)))
+}
+
+fn deserialize_vint(cursor: &mut Cursor<&[u8]>) -> io::Result<i64> {
+    let mut first = [0];
+    cursor.read_exact(&mut first)?;
+
+    // number of leading ones is the number of bytes following the first one
+    let extra_bytes = first[0].leading_ones() as usize;
+    let mut value = (first[0] as u64) & (0xff_u64 >> extra_bytes);
+
+    let mut buffer = [0u8; 8];
+    let extra = &mut buffer[..extra_bytes];
+    cursor.read_exact(extra)?;
+    for byte in extra.iter() {
+        value = (value << 8) | *byte as u64;
+    }
+
+    // zig-zag decoding
+    Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cql_duration_serialize() {
+        assert_eq!(CqlDuration::new(1, 2, 3).serialize_to_vec(), vec![2, 4, 6]);
+        assert_eq!(CqlDuration::new(0, -1, 0).serialize_to_vec(), vec![0, 1, 0]);
+        assert_eq!(
+            CqlDuration::new(0, 0, 1000).serialize_to_vec(),
+            vec![0, 0, 0x87, 0xd0]
+        );
+        assert_eq!(
+            CqlDuration::new(0, 0, i64::MIN).serialize_to_vec(),
+            vec![0, 0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]
+        );
+    }
+
+    #[test]
+    fn cql_duration_roundtrip() {
+        for duration in [
+            CqlDuration::new(1, 2, 3),
+            CqlDuration::new(0, -1, 0),
+            CqlDuration::new(-14, 400, 1000),
+            CqlDuration::new(i32::MAX, i32::MIN, i64::MIN),
+            CqlDuration::new(0, 0, i64::MAX),
+        ] {
+            let bytes = duration.serialize_to_vec();
+            let mut cursor = Cursor::new(bytes.as_slice());
+            assert_eq!(CqlDuration::from_cursor(&mut cursor).unwrap(), duration);
+        }
+    }
+
+    #[test]
+    fn cql_duration_out_of_range() {
+        let mut bytes = vec![];
+        let mut cursor = Cursor::new(&mut bytes);
+        serialize_vint(0, &mut cursor);
+        serialize_vint(i32::MAX as i64 + 1, &mut cursor);
+        serialize_vint(0, &mut cursor);
+
+        let mut cursor = Cursor::new(bytes.as_slice());
+        assert!(matches!(
+            CqlDuration::from_cursor(&mut cursor),
+            Err(error::Error::Protocol(_))
+        ));
+    }
+
+    #[test]
+    fn cql_duration_from_chrono() {
+        let duration = CqlDuration::try_from(chrono::Duration::minutes(90)).unwrap();
+        assert_eq!(duration, CqlDuration::new(0, 0, 5_400_000_000_000));
+
+        let duration = CqlDuration::try_from(chrono::Duration::hours(49)).unwrap();
+        assert_eq!(duration, CqlDuration::new(0, 2, 3_600_000_000_000));
+
+        let days = i32::MAX as i64 + 1;
+        assert!(CqlDuration::try_from(chrono::Duration::days(days)).is_err());
+    }
+}
@@ -0,0 +1,110 @@
+//! DataStax Enterprise geospatial types. They are sent as custom types, with values encoded as
+//! [WKB](https://en.wikipedia.org/wiki/Well-known_text_representation_of_geometry#Well-known_binary).
+use derive_more::Constructor;
+
+use crate::frame::frame_result::{ColType, ColTypeOption, ColTypeOptionValue, MARSHAL_PACKAGE};
+use crate::types::value::Bytes;
+
+const WKB_LITTLE_ENDIAN: u8 = 1;
+const WKB_POINT: u32 = 1;
+
+/// Geospatial custom type.
+#[derive(Debug, PartialEq, Copy, Clone, Ord, PartialOrd, Eq, Hash)]
+pub enum GeoType {
+    Point,
+    LineString,
+    Polygon,
+}
+
+impl GeoType {
+    /// Recognizes a geospatial type by its custom type class name, with or without the package.
+    pub fn from_class_name(class_name: &str) -> Option<GeoType> {
+        match class_name
+            .strip_prefix(MARSHAL_PACKAGE)
+            .unwrap_or(class_name)
+        {
+            "PointType" => Some(GeoType::Point),
+            "LineStringType" => Some(GeoType::LineString),
+            "PolygonType" => Some(GeoType::Polygon),
+            _ => None,
+        }
+    }
+
+    /// Returns the full custom type class name.
+    pub fn class_name(self) -> &'static str {
+        match self {
+            GeoType::Point => "org.apache.cassandra.db.marshal.PointType",
+            GeoType::LineString => "org.apache.cassandra.db.marshal.LineStringType",
+            GeoType::Polygon => "org.apache.cassandra.db.marshal.PolygonType",
+        }
+    }
+}
+
+impl ColTypeOption {
+    /// Returns the geospatial type of a custom column, if it is one.
+    pub fn geo_type(&self) -> Option<GeoType> {
+        match (&self.id, &self.value) {
+            (ColType::Custom, Some(ColTypeOptionValue::CString(class_name))) => {
+                GeoType::from_class_name(class_name.as_str())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Geospatial point, which can be bound to `PointType` columns.
+#[derive(Debug, PartialEq, Copy, Clone, Default, Constructor)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl From<Point> for Bytes {
+    /// Encodes the point as little-endian WKB.
+    fn from(point: Point) -> Self {
+        let mut bytes = Vec::with_capacity(21);
+        bytes.push(WKB_LITTLE_ENDIAN);
+        bytes.extend_from_slice(&WKB_POINT.to_le_bytes());
+        bytes.extend_from_slice(&point.x.to_le_bytes());
+        bytes.extend_from_slice(&point.y.to_le_bytes());
+        Bytes::new(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::value::Value;
+    use crate::types::CString;
+
+    #[test]
+    fn point_wkb() {
+        let value: Value = Point::new(1.0, -2.0).into();
+        assert_eq!(
+            value,
+            Value::Some(vec![
+                1, // little endian
+                1, 0, 0, 0, // point
+                0, 0, 0, 0, 0, 0, 0xF0, 0x3F, // x
+                0, 0, 0, 0, 0, 0, 0x00, 0xC0, // y
+            ])
+        );
+    }
+
+    #[test]
+    fn geo_type_from_custom_class() {
+        let col_type = ColTypeOption::from_custom_class(GeoType::Point.class_name());
+        assert_eq!(col_type.geo_type(), Some(GeoType::Point));
+
+        let col_type = ColTypeOption {
+            id: ColType::Custom,
+            value: Some(ColTypeOptionValue::CString(CString::new(
+                "PolygonType".into(),
+            ))),
+        };
+        assert_eq!(col_type.geo_type(), Some(GeoType::Polygon));
+
+        let col_type = ColTypeOption::from_custom_class("org.apache.cassandra.db.marshal.Other");
+        assert_eq!(col_type.geo_type(), None);
+    }
+}
@@ -1,10 +1,10 @@
-use derive_more::Constructor;
 use num::BigInt;
 use std::net::IpAddr;
 use uuid::Uuid;
 
 use crate::error::{Error, Result};
 use crate::frame::frame_result::{ColType, ColTypeOption, ColTypeOptionValue};
+use crate::frame::Version;
 use crate::types::blob::Blob;
 use crate::types::data_serialization_types::*;
 use crate::types::decimal::Decimal;
@@ -14,15 +14,25 @@ use crate::types::udt::Udt;
 use crate::types::{AsRust, AsRustType, CBytes};
 
 // TODO: consider using pointers to ColTypeOption and Vec<CBytes> instead of owning them.
-#[derive(Debug, Constructor)]
+#[derive(Debug)]
 pub struct List {
     /// column spec of the list, i.e. id should be List as it's a list and value should contain
     /// a type of list items.
     metadata: ColTypeOption,
     data: Vec<CBytes>,
+    /// protocol version the list was received with, used for decoding nested collections.
+    version: Version,
 }
 
 impl List {
+    pub fn new(metadata: ColTypeOption, data: Vec<CBytes>, version: Version) -> List {
+        List {
+            metadata,
+            data,
+            version,
+        }
+    }
+
     fn map<T, F>(&self, f: F) -> Vec<T>
     where
         F: FnMut(&CBytes) -> T,
@@ -5,6 +5,7 @@ use uuid::Uuid;
 
 use crate::error::{Error, Result};
 use crate::frame::frame_result::{ColType, ColTypeOption, ColTypeOptionValue};
+use crate::frame::Version;
 use crate::types::blob::Blob;
 use crate::types::data_serialization_types::*;
 use crate::types::decimal::Decimal;
@@ -18,14 +19,17 @@ use num::BigInt;
 pub struct Map {
     metadata: ColTypeOption,
     data: Vec<(CBytes, CBytes)>,
+    version: Version,
 }
 
 impl Map {
-    /// Creates new `Map` using the provided data and key and value types.
-    pub fn new(data: Vec<(CBytes, CBytes)>, meta: ColTypeOption) -> Map {
+    /// Creates new `Map` using the provided data and key and value types, received with given
+    /// protocol version.
+    pub fn new(data: Vec<(CBytes, CBytes)>, meta: ColTypeOption, version: Version) -> Map {
         Map {
             metadata: meta,
             data,
+            version,
         }
     }
 }
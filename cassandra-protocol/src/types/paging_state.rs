@@ -0,0 +1,105 @@
+use std::io::Cursor;
+
+use crate::error;
+use crate::frame::{FromCursor, Serialize};
+use crate::types::CBytes;
+
+/// Opaque paging state returned by the server in rows metadata, which should be passed as-is to
+/// a follow-up query in order to fetch the next page of results.
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Default)]
+pub struct PagingState(Vec<u8>);
+
+impl PagingState {
+    #[inline]
+    pub fn new(bytes: Vec<u8>) -> Self {
+        PagingState(bytes)
+    }
+
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[inline]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+
+    /// Decodes a paging state sent as `[bytes]`, where null means there is no paging state.
+    pub fn from_cursor_opt(cursor: &mut Cursor<&[u8]>) -> error::Result<Option<PagingState>> {
+        CBytes::from_cursor(cursor).map(|bytes| bytes.into_plain().map(PagingState))
+    }
+}
+
+/// Serializes the paging state as `[bytes]`. An empty state carries no position, so it is sent
+/// as null.
+impl Serialize for PagingState {
+    fn serialize(&self, cursor: &mut Cursor<&mut Vec<u8>>) {
+        if self.is_empty() {
+            CBytes::new_empty().serialize(cursor);
+        } else {
+            CBytes::new(self.0.clone()).serialize(cursor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::frame_result::{RowsMetadata, RowsMetadataFlags};
+    use crate::query::{QueryParams, QueryParamsBuilder};
+
+    #[test]
+    fn paging_state_roundtrip_into_query() {
+        let metadata_bytes = [
+            0, 0, 0, 6, // flags: has more pages, no metadata
+            0, 0, 0, 0, // columns count
+            0, 0, 0, 3, 1, 2, 3, // paging state
+        ];
+
+        let metadata = RowsMetadata::from_cursor(&mut Cursor::new(&metadata_bytes)).unwrap();
+        assert!(metadata.flags.contains(RowsMetadataFlags::HAS_MORE_PAGES));
+
+        let paging_state = metadata.paging_state.unwrap();
+        assert_eq!(paging_state.as_bytes(), &[1, 2, 3]);
+
+        let params = QueryParamsBuilder::new()
+            .paging_state(paging_state.clone())
+            .finalize();
+        assert_eq!(params.paging_state, Some(paging_state));
+
+        let encoded = params.serialize_to_vec();
+        let decoded = QueryParams::from_cursor(&mut Cursor::new(&encoded)).unwrap();
+        assert_eq!(decoded.paging_state, Some(PagingState::new(vec![1, 2, 3])));
+    }
+
+    #[test]
+    fn null_paging_state_is_absent() {
+        let metadata_bytes = [
+            0, 0, 0, 6, // flags: has more pages, no metadata
+            0, 0, 0, 0, // columns count
+            0xff, 0xff, 0xff, 0xff, // null paging state
+        ];
+
+        let metadata = RowsMetadata::from_cursor(&mut Cursor::new(&metadata_bytes)).unwrap();
+        assert_eq!(metadata.paging_state, None);
+
+        assert_eq!(
+            PagingState::default().serialize_to_vec(),
+            vec![0xff, 0xff, 0xff, 0xff]
+        );
+
+        let with_empty_state = QueryParamsBuilder::new()
+            .paging_state(PagingState::default())
+            .finalize();
+        assert_eq!(
+            with_empty_state.serialize_to_vec(),
+            QueryParams::default().serialize_to_vec()
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::convert::TryFrom;
+use std::io::Cursor;
 use std::net::IpAddr;
 use std::num::{NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8};
 use std::sync::Arc;
@@ -9,10 +11,13 @@ use uuid::Uuid;
 use crate::error::{column_is_empty_err, Error, Result};
 use crate::frame::frame_result::{
     BodyResResultRows, ColSpec, ColType, ColTypeOption, ColTypeOptionValue, RowsMetadata,
+    RowsMetadataFlags,
 };
+use crate::frame::Version;
 use crate::types::blob::Blob;
 use crate::types::data_serialization_types::*;
 use crate::types::decimal::Decimal;
+use crate::types::decoded_value::DecodedValue;
 use crate::types::list::List;
 use crate::types::map::Map;
 use crate::types::tuple::Tuple;
@@ -24,18 +29,53 @@ use num::BigInt;
 pub struct Row {
     metadata: Arc<RowsMetadata>,
     row_content: Vec<CBytes>,
+    version: Version,
 }
 
 impl Row {
+    /// Creates a row from given metadata and raw column values, received with given protocol
+    /// version.
+    pub fn new(metadata: Arc<RowsMetadata>, row_content: Vec<CBytes>, version: Version) -> Row {
+        Row {
+            metadata,
+            row_content,
+            version,
+        }
+    }
+
     pub fn from_frame_body(body: BodyResResultRows) -> Vec<Row> {
-        let metadata = Arc::new(body.metadata);
-        body.rows_content
+        body.into_iter().collect()
+    }
+
+    /// Creates rows from a response body, which might have been sent without metadata (with the
+    /// `NO_METADATA` flag set), e.g. when executing a prepared statement with skipped metadata.
+    /// In such case, given metadata (usually cached result metadata of the prepared statement)
+    /// is used instead.
+    pub fn from_frame_body_with_metadata(
+        body: BodyResResultRows,
+        metadata: Option<Arc<RowsMetadata>>,
+    ) -> Result<Vec<Row>> {
+        if !body.metadata.flags.contains(RowsMetadataFlags::NO_METADATA) {
+            return Ok(Row::from_frame_body(body));
+        }
+
+        let metadata = metadata.ok_or_else(|| {
+            Error::General("Rows sent without metadata, but none was supplied".into())
+        })?;
+
+        if metadata.columns_count != body.metadata.columns_count {
+            return Err(Error::General(format!(
+                "Supplied metadata has {} columns, while rows have {}",
+                metadata.columns_count, body.metadata.columns_count
+            )));
+        }
+
+        let version = body.version;
+        Ok(body
+            .rows_content
             .into_iter()
-            .map(|row| Row {
-                metadata: metadata.clone(),
-                row_content: row,
-            })
-            .collect()
+            .map(|row| Row::new(metadata.clone(), row, version))
+            .collect())
     }
 
     /// Checks if a column is present in the row.
@@ -64,6 +104,48 @@ impl Row {
             .unwrap_or(false)
     }
 
+    /// Decodes a scalar column by its name and converts it into requested Rust type, e.g.
+    /// `let id: i64 = row.get("id")?;`.
+    pub fn get<T>(&self, name: &str) -> Result<T>
+    where
+        T: TryFrom<DecodedValue, Error = Error>,
+    {
+        self.get_opt(name)?.ok_or_else(|| column_is_empty_err(name))
+    }
+
+    /// Like `get`, but returns `None` for null values.
+    pub fn get_opt<T>(&self, name: &str) -> Result<Option<T>>
+    where
+        T: TryFrom<DecodedValue, Error = Error>,
+    {
+        let (col_spec, cbytes) = self
+            .col_spec_by_name(name)
+            .ok_or_else(|| Error::General(format!("Column '{}' not found", name)))?;
+
+        convert_decoded(DecodedValue::decode(cbytes, &col_spec.col_type)?)
+    }
+
+    /// Decodes a scalar column by its position and converts it into requested Rust type.
+    pub fn get_by_index<T>(&self, index: usize) -> Result<T>
+    where
+        T: TryFrom<DecodedValue, Error = Error>,
+    {
+        self.get_opt_by_index(index)?
+            .ok_or_else(|| column_is_empty_err(index))
+    }
+
+    /// Like `get_by_index`, but returns `None` for null values.
+    pub fn get_opt_by_index<T>(&self, index: usize) -> Result<Option<T>>
+    where
+        T: TryFrom<DecodedValue, Error = Error>,
+    {
+        let (col_spec, cbytes) = self
+            .col_spec_by_index(index)
+            .ok_or_else(|| Error::General(format!("Column {} not found", index)))?;
+
+        convert_decoded(DecodedValue::decode(cbytes, &col_spec.col_type)?)
+    }
+
     fn col_spec_by_name(&self, name: &str) -> Option<(&ColSpec, &CBytes)> {
         self.metadata
             .col_specs
@@ -83,6 +165,63 @@ impl Row {
     }
 }
 
+fn convert_decoded<T>(value: DecodedValue) -> Result<Option<T>>
+where
+    T: TryFrom<DecodedValue, Error = Error>,
+{
+    match value {
+        DecodedValue::Null => Ok(None),
+        value => T::try_from(value).map(Some),
+    }
+}
+
+/// Iterator over rows of a decoded rows result. All rows share the result metadata.
+#[derive(Debug)]
+pub struct RowsIter {
+    metadata: Arc<RowsMetadata>,
+    rows_content: std::vec::IntoIter<Vec<CBytes>>,
+    version: Version,
+}
+
+impl RowsIter {
+    /// Returns the result metadata, which is available even if there are no rows.
+    #[inline]
+    pub fn metadata(&self) -> &RowsMetadata {
+        &self.metadata
+    }
+}
+
+impl Iterator for RowsIter {
+    type Item = Row;
+
+    #[inline]
+    fn next(&mut self) -> Option<Row> {
+        self.rows_content
+            .next()
+            .map(|row_content| Row::new(self.metadata.clone(), row_content, self.version))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows_content.size_hint()
+    }
+}
+
+impl ExactSizeIterator for RowsIter {}
+
+impl IntoIterator for BodyResResultRows {
+    type Item = Row;
+    type IntoIter = RowsIter;
+
+    fn into_iter(self) -> RowsIter {
+        RowsIter {
+            metadata: Arc::new(self.metadata),
+            rows_content: self.rows_content.into_iter(),
+            version: self.version,
+        }
+    }
+}
+
 impl ByName for Row {}
 
 into_rust_by_name!(Row, Blob);
@@ -136,3 +275,190 @@ into_rust_by_index!(Row, NonZeroI64);
 into_rust_by_index!(Row, NaiveDateTime);
 into_rust_by_index!(Row, DateTime<Utc>);
 into_rust_by_index!(Row, BigInt);
+
+const BITMAP_WORD_BITS: usize = 64;
+
+/// Row representation optimized for wide rows with many nulls. Null columns are only recorded in
+/// a bitmap and do not take any space in column values.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SparseRow {
+    null_bitmap: Vec<u64>,
+    values: Vec<Vec<u8>>,
+    columns_count: usize,
+}
+
+impl SparseRow {
+    /// Returns the number of columns in the row.
+    #[inline]
+    pub fn columns_count(&self) -> usize {
+        self.columns_count
+    }
+
+    /// Checks for NULL for a given column. Returns false if given column does not exist.
+    #[inline]
+    pub fn is_null(&self, index: usize) -> bool {
+        index < self.columns_count
+            && self.null_bitmap[index / BITMAP_WORD_BITS] & (1 << (index % BITMAP_WORD_BITS)) != 0
+    }
+
+    /// Returns raw value of a given column or `None`, if the column is null or does not exist.
+    pub fn get(&self, index: usize) -> Option<&[u8]> {
+        if index >= self.columns_count || self.is_null(index) {
+            return None;
+        }
+
+        let word = index / BITMAP_WORD_BITS;
+        let preceding_nulls = self.null_bitmap[..word]
+            .iter()
+            .map(|bits| bits.count_ones() as usize)
+            .sum::<usize>()
+            + (self.null_bitmap[word] & ((1 << (index % BITMAP_WORD_BITS)) - 1)).count_ones()
+                as usize;
+
+        self.values
+            .get(index - preceding_nulls)
+            .map(|value| value.as_slice())
+    }
+}
+
+/// Decodes a single row of `columns_count` columns, tracking nulls in a bitmap instead of
+/// allocating a value for each of them.
+pub fn decode_row_sparse(cursor: &mut Cursor<&[u8]>, columns_count: usize) -> Result<SparseRow> {
+    let mut null_bitmap = vec![0u64; columns_count.div_ceil(BITMAP_WORD_BITS)];
+    let mut values = Vec::new();
+
+    for index in 0..columns_count {
+        match CBytes::from_result_cursor(cursor)?.into_plain() {
+            Some(value) => values.push(value),
+            None => null_bitmap[index / BITMAP_WORD_BITS] |= 1 << (index % BITMAP_WORD_BITS),
+        }
+    }
+
+    Ok(SparseRow {
+        null_bitmap,
+        values,
+        columns_count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::FromCursor;
+    use crate::types::CString;
+
+    #[test]
+    fn rows_with_supplied_metadata() {
+        // NO_METADATA, 1 column, 1 row with int 5
+        let bytes = [0, 0, 0, 4, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 4, 0, 0, 0, 5];
+        let body = BodyResResultRows::from_cursor(&mut Cursor::new(&bytes[..])).unwrap();
+
+        assert!(Row::from_frame_body_with_metadata(body.clone(), None).is_err());
+
+        let metadata = Arc::new(RowsMetadata {
+            flags: RowsMetadataFlags::empty(),
+            columns_count: 1,
+            paging_state: None,
+            new_metadata_id: None,
+            global_table_spec: None,
+            col_specs: vec![ColSpec {
+                table_spec: None,
+                name: CString::new("id".into()),
+                col_type: ColTypeOption {
+                    id: ColType::Int,
+                    value: None,
+                },
+            }],
+        });
+
+        let rows = Row::from_frame_body_with_metadata(body, Some(metadata)).unwrap();
+        assert_eq!(rows.len(), 1);
+
+        let id: i32 = rows[0].get_r_by_name("id").unwrap();
+        assert_eq!(id, 5);
+
+        let id: i64 = rows[0].get("id").unwrap();
+        assert_eq!(id, 5);
+        assert!(rows[0].get::<String>("id").is_err());
+        assert!(rows[0].get::<i64>("missing").is_err());
+    }
+
+    #[test]
+    fn iterate_rows() {
+        let bytes = [
+            0, 0, 0, 1, // global table space flag
+            0, 0, 0, 1, // columns count
+            0, 1, 107, // ks
+            0, 1, 116, // table
+            0, 2, 105, 100, 0, 9, // id int
+            0, 0, 0, 2, // rows count
+            0, 0, 0, 4, 0, 0, 0, 1, // row 1
+            0, 0, 0, 4, 0, 0, 0, 2, // row 2
+        ];
+        let body = BodyResResultRows::from_cursor(&mut Cursor::new(&bytes[..])).unwrap();
+
+        let mut ids = vec![];
+        for row in body {
+            ids.push(row.get::<i32>("id").unwrap());
+        }
+
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn iterate_zero_rows() {
+        let bytes = [
+            0, 0, 0, 1, // global table space flag
+            0, 0, 0, 3, // columns count
+            0, 1, 107, // ks
+            0, 1, 116, // table
+            0, 2, 105, 100, 0, 9, // id int
+            0, 4, 110, 97, 109, 101, 0, 13, // name varchar
+            0, 3, 97, 103, 101, 0, 20, // age tinyint
+            0, 0, 0, 0, // rows count
+        ];
+        let body = BodyResResultRows::from_cursor(&mut Cursor::new(&bytes[..])).unwrap();
+        assert_eq!(body.rows_count, 0);
+
+        let mut rows = body.into_iter();
+        assert_eq!(rows.len(), 0);
+
+        let names: Vec<_> = rows
+            .metadata()
+            .col_specs
+            .iter()
+            .map(|col_spec| col_spec.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["id", "name", "age"]);
+
+        assert!(rows.next().is_none());
+    }
+
+    #[test]
+    fn decode_row_sparse_nulls() {
+        let mut bytes = vec![];
+        for index in 0..50 {
+            if index % 5 == 0 {
+                bytes.extend_from_slice(&[0, 0, 0, 1, index as u8]);
+            } else {
+                bytes.extend_from_slice(&[255, 255, 255, 255]);
+            }
+        }
+
+        let row = decode_row_sparse(&mut Cursor::new(bytes.as_slice()), 50).unwrap();
+        assert_eq!(row.columns_count(), 50);
+
+        for index in 0..50 {
+            if index % 5 == 0 {
+                assert!(!row.is_null(index));
+                assert_eq!(row.get(index), Some(&[index as u8][..]));
+            } else {
+                assert!(row.is_null(index));
+                assert_eq!(row.get(index), None);
+            }
+        }
+
+        assert!(!row.is_null(50));
+        assert_eq!(row.get(50), None);
+    }
+}
@@ -0,0 +1,36 @@
+use derive_more::Constructor;
+
+/// Day offset of the Unix epoch in `date` values.
+const EPOCH_DAY: i64 = 1 << 31;
+
+/// Cassandra `date` value - an unsigned number of days, with the Unix epoch at 2^31.
+#[derive(Debug, Clone, Copy, PartialEq, Constructor, Ord, PartialOrd, Eq, Hash)]
+pub struct CqlDate {
+    pub days: u32,
+}
+
+impl CqlDate {
+    /// Returns the number of days since the Unix epoch, negative for earlier dates.
+    #[inline]
+    pub fn days_since_epoch(self) -> i64 {
+        self.days as i64 - EPOCH_DAY
+    }
+}
+
+/// Cassandra `time` value - number of nanoseconds since midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Constructor, Ord, PartialOrd, Eq, Hash, Default)]
+pub struct CqlTime {
+    pub nanoseconds: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cql_date_days_since_epoch() {
+        assert_eq!(CqlDate::new(1 << 31).days_since_epoch(), 0);
+        assert_eq!(CqlDate::new((1 << 31) - 1).days_since_epoch(), -1);
+        assert_eq!(CqlDate::new(0).days_since_epoch(), -(1 << 31));
+    }
+}
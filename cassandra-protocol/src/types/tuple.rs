@@ -6,6 +6,7 @@ use uuid::Uuid;
 
 use crate::error::{column_is_empty_err, Error, Result};
 use crate::frame::frame_result::{CTuple, ColType, ColTypeOption, ColTypeOptionValue};
+use crate::frame::Version;
 use crate::types::blob::Blob;
 use crate::types::data_serialization_types::*;
 use crate::types::decimal::Decimal;
@@ -20,6 +21,7 @@ use std::hash::{Hash, Hasher};
 #[derive(Debug)]
 pub struct Tuple {
     data: Vec<(ColTypeOption, CBytes)>,
+    version: Version,
 }
 
 impl PartialEq for Tuple {
@@ -47,7 +49,7 @@ impl Hash for Tuple {
 }
 
 impl Tuple {
-    pub fn new(data: Vec<CBytes>, metadata: &CTuple) -> Tuple {
+    pub fn new(data: Vec<CBytes>, metadata: &CTuple, version: Version) -> Tuple {
         let meta_iter = metadata.types.iter();
 
         let acc = Vec::with_capacity(metadata.types.len());
@@ -57,7 +59,7 @@ impl Tuple {
             a
         });
 
-        Tuple { data: d }
+        Tuple { data: d, version }
     }
 }
 
@@ -8,6 +8,7 @@ use uuid::Uuid;
 
 use crate::error::{column_is_empty_err, Error, Result};
 use crate::frame::frame_result::{CUdt, ColType, ColTypeOption, ColTypeOptionValue};
+use crate::frame::Version;
 use crate::types::blob::Blob;
 use crate::types::data_serialization_types::*;
 use crate::types::decimal::Decimal;
@@ -20,10 +21,11 @@ use num::BigInt;
 #[derive(Clone, Debug)]
 pub struct Udt {
     data: HashMap<String, (ColTypeOption, CBytes)>,
+    version: Version,
 }
 
 impl Udt {
-    pub fn new(data: Vec<CBytes>, metadata: &CUdt) -> Udt {
+    pub fn new(data: Vec<CBytes>, metadata: &CUdt, version: Version) -> Udt {
         let meta_iter = metadata.descriptions.iter();
 
         let acc: HashMap<String, (ColTypeOption, CBytes)> =
@@ -36,7 +38,7 @@ impl Udt {
             a
         });
 
-        Udt { data: d }
+        Udt { data: d, version }
     }
 }
 
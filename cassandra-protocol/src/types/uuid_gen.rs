@@ -0,0 +1,44 @@
+//! Convenience functions for generating `uuid` and `timeuuid` values, which can be bound directly
+//! as query values.
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::v1::{Context, Timestamp};
+use uuid::Uuid;
+
+static CONTEXT: Context = Context::new(0);
+
+/// Creates a new random (version 4) uuid, suitable for `uuid` columns.
+#[inline]
+pub fn new_v4() -> Uuid {
+    Uuid::new_v4()
+}
+
+/// Creates a new time-based (version 1) uuid for the current time, suitable for `timeuuid`
+/// columns. The node id should be unique for the generating host, e.g. derived from its MAC
+/// address.
+pub fn now_v1(node_id: &[u8; 6]) -> Uuid {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    let timestamp = Timestamp::from_unix(&CONTEXT, now.as_secs(), now.subsec_nanos());
+
+    // node id always has the correct length, so this cannot fail
+    Uuid::new_v1(timestamp, node_id).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn now_v1_version() {
+        let uuid = now_v1(&[1, 2, 3, 4, 5, 6]);
+        assert_eq!(uuid.get_version_num(), 1);
+        assert_ne!(uuid, now_v1(&[1, 2, 3, 4, 5, 6]));
+    }
+
+    #[test]
+    fn new_v4_version() {
+        assert_eq!(new_v4().get_version_num(), 4);
+    }
+}
@@ -1,204 +1,215 @@
 use std::cmp::Eq;
 use std::collections::HashMap;
 use std::convert::Into;
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 use std::hash::Hash;
 use std::net::IpAddr;
-use std::num::{NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8};
+use std::sync::Arc;
 
 use chrono::prelude::*;
 use time::PrimitiveDateTime;
 use uuid::Uuid;
 
 use super::blob::Blob;
+use super::data_serialization_types::{
+    decode_bigint, decode_date, decode_int, decode_smallint, decode_time, decode_tinyint,
+    decode_varint,
+};
 use super::decimal::Decimal;
+use super::duration::CqlDuration;
 use super::*;
+use crate::constants::{NOT_SET_LENGTH, NULL_LENGTH};
+use crate::frame::frame_result::ColType;
 use crate::Error;
 
-const NULL_INT_VALUE: i32 = -1;
-const NOT_SET_INT_VALUE: i32 = -2;
-
-/// Cassandra value which could be an array of bytes, null and non-set values.
-#[derive(Debug, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
-pub enum Value {
-    Some(Vec<u8>),
-    Null,
-    NotSet,
-}
+pub use crate::encoding::{Bytes, Value};
 
 impl Value {
-    /// The factory method which creates a normal type value basing on provided bytes.
-    pub fn new<B>(v: B) -> Value
+    /// Creates a `list` value from given elements, without cloning them. Useful for binding
+    /// a variable number of keys in a `WHERE key IN ?` clause.
+    pub fn list_from_iter<I, T>(elements: I) -> Value
     where
-        B: Into<Bytes>,
+        I: IntoIterator<Item = T>,
+        T: Into<Value>,
     {
-        Value::Some(v.into().0)
+        let mut bytes = vec![0; INT_LEN];
+        let mut count: CInt = 0;
+
+        let mut cursor = Cursor::new(&mut bytes);
+        cursor.set_position(INT_LEN as u64);
+        for element in elements {
+            element.into().serialize(&mut cursor);
+            count += 1;
+        }
+
+        bytes[..INT_LEN].copy_from_slice(&count.to_be_bytes());
+        Value::Some(bytes)
     }
-}
 
-impl Serialize for Value {
-    fn serialize(&self, cursor: &mut Cursor<&mut Vec<u8>>) {
+    /// Number of bytes the value takes when serialized, including the `[int]` length prefix.
+    #[inline]
+    pub fn serialized_size(&self) -> usize {
         match self {
-            Value::Null => NULL_INT_VALUE.serialize(cursor),
-            Value::NotSet => NOT_SET_INT_VALUE.serialize(cursor),
-            Value::Some(value) => {
-                let len = value.len() as CInt;
-                len.serialize(cursor);
-                value.serialize(cursor);
-            }
+            Value::Some(value) => INT_LEN + value.len(),
+            _ => INT_LEN,
         }
     }
-}
 
-impl FromCursor for Value {
-    fn from_cursor(cursor: &mut Cursor<&[u8]>) -> Result<Value, Error> {
-        let value_size = {
-            let mut buff = [0; 4];
-            cursor.read_exact(&mut buff)?;
-            i32::from_be_bytes(buff)
-        };
-        if value_size > 0 {
-            Ok(Value::Some(cursor_next_value(cursor, value_size as usize)?))
-        } else if value_size == -1 {
-            Ok(Value::Null)
-        } else if value_size == -2 {
-            Ok(Value::NotSet)
-        } else {
-            Err(Error::General("Could not decode query values".into()))
+    /// Checks if the value can be encoded in a frame, i.e. its length fits in `[int]`.
+    pub fn validate(&self) -> Result<(), Error> {
+        match self {
+            Value::Some(value) => validate_value_length(value.len()),
+            _ => Ok(()),
         }
     }
-}
-
-impl<T: Into<Bytes>> From<T> for Value {
-    fn from(b: T) -> Value {
-        Value::new(b.into())
-    }
-}
 
-impl<T: Into<Bytes>> From<Option<T>> for Value {
-    fn from(b: Option<T>) -> Value {
-        match b {
-            Some(b) => Value::new(b.into()),
-            None => Value::Null,
-        }
-    }
-}
+    /// Compares values logically by decoding them as `col_type`, so that e.g. `varint`s with
+    /// redundant leading bytes are considered equal. Types without a decoded comparison, or
+    /// values which cannot be decoded, are compared byte by byte.
+    pub fn semantic_eq(&self, other: &Value, col_type: ColType) -> bool {
+        let (a, b) = match (self, other) {
+            (Value::Some(a), Value::Some(b)) => (a.as_slice(), b.as_slice()),
+            _ => return self == other,
+        };
 
-#[derive(Debug, Clone, Constructor)]
-pub struct Bytes(Vec<u8>);
+        let result = match col_type {
+            ColType::Varint => decoded_eq(decode_varint, a, b),
+            ColType::Bigint | ColType::Counter | ColType::Timestamp => {
+                decoded_eq(decode_bigint, a, b)
+            }
+            ColType::Time => decoded_eq(decode_time, a, b),
+            ColType::Int => decoded_eq(decode_int, a, b),
+            ColType::Date => decoded_eq(decode_date, a, b),
+            ColType::Smallint => decoded_eq(decode_smallint, a, b),
+            ColType::Tinyint => decoded_eq(decode_tinyint, a, b),
+            _ => None,
+        };
 
-impl From<String> for Bytes {
-    #[inline]
-    fn from(value: String) -> Self {
-        Bytes(value.into_bytes())
+        result.unwrap_or(a == b)
     }
 }
 
-impl From<&str> for Bytes {
-    #[inline]
-    fn from(value: &str) -> Self {
-        Bytes(value.as_bytes().to_vec())
+fn decoded_eq<T: PartialEq, E>(
+    decode: fn(&[u8]) -> Result<T, E>,
+    a: &[u8],
+    b: &[u8],
+) -> Option<bool> {
+    match (decode(a), decode(b)) {
+        (Ok(a), Ok(b)) => Some(a == b),
+        _ => None,
     }
 }
 
-impl From<i8> for Bytes {
-    #[inline]
-    fn from(value: i8) -> Self {
-        Bytes(vec![value as u8])
+fn validate_value_length(len: usize) -> Result<(), Error> {
+    if len > CInt::MAX as usize {
+        return Err(Error::General(format!(
+            "Value too large: {} bytes, maximum is {}",
+            len,
+            CInt::MAX
+        )));
     }
-}
 
-impl From<i16> for Bytes {
-    #[inline]
-    fn from(value: i16) -> Self {
-        Bytes(to_short(value))
-    }
+    Ok(())
 }
 
-impl From<i32> for Bytes {
-    #[inline]
-    fn from(value: i32) -> Self {
-        Bytes(to_int(value))
+impl Serialize for Value {
+    fn serialize(&self, cursor: &mut Cursor<&mut Vec<u8>>) {
+        match self {
+            Value::Null => NULL_LENGTH.serialize(cursor),
+            Value::NotSet => NOT_SET_LENGTH.serialize(cursor),
+            Value::Some(value) => {
+                let len = value.len() as CInt;
+                len.serialize(cursor);
+                value.serialize(cursor);
+            }
+        }
     }
 }
 
-impl From<i64> for Bytes {
-    #[inline]
-    fn from(value: i64) -> Self {
-        Bytes(to_bigint(value))
-    }
-}
+/// Maximum number of bytes shown when displaying a value.
+const DISPLAY_MAX_BYTES: usize = 32;
 
-impl From<u8> for Bytes {
-    #[inline]
-    fn from(value: u8) -> Self {
-        Bytes(vec![value])
-    }
-}
+impl Display for Value {
+    /// Renders `NULL`, `NOT SET` or hex encoded bytes, truncated for large values.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Some(value) => {
+                write!(f, "0x")?;
+                for byte in value.iter().take(DISPLAY_MAX_BYTES) {
+                    write!(f, "{:02x}", byte)?;
+                }
 
-impl From<u16> for Bytes {
-    #[inline]
-    fn from(value: u16) -> Self {
-        Bytes(to_u_short(value))
-    }
-}
+                if value.len() > DISPLAY_MAX_BYTES {
+                    write!(f, "... ({} bytes)", value.len())?;
+                }
 
-impl From<u32> for Bytes {
-    #[inline]
-    fn from(value: u32) -> Self {
-        Bytes(to_u_int(value))
+                Ok(())
+            }
+            Value::Null => write!(f, "NULL"),
+            Value::NotSet => write!(f, "NOT SET"),
+        }
     }
 }
 
-impl From<u64> for Bytes {
-    #[inline]
-    fn from(value: u64) -> Self {
-        Bytes(to_u_big(value))
+impl FromCursor for Value {
+    fn from_cursor(cursor: &mut Cursor<&[u8]>) -> Result<Value, Error> {
+        let value_size = {
+            let mut buff = [0; 4];
+            cursor.read_exact(&mut buff)?;
+            i32::from_be_bytes(buff)
+        };
+        if value_size >= 0 {
+            Ok(Value::Some(cursor_next_value(cursor, value_size as usize)?))
+        } else if value_size == NULL_LENGTH {
+            Ok(Value::Null)
+        } else if value_size == NOT_SET_LENGTH {
+            Ok(Value::NotSet)
+        } else {
+            Err(Error::General("Could not decode query values".into()))
+        }
     }
 }
 
-impl From<NonZeroI8> for Bytes {
-    #[inline]
-    fn from(value: NonZeroI8) -> Self {
-        value.get().into()
-    }
-}
+impl Bytes {
+    /// Serializes a map by consuming it, which avoids cloning keys and values as opposed to
+    /// `From<HashMap>`.
+    pub fn from_map_owned<K, V>(map: HashMap<K, V>) -> Bytes
+    where
+        K: Into<Bytes>,
+        V: Into<Bytes>,
+    {
+        let mut bytes: Vec<u8> = vec![];
+        let mut cursor = Cursor::new(&mut bytes);
 
-impl From<NonZeroI16> for Bytes {
-    #[inline]
-    fn from(value: NonZeroI16) -> Self {
-        value.get().into()
-    }
-}
+        let len = map.len() as i32;
+        len.serialize(&mut cursor);
 
-impl From<NonZeroI32> for Bytes {
-    #[inline]
-    fn from(value: NonZeroI32) -> Self {
-        value.get().into()
+        for (k, v) in map {
+            Value::new(k).serialize(&mut cursor);
+            Value::new(v).serialize(&mut cursor);
+        }
+
+        Bytes(bytes)
     }
 }
 
-impl From<NonZeroI64> for Bytes {
+impl From<Arc<str>> for Bytes {
     #[inline]
-    fn from(value: NonZeroI64) -> Self {
-        value.get().into()
+    fn from(value: Arc<str>) -> Self {
+        Bytes::from(value.as_ref())
     }
 }
 
-impl From<bool> for Bytes {
+impl From<Uuid> for Bytes {
     #[inline]
-    fn from(value: bool) -> Self {
-        if value {
-            Bytes(vec![1])
-        } else {
-            Bytes(vec![0])
-        }
+    fn from(value: Uuid) -> Self {
+        Bytes(value.as_bytes().to_vec())
     }
 }
 
-impl From<Uuid> for Bytes {
+impl From<&Uuid> for Bytes {
     #[inline]
-    fn from(value: Uuid) -> Self {
+    fn from(value: &Uuid) -> Self {
         Bytes(value.as_bytes().to_vec())
     }
 }
@@ -213,25 +224,16 @@ impl From<IpAddr> for Bytes {
     }
 }
 
-impl From<f32> for Bytes {
-    #[inline]
-    fn from(value: f32) -> Self {
-        Bytes(to_float(value))
-    }
-}
-
-impl From<f64> for Bytes {
-    #[inline]
-    fn from(value: f64) -> Self {
-        Bytes(to_float_big(value))
-    }
-}
-
+/// Encodes a `timestamp` column value, in milliseconds since the Unix epoch. Values outside of
+/// the `i64` range saturate instead of wrapping around.
 impl From<PrimitiveDateTime> for Bytes {
     #[inline]
     fn from(value: PrimitiveDateTime) -> Self {
-        let ts: i64 =
-            value.assume_utc().unix_timestamp() * 1_000 + value.nanosecond() as i64 / 1_000_000;
+        let ts: i64 = value
+            .assume_utc()
+            .unix_timestamp()
+            .saturating_mul(1_000)
+            .saturating_add(value.nanosecond() as i64 / 1_000_000);
         Bytes(to_bigint(ts))
     }
 }
@@ -250,6 +252,14 @@ impl From<Decimal> for Bytes {
     }
 }
 
+impl From<CqlDuration> for Bytes {
+    #[inline]
+    fn from(value: CqlDuration) -> Self {
+        Bytes(value.serialize_to_vec())
+    }
+}
+
+/// Encodes a `timestamp` column value, in milliseconds since the Unix epoch.
 impl From<NaiveDateTime> for Bytes {
     #[inline]
     fn from(value: NaiveDateTime) -> Self {
@@ -257,6 +267,7 @@ impl From<NaiveDateTime> for Bytes {
     }
 }
 
+/// Encodes a `timestamp` column value, in milliseconds since the Unix epoch.
 impl From<DateTime<Utc>> for Bytes {
     #[inline]
     fn from(value: DateTime<Utc>) -> Self {
@@ -300,9 +311,191 @@ where
     }
 }
 
+macro_rules! impl_from_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: Into<Value>),+> From<($($name,)+)> for Bytes {
+            /// Converts a tuple into positional CQL tuple encoding.
+            #[allow(non_snake_case)]
+            fn from(tuple: ($($name,)+)) -> Bytes {
+                let ($($name,)+) = tuple;
+
+                let mut bytes: Vec<u8> = vec![];
+                let mut cursor = Cursor::new(&mut bytes);
+                $(
+                    let value: Value = $name.into();
+                    value.serialize(&mut cursor);
+                )+
+
+                Bytes(bytes)
+            }
+        }
+    };
+}
+
+impl_from_tuple!(A, B);
+impl_from_tuple!(A, B, C);
+impl_from_tuple!(A, B, C, D);
+impl_from_tuple!(A, B, C, D, E);
+impl_from_tuple!(A, B, C, D, E, F);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::borrow::Cow;
+    use std::num::{NonZeroI16, NonZeroI32, NonZeroI64};
+
+    #[test]
+    fn test_timestamp_millis() {
+        let expected = Value::Some(1_500i64.to_be_bytes().to_vec());
+        let timestamp = Utc.timestamp_opt(1, 500_000_000).unwrap();
+        assert_eq!(Value::from(timestamp), expected);
+        assert_eq!(Value::from(timestamp.naive_utc()), expected);
+    }
+
+    #[test]
+    fn test_primitive_date_time_extreme_dates() {
+        let max = PrimitiveDateTime::new(time::Date::MAX, time::macros::time!(23:59:59.999));
+        assert_eq!(
+            Value::from(max),
+            Value::Some(253_402_300_799_999i64.to_be_bytes().to_vec())
+        );
+
+        let min = PrimitiveDateTime::new(time::Date::MIN, time::macros::time!(0:00));
+        assert_eq!(
+            Value::from(min),
+            Value::Some((-377_705_116_800_000i64).to_be_bytes().to_vec())
+        );
+    }
+
+    #[test]
+    fn test_value_from_cursor_roundtrip() {
+        for value in &[
+            Value::Some(vec![1, 2, 3]),
+            Value::Some(vec![]),
+            Value::Null,
+            Value::NotSet,
+        ] {
+            let bytes = value.serialize_to_vec();
+            let mut cursor = Cursor::new(bytes.as_slice());
+            assert_eq!(&Value::from_cursor(&mut cursor).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_nested_map_of_lists() {
+        use crate::frame::Version;
+        use crate::types::data_serialization_types::{decode_int, decode_list, decode_map};
+
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), vec![1i32, 2]);
+
+        let bytes = Bytes::from(map).0;
+        assert_eq!(
+            bytes,
+            vec![
+                0, 0, 0, 1, // map entry count
+                0, 0, 0, 1, b'a', // key
+                0, 0, 0, 20, // value length
+                0, 0, 0, 2, // list element count
+                0, 0, 0, 4, 0, 0, 0, 1, // element 1
+                0, 0, 0, 4, 0, 0, 0, 2, // element 2
+            ]
+        );
+
+        let entries = decode_map(&bytes, Version::V4).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0.as_slice(), Some(&b"a"[..]));
+
+        let list = decode_list(entries[0].1.as_slice().unwrap(), Version::V4).unwrap();
+        let list: Vec<i32> = list
+            .iter()
+            .map(|element| decode_int(element.as_slice().unwrap()).unwrap())
+            .collect();
+        assert_eq!(list, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_list_from_iter() {
+        let value = Value::list_from_iter(1..=5);
+
+        let mut expected = vec![0, 0, 0, 5];
+        for key in 1..=5i32 {
+            expected.extend_from_slice(&[0, 0, 0, 4]);
+            expected.extend_from_slice(&key.to_be_bytes());
+        }
+
+        assert_eq!(value, Value::Some(expected));
+        assert_eq!(value, Value::new(vec![1, 2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn test_value_display() {
+        assert_eq!(Value::Null.to_string(), "NULL");
+        assert_eq!(Value::NotSet.to_string(), "NOT SET");
+        assert_eq!(Value::Some(vec![0x01, 0xab, 0xff]).to_string(), "0x01abff");
+        assert_eq!(Value::Some(vec![]).to_string(), "0x");
+        assert_eq!(
+            Value::Some(vec![0; 40]).to_string(),
+            format!("0x{}... (40 bytes)", "00".repeat(32))
+        );
+    }
+
+    #[test]
+    fn test_value_serialized_size() {
+        for value in &[Value::Some(vec![1, 2, 3]), Value::Null, Value::NotSet] {
+            assert_eq!(value.serialized_size(), value.serialize_to_vec().len());
+        }
+    }
+
+    #[test]
+    fn test_borrowed_string_and_uuid() {
+        let string = "abc".to_string();
+        assert_eq!(Value::from(&string), Value::Some(b"abc".to_vec()));
+
+        let uuid = Uuid::from_bytes([1; 16]);
+        assert_eq!(Value::from(&uuid), Value::Some(vec![1; 16]));
+    }
+
+    #[test]
+    fn test_borrowed_optionals() {
+        struct Row {
+            name: Option<String>,
+            id: Option<Uuid>,
+        }
+
+        let row = Row {
+            name: Some("abc".into()),
+            id: None,
+        };
+
+        assert_eq!(
+            Value::from(row.name.as_deref()),
+            Value::Some(b"abc".to_vec())
+        );
+        assert_eq!(Value::from(row.name.as_ref()), Value::Some(b"abc".to_vec()));
+        assert_eq!(Value::from(row.id.as_ref()), Value::Null);
+        assert_eq!(Value::from(None::<&str>), Value::Null);
+    }
+
+    #[test]
+    fn test_value_semantic_eq() {
+        let short = Value::Some(vec![0x00, 0x80]);
+        let padded = Value::Some(vec![0x00, 0x00, 0x80]);
+
+        assert_ne!(short, padded);
+        assert!(short.semantic_eq(&padded, ColType::Varint));
+        assert!(!short.semantic_eq(&Value::Some(vec![0x80]), ColType::Varint));
+        assert!(!short.semantic_eq(&padded, ColType::Blob));
+        assert!(Value::Null.semantic_eq(&Value::Null, ColType::Varint));
+    }
+
+    #[test]
+    fn test_value_length_validation() {
+        assert!(Value::Some(vec![1, 2, 3]).validate().is_ok());
+        assert!(Value::Null.validate().is_ok());
+        assert!(validate_value_length(CInt::MAX as usize).is_ok());
+        assert!(validate_value_length(CInt::MAX as usize + 1).is_err());
+    }
 
     #[test]
     fn test_value_serialization() {
@@ -337,4 +530,81 @@ mod tests {
         assert_eq!(Value::new(1_i64), Value::Some(vec!(0, 0, 0, 0, 0, 0, 0, 1)));
         assert_eq!(Value::new(true), Value::Some(vec!(1)));
     }
+
+    #[test]
+    fn test_tuple() {
+        assert_eq!(
+            Value::new((1_i32, "a")),
+            Value::Some(vec![0, 0, 0, 4, 0, 0, 0, 1, 0, 0, 0, 1, 97])
+        );
+        assert_eq!(
+            Value::new((1_i8, None::<i32>, true)),
+            Value::Some(vec![0, 0, 0, 1, 1, 255, 255, 255, 255, 0, 0, 0, 1, 1])
+        );
+    }
+
+    #[test]
+    fn test_map_owned() {
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), 1_i32);
+        map.insert("b".to_string(), 2_i32);
+        map.insert("c".to_string(), 3_i32);
+
+        assert_eq!(
+            Value::new(Bytes::from_map_owned(map.clone())),
+            Value::new(map)
+        );
+    }
+
+    #[test]
+    fn test_new_value_shared_strings() {
+        let expected = Value::new("hello");
+        assert_eq!(Value::new(Cow::Borrowed("hello")), expected);
+        assert_eq!(Value::new(Cow::<str>::Owned("hello".to_string())), expected);
+        assert_eq!(Value::new(Arc::<str>::from("hello")), expected);
+    }
+
+    #[test]
+    fn test_from_byte_array() {
+        assert_eq!(Bytes::from([1u8, 2, 3, 4]).0, vec![1, 2, 3, 4]);
+        assert_eq!(Value::new([1u8, 2, 3, 4]), Value::Some(vec![1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn test_numeric_bytes_are_big_endian() {
+        assert_eq!(Bytes::from(-2i8).0, vec![0xFE]);
+        assert_eq!(Bytes::from(0x0102i16).0, vec![1, 2]);
+        assert_eq!(Bytes::from(0x01020304i32).0, vec![1, 2, 3, 4]);
+        assert_eq!(
+            Bytes::from(0x0102030405060708i64).0,
+            vec![1, 2, 3, 4, 5, 6, 7, 8]
+        );
+        assert_eq!(Bytes::from(0xFEu8).0, vec![0xFE]);
+        assert_eq!(Bytes::from(0x0102u16).0, vec![1, 2]);
+        assert_eq!(Bytes::from(0x01020304u32).0, vec![1, 2, 3, 4]);
+        assert_eq!(
+            Bytes::from(0x0102030405060708u64).0,
+            vec![1, 2, 3, 4, 5, 6, 7, 8]
+        );
+        assert_eq!(Bytes::from(NonZeroI16::new(0x0102).unwrap()).0, vec![1, 2]);
+        assert_eq!(
+            Bytes::from(NonZeroI32::new(0x01020304).unwrap()).0,
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(
+            Bytes::from(NonZeroI64::new(0x0102030405060708).unwrap()).0,
+            vec![1, 2, 3, 4, 5, 6, 7, 8]
+        );
+
+        // IEEE-754 representations of 1.5
+        assert_eq!(Bytes::from(1.5f32).0, vec![0x3F, 0xC0, 0, 0]);
+        assert_eq!(Bytes::from(1.5f64).0, vec![0x3F, 0xF8, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_value_bytes() {
+        assert_eq!(Value::Some(vec![1, 2]).bytes(), Some(&[1, 2][..]));
+        assert_eq!(Value::Null.bytes(), None);
+        assert_eq!(Value::NotSet.bytes(), None);
+    }
 }
@@ -0,0 +1,140 @@
+use derive_more::Constructor;
+use std::io;
+use uuid::Uuid;
+
+use crate::error::{self, Error};
+use crate::types::data_serialization_types::*;
+use crate::types::value::Bytes;
+
+/// Element of a vector with a fixed-width serialized form. Such elements are serialized
+/// contiguously, without per-element length prefixes.
+pub trait VectorElement: Sized {
+    /// Serialized element length in bytes.
+    const WIDTH: usize;
+
+    fn encode_element(&self, buffer: &mut Vec<u8>);
+
+    fn decode_element(bytes: &[u8]) -> Result<Self, io::Error>;
+}
+
+macro_rules! vector_element {
+    ($t:ty, $decode:ident) => {
+        impl VectorElement for $t {
+            const WIDTH: usize = std::mem::size_of::<$t>();
+
+            #[inline]
+            fn encode_element(&self, buffer: &mut Vec<u8>) {
+                buffer.extend_from_slice(&self.to_be_bytes());
+            }
+
+            #[inline]
+            fn decode_element(bytes: &[u8]) -> Result<Self, io::Error> {
+                $decode(bytes)
+            }
+        }
+    };
+}
+
+vector_element!(f32, decode_float);
+vector_element!(f64, decode_double);
+vector_element!(i32, decode_int);
+vector_element!(i64, decode_bigint);
+
+impl VectorElement for Uuid {
+    const WIDTH: usize = 16;
+
+    #[inline]
+    fn encode_element(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(self.as_bytes());
+    }
+
+    #[inline]
+    fn decode_element(bytes: &[u8]) -> Result<Self, io::Error> {
+        decode_timeuuid(bytes).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+/// Cassandra fixed-size `vector<type, dimension>` value (Cassandra 5.0+), commonly used with
+/// `float` elements for similarity search.
+#[derive(Debug, Clone, PartialEq, Default, Constructor)]
+pub struct CqlVector<T> {
+    pub elements: Vec<T>,
+}
+
+impl<T: VectorElement> CqlVector<T> {
+    /// Decodes a vector of given dimension from its serialized form.
+    pub fn decode(bytes: &[u8], dimension: usize) -> error::Result<Self> {
+        let expected_len = T::WIDTH
+            .checked_mul(dimension)
+            .ok_or_else(|| Error::Protocol(format!("Vector dimension too large: {}", dimension)))?;
+
+        if bytes.len() != expected_len {
+            return Err(Error::Protocol(format!(
+                "Expected {} bytes for vector of dimension {}, got {}",
+                expected_len,
+                dimension,
+                bytes.len()
+            )));
+        }
+
+        bytes
+            .chunks_exact(T::WIDTH)
+            .map(T::decode_element)
+            .collect::<Result<_, _>>()
+            .map(CqlVector::new)
+            .map_err(Error::from)
+    }
+
+    #[inline]
+    pub fn dimension(&self) -> usize {
+        self.elements.len()
+    }
+}
+
+impl<T> From<Vec<T>> for CqlVector<T> {
+    #[inline]
+    fn from(elements: Vec<T>) -> Self {
+        CqlVector::new(elements)
+    }
+}
+
+impl<T: VectorElement> From<CqlVector<T>> for Bytes {
+    fn from(value: CqlVector<T>) -> Self {
+        let mut buffer = Vec::with_capacity(T::WIDTH * value.elements.len());
+        for element in &value.elements {
+            element.encode_element(&mut buffer);
+        }
+
+        Bytes::new(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::value::Value;
+
+    #[test]
+    fn float_vector_roundtrip() {
+        let vector = CqlVector::new(vec![1.0f32, -2.5, 0.0]);
+
+        let bytes = match Value::from(vector.clone()) {
+            Value::Some(bytes) => bytes,
+            value => panic!("Unexpected value: {:?}", value),
+        };
+        assert_eq!(bytes.len(), 12);
+        assert_eq!(&bytes[..4], &1.0f32.to_be_bytes());
+
+        let decoded = CqlVector::<f32>::decode(&bytes, 3).unwrap();
+        assert_eq!(decoded, vector);
+        assert!(CqlVector::<f32>::decode(&bytes, 4).is_err());
+    }
+
+    #[test]
+    fn vector_dimension_overflow() {
+        assert!(matches!(
+            CqlVector::<i64>::decode(&[], usize::MAX),
+            Err(Error::Protocol(_))
+        ));
+    }
+}
@@ -0,0 +1,46 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::*;
+use syn::{DeriveInput, Index, Member};
+
+use crate::common::{get_ident_string, struct_fields};
+
+pub fn impl_from_row(ast: &DeriveInput) -> TokenStream {
+    let name = &ast.ident;
+    let fields = struct_fields(ast).iter().enumerate().map(|(index, field)| {
+        let optional = get_ident_string(&field.ty) == "Option";
+
+        match &field.ident {
+            Some(ident) => {
+                let column = ident.to_string();
+                let column = Literal::string(column.strip_prefix("r#").unwrap_or(&column));
+                let value = if optional {
+                    quote! { row.get_opt(#column)? }
+                } else {
+                    quote! { row.get(#column)? }
+                };
+
+                quote! { #ident: #value }
+            }
+            None => {
+                let member = Member::Unnamed(Index::from(index));
+                let value = if optional {
+                    quote! { row.get_opt_by_index(#index)? }
+                } else {
+                    quote! { row.get_by_index(#index)? }
+                };
+
+                quote! { #member: #value }
+            }
+        }
+    });
+
+    quote! {
+        impl #name {
+            pub fn from_row(row: &cdrs_tokio::types::rows::Row) -> cdrs_tokio::Result<Self> {
+                Ok(#name {
+                    #(#fields),*
+                })
+            }
+        }
+    }
+}
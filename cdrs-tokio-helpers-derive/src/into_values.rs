@@ -0,0 +1,27 @@
+use proc_macro2::TokenStream;
+use quote::*;
+use syn::{DeriveInput, Index, Member};
+
+use crate::common::struct_fields;
+
+pub fn impl_into_values(ast: &DeriveInput) -> TokenStream {
+    let name = &ast.ident;
+    let members = struct_fields(ast)
+        .iter()
+        .enumerate()
+        .map(|(index, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        })
+        .collect::<Vec<_>>();
+
+    quote! {
+        impl #name {
+            pub fn into_values(self) -> Vec<cdrs_tokio::types::value::Value> {
+                vec![
+                    #(self.#members.into()),*
+                ]
+            }
+        }
+    }
+}
@@ -5,12 +5,16 @@ use syn::{parse_macro_input, DeriveInput};
 
 mod common;
 mod db_mirror;
+mod from_row;
 mod into_cdrs_value;
+mod into_values;
 mod try_from_row;
 mod try_from_udt;
 
 use crate::db_mirror::impl_db_mirror;
+use crate::from_row::impl_from_row;
 use crate::into_cdrs_value::impl_into_cdrs_value;
+use crate::into_values::impl_into_values;
 use crate::try_from_row::impl_try_from_row;
 use crate::try_from_udt::impl_try_from_udt;
 
@@ -23,6 +27,17 @@ pub fn db_mirror(input: TokenStream) -> TokenStream {
     impl_db_mirror(&ast).into()
 }
 
+/// Generates `fn from_row(row: &Row) -> Result<Self>`, decoding scalar columns matching field
+/// names (or positions, for tuple structs). `Option` fields accept null values.
+#[proc_macro_derive(FromRow)]
+pub fn from_row(input: TokenStream) -> TokenStream {
+    // Parse the string representation
+    let ast = parse_macro_input!(input as DeriveInput);
+
+    // Build the impl
+    impl_from_row(&ast).into()
+}
+
 #[proc_macro_derive(IntoCdrsValue)]
 pub fn into_cdrs_value(input: TokenStream) -> TokenStream {
     // Parse the string representation
@@ -32,6 +47,17 @@ pub fn into_cdrs_value(input: TokenStream) -> TokenStream {
     impl_into_cdrs_value(&ast).into()
 }
 
+/// Generates `fn into_values(self) -> Vec<Value>`, converting fields into positional query
+/// values in declaration order. Every field needs to implement `Into<Value>`.
+#[proc_macro_derive(IntoValues)]
+pub fn into_values(input: TokenStream) -> TokenStream {
+    // Parse the string representation
+    let ast = parse_macro_input!(input as DeriveInput);
+
+    // Build the impl
+    impl_into_values(&ast).into()
+}
+
 #[proc_macro_derive(TryFromRow)]
 pub fn try_from_row(input: TokenStream) -> TokenStream {
     // Parse the string representation
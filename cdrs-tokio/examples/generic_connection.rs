@@ -112,9 +112,11 @@ impl VirtualConnectionManager {
                 config.keyspace_holder.clone(),
                 config.reconnection_policy.clone(),
                 Compression::None,
+                0,
                 DEFAULT_TRANSPORT_BUFFER_SIZE,
                 true,
                 config.version,
+                false,
             ),
             mask: config.mask,
             actual: config.actual,
@@ -1,4 +1,5 @@
 pub(crate) use self::cluster_metadata_manager::ClusterMetadataManager;
+pub use self::composite_key_builder::CompositeKeyBuilder;
 #[cfg(feature = "rust-tls")]
 pub use self::config_rustls::{NodeRustlsConfig, NodeRustlsConfigBuilder};
 pub use self::config_tcp::{NodeTcpConfig, NodeTcpConfigBuilder};
@@ -9,6 +10,7 @@ pub use self::node_info::NodeInfo;
 pub use self::pager::{ExecPager, PagerState, QueryPager, SessionPager};
 #[cfg(feature = "rust-tls")]
 pub use self::rustls_connection_manager::RustlsConnectionManager;
+pub use self::schema_agreement::schema_versions_agree;
 pub use self::session::connect_generic;
 pub(crate) use self::session_context::SessionContext;
 pub use self::tcp_connection_manager::TcpConnectionManager;
@@ -20,6 +22,7 @@ use cassandra_protocol::error;
 use cassandra_protocol::frame::Version;
 
 mod cluster_metadata_manager;
+mod composite_key_builder;
 #[cfg(feature = "rust-tls")]
 mod config_rustls;
 mod config_tcp;
@@ -32,6 +35,7 @@ mod node_info;
 mod pager;
 #[cfg(feature = "rust-tls")]
 mod rustls_connection_manager;
+mod schema_agreement;
 mod send_frame;
 pub mod session;
 mod session_context;
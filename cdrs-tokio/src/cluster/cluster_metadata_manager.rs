@@ -1,8 +1,7 @@
-use arc_swap::ArcSwap;
+use arc_swap::{ArcSwap, ArcSwapOption};
 use fxhash::FxHashMap;
 use itertools::Itertools;
 use serde_json::{Map, Value as JsonValue};
-use std::convert::TryInto;
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -12,7 +11,9 @@ use tokio::sync::broadcast::Receiver;
 use tracing::*;
 
 use crate::cluster::metadata_builder::{add_new_node, build_initial_metadata, refresh_metadata};
-use crate::cluster::topology::{KeyspaceMetadata, Node, NodeState, ReplicationStrategy};
+use crate::cluster::topology::{
+    KeyspaceMetadata, Node, NodeState, Partitioner, ReplicationStrategy,
+};
 use crate::cluster::{ClusterMetadata, ConnectionManager};
 use crate::cluster::{NodeInfo, SessionContext};
 use crate::load_balancing::node_distance_evaluator::NodeDistanceEvaluator;
@@ -25,16 +26,17 @@ use cassandra_protocol::frame::events::{
 };
 use cassandra_protocol::frame::frame_error::{AdditionalErrorInfo, CdrsError};
 use cassandra_protocol::frame::{Frame, Version};
+use cassandra_protocol::query::query_params::{Murmur3Token, Token};
 use cassandra_protocol::query::utils::prepare_flags;
 use cassandra_protocol::query::{Query, QueryParams, QueryParamsBuilder, QueryValues};
-use cassandra_protocol::types::list::List;
 use cassandra_protocol::types::rows::Row;
-use cassandra_protocol::types::{AsRustType, ByName, IntoRustByName};
+use cassandra_protocol::types::{ByName, IntoRustByName};
 
 fn find_in_peers(
     peers: &[Row],
     broadcast_rpc_address: SocketAddr,
     control_addr: SocketAddr,
+    partitioner: Option<Partitioner>,
 ) -> Result<Option<NodeInfo>> {
     peers
         .iter()
@@ -43,7 +45,7 @@ fn find_in_peers(
                 .filter(|peer_address| {
                     *peer_address == broadcast_rpc_address && is_peer_row_valid(peer)
                 })
-                .map(|peer_address| build_node_info(peer, peer_address))
+                .map(|peer_address| build_node_info(peer, peer_address, partitioner))
         })
         .transpose()
 }
@@ -91,7 +93,11 @@ async fn send_query_with_params<T: CdrsTransport>(
         .map(|body| body.into_rows())
 }
 
-fn build_node_info(row: &Row, broadcast_rpc_address: SocketAddr) -> Result<NodeInfo> {
+fn build_node_info(
+    row: &Row,
+    broadcast_rpc_address: SocketAddr,
+    partitioner: Option<Partitioner>,
+) -> Result<NodeInfo> {
     row.get_r_by_name("host_id").and_then(move |host_id| {
         let broadcast_address: Option<IpAddr> = row
             .get_by_name("broadcast_address")
@@ -115,23 +121,49 @@ fn build_node_info(row: &Row, broadcast_rpc_address: SocketAddr) -> Result<NodeI
 
         let datacenter = row.get_r_by_name("data_center")?;
         let rack = row.get_r_by_name("rack")?;
-        let tokens: List = row.get_r_by_name("tokens")?;
-        let tokens: Vec<String> = tokens.as_r_type()?;
+        let tokens = build_node_tokens(row, partitioner)?;
+        let schema_version = row.get_opt("schema_version")?;
 
         Ok(NodeInfo::new(
             host_id,
             broadcast_rpc_address,
             broadcast_address,
             datacenter,
-            tokens
-                .into_iter()
-                .filter_map(|token| token.try_into().ok()) // ignore unsupported tokens
-                .collect(),
+            tokens,
             rack,
+            schema_version,
         ))
     })
 }
 
+// The token map only supports Murmur3 tokens, so nodes of clusters using other partitioners
+// have none, which disables token aware routing.
+fn build_node_tokens(row: &Row, partitioner: Option<Partitioner>) -> Result<Vec<Murmur3Token>> {
+    match partitioner {
+        Some(Partitioner::Murmur3) => Ok(Partitioner::Murmur3
+            .parse_tokens(row)?
+            .into_iter()
+            .filter_map(|token| match token {
+                Token::Murmur3(value) => Some(Murmur3Token::new(value)),
+                Token::Random(_) => None,
+            })
+            .collect()),
+        _ => Ok(vec![]),
+    }
+}
+
+fn partitioner_from_row(row: &Row) -> Option<Partitioner> {
+    let partitioner: Option<String> = row.get_by_name("partitioner").ok()?;
+    let partitioner = partitioner?;
+
+    let result = Partitioner::from_name(&partitioner);
+    if result.is_none() {
+        warn!(%partitioner, "Unsupported partitioner - token aware routing will be disabled.");
+    }
+
+    result
+}
+
 fn build_node_broadcast_rpc_address(
     row: &Row,
     broadcast_rpc_address: Option<SocketAddr>,
@@ -295,6 +327,7 @@ pub struct ClusterMetadataManager<T: CdrsTransport + 'static, CM: ConnectionMana
     connection_manager: Arc<CM>,
     did_initial_refresh: AtomicBool,
     is_schema_v2: AtomicBool,
+    partitioner: ArcSwapOption<Partitioner>,
     session_context: Arc<SessionContext<T>>,
     node_distance_evaluator: Box<dyn NodeDistanceEvaluator + Send + Sync>,
     version: Version,
@@ -314,6 +347,7 @@ impl<T: CdrsTransport + 'static, CM: ConnectionManager<T> + 'static> ClusterMeta
             connection_manager,
             did_initial_refresh: AtomicBool::new(false),
             is_schema_v2: AtomicBool::new(true),
+            partitioner: ArcSwapOption::empty(),
             session_context,
             node_distance_evaluator,
             version,
@@ -516,7 +550,12 @@ impl<T: CdrsTransport + 'static, CM: ConnectionManager<T> + 'static> ClusterMeta
             )
             .await?;
 
-            return build_node_info(&local_info, broadcast_rpc_address).map(Some);
+            return build_node_info(
+                &local_info,
+                broadcast_rpc_address,
+                partitioner_from_row(&local_info),
+            )
+            .map(Some);
         }
 
         send_query(
@@ -527,7 +566,13 @@ impl<T: CdrsTransport + 'static, CM: ConnectionManager<T> + 'static> ClusterMeta
         .await
         .map(|peers| {
             peers.and_then(|peers| {
-                find_in_peers(&peers, broadcast_rpc_address, control_addr).transpose()
+                find_in_peers(
+                    &peers,
+                    broadcast_rpc_address,
+                    control_addr,
+                    self.partitioner(),
+                )
+                .transpose()
             })
         })?
         .transpose()
@@ -542,6 +587,11 @@ impl<T: CdrsTransport + 'static, CM: ConnectionManager<T> + 'static> ClusterMeta
             .ok_or_else(|| "Cannot fetch information without a control connection!".into())
     }
 
+    #[inline]
+    fn partitioner(&self) -> Option<Partitioner> {
+        self.partitioner.load().as_deref().copied()
+    }
+
     #[inline]
     fn peer_table_name(&self) -> &'static str {
         if self.is_schema_v2.load(Ordering::Relaxed) {
@@ -621,7 +671,14 @@ impl<T: CdrsTransport + 'static, CM: ConnectionManager<T> + 'static> ClusterMeta
         let local_broadcast_rpc_address =
             build_node_broadcast_rpc_address(&local, local_broadcast_rpc_address, control_addr);
 
-        let mut node_infos = vec![build_node_info(&local, local_broadcast_rpc_address)?];
+        let partitioner = partitioner_from_row(&local);
+        self.partitioner.store(partitioner.map(Arc::new));
+
+        let mut node_infos = vec![build_node_info(
+            &local,
+            local_broadcast_rpc_address,
+            partitioner,
+        )?];
 
         let peers = self.query_peers(control_transport.as_ref()).await?;
         if let Some(peers) = peers {
@@ -633,8 +690,9 @@ impl<T: CdrsTransport + 'static, CM: ConnectionManager<T> + 'static> ClusterMeta
                         return None;
                     }
 
-                    broadcast_rpc_address_from_row(row, control_addr)
-                        .map(|broadcast_rpc_address| build_node_info(row, broadcast_rpc_address))
+                    broadcast_rpc_address_from_row(row, control_addr).map(|broadcast_rpc_address| {
+                        build_node_info(row, broadcast_rpc_address, partitioner)
+                    })
                 })
                 .fold_ok(node_infos, |mut node_infos, node_info| {
                     node_infos.push(node_info);
@@ -662,3 +720,138 @@ impl<T: CdrsTransport + 'static, CM: ConnectionManager<T> + 'static> ClusterMeta
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use cassandra_protocol::frame::frame_result::{
+        ColSpec, ColType, ColTypeOption, ColTypeOptionValue, RowsMetadata, RowsMetadataFlags,
+    };
+    use cassandra_protocol::frame::Version;
+    use cassandra_protocol::query::query_params::Murmur3Token;
+    use cassandra_protocol::testing::mock_row;
+    use cassandra_protocol::types::value::Value;
+    use cassandra_protocol::types::{CBytes, CString};
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    use super::{build_node_info, build_node_tokens, partitioner_from_row};
+    use crate::cluster::topology::Partitioner;
+    use cassandra_protocol::types::rows::Row;
+
+    fn col_spec(name: &str, id: ColType, value: Option<ColTypeOptionValue>) -> ColSpec {
+        ColSpec {
+            table_spec: None,
+            name: CString::new(name.into()),
+            col_type: ColTypeOption { id, value },
+        }
+    }
+
+    #[test]
+    fn should_read_host_id_and_schema_version() {
+        let host_id = Uuid::new_v4();
+        let schema_version = Uuid::new_v4();
+
+        let col_specs = vec![
+            col_spec("host_id", ColType::Uuid, None),
+            col_spec("schema_version", ColType::Uuid, None),
+            col_spec("broadcast_address", ColType::Inet, None),
+            col_spec("data_center", ColType::Varchar, None),
+            col_spec("rack", ColType::Varchar, None),
+            col_spec(
+                "tokens",
+                ColType::Set,
+                Some(ColTypeOptionValue::CSet(Box::new(ColTypeOption {
+                    id: ColType::Varchar,
+                    value: None,
+                }))),
+            ),
+        ];
+        let metadata = Arc::new(RowsMetadata {
+            flags: RowsMetadataFlags::empty(),
+            columns_count: col_specs.len() as i32,
+            paging_state: None,
+            new_metadata_id: None,
+            global_table_spec: None,
+            col_specs,
+        });
+
+        let row = Row::new(
+            metadata,
+            vec![
+                CBytes::new(host_id.as_bytes().to_vec()),
+                CBytes::new(schema_version.as_bytes().to_vec()),
+                CBytes::new_empty(),
+                CBytes::new(b"dc1".to_vec()),
+                CBytes::new(b"rack1".to_vec()),
+                CBytes::new(vec![0, 0, 0, 1, 0, 0, 0, 1, b'1']),
+            ],
+            Version::V4,
+        );
+
+        let address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 9042);
+        let node_info = build_node_info(&row, address, Some(Partitioner::Murmur3)).unwrap();
+
+        assert_eq!(node_info.host_id, host_id);
+        assert_eq!(node_info.schema_version, Some(schema_version));
+        assert_eq!(node_info.datacenter, "dc1");
+        assert_eq!(node_info.rack, "rack1");
+        assert_eq!(node_info.tokens.len(), 1);
+    }
+
+    #[test]
+    fn should_parse_tokens_with_local_partitioner() {
+        let text_type = ColTypeOption {
+            id: ColType::Varchar,
+            value: None,
+        };
+        let tokens_type = ColTypeOption {
+            id: ColType::Set,
+            value: Some(ColTypeOptionValue::CSet(Box::new(text_type.clone()))),
+        };
+
+        let row = mock_row(
+            Version::V4,
+            vec![
+                (
+                    "partitioner",
+                    text_type.clone(),
+                    Value::new("org.apache.cassandra.dht.Murmur3Partitioner"),
+                ),
+                (
+                    "tokens",
+                    tokens_type.clone(),
+                    Value::list_from_iter(["-5", "0", "7"]),
+                ),
+            ],
+        );
+
+        let partitioner = partitioner_from_row(&row);
+        assert_eq!(partitioner, Some(Partitioner::Murmur3));
+        assert_eq!(
+            build_node_tokens(&row, partitioner).unwrap(),
+            vec![
+                Murmur3Token::new(-5),
+                Murmur3Token::new(0),
+                Murmur3Token::new(7)
+            ]
+        );
+
+        // tokens of other partitioners cannot be put in the token map
+        assert!(build_node_tokens(&row, Some(Partitioner::Random))
+            .unwrap()
+            .is_empty());
+        assert!(build_node_tokens(&row, None).unwrap().is_empty());
+
+        let row = mock_row(
+            Version::V4,
+            vec![(
+                "tokens",
+                tokens_type,
+                Value::list_from_iter(["170141183460469231731687303715884105727"]),
+            )],
+        );
+        assert_eq!(partitioner_from_row(&row), None);
+        assert!(build_node_tokens(&row, Some(Partitioner::Murmur3)).is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use std::io::{Cursor, Write};
+
+use cassandra_protocol::frame::Serialize;
+use cassandra_protocol::types::CIntShort;
+
+// https://github.com/apache/cassandra/blob/3a950b45c321e051a9744721408760c568c05617/src/java/org/apache/cassandra/db/marshal/CompositeType.java#L39
+
+/// Builds a routing key for partition keys, which can be used to compute the token for
+/// token-aware routing. Each component of a composite key is encoded as
+/// `[short len][bytes][0x00]`, while a single-component key is the raw value itself.
+#[derive(Default, Clone, Debug)]
+pub struct CompositeKeyBuilder {
+    components: Vec<Vec<u8>>,
+}
+
+impl CompositeKeyBuilder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Appends a serialized partition key component, in partition key order.
+    pub fn add_component<T: Into<Vec<u8>>>(mut self, component: T) -> Self {
+        self.components.push(component.into());
+        self
+    }
+
+    /// Builds the final routing key.
+    pub fn build(mut self) -> Vec<u8> {
+        if self.components.len() == 1 {
+            return self.components.pop().unwrap_or_default();
+        }
+
+        let mut buf = Vec::with_capacity(
+            self.components
+                .iter()
+                .map(|component| component.len() + 3)
+                .sum(),
+        );
+
+        let mut cursor = Cursor::new(&mut buf);
+        for component in &self.components {
+            let len = component.len() as CIntShort;
+            len.serialize(&mut cursor);
+            component.serialize(&mut cursor);
+            let _ = cursor.write(&[0]);
+        }
+
+        buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CompositeKeyBuilder;
+
+    #[test]
+    fn should_build_composite_key() {
+        let key = CompositeKeyBuilder::new()
+            .add_component(vec![0, 1])
+            .add_component(vec![2])
+            .build();
+
+        assert_eq!(key, vec![0, 2, 0, 1, 0, 0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn should_build_single_component_key() {
+        let key = CompositeKeyBuilder::new()
+            .add_component(vec![0, 0, 0, 1])
+            .build();
+
+        assert_eq!(key, vec![0, 0, 0, 1]);
+    }
+}
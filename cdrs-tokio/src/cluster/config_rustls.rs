@@ -14,6 +14,9 @@ pub struct NodeRustlsConfig {
     pub authenticator_provider: Arc<dyn SaslAuthenticatorProvider + Send + Sync>,
     pub config: Arc<rustls::ClientConfig>,
     pub version: Version,
+    /// Sets the beta flag on all frames, which is required to use a protocol version supported
+    /// by the server only as a beta (e.g. v5 on Cassandra 3.x).
+    pub use_beta: bool,
 }
 
 /// Builder structure that helps to configure TLS connection for node.
@@ -23,6 +26,7 @@ pub struct NodeRustlsConfigBuilder {
     authenticator_provider: Arc<dyn SaslAuthenticatorProvider + Send + Sync>,
     config: Arc<rustls::ClientConfig>,
     version: Version,
+    use_beta: bool,
 }
 
 impl NodeRustlsConfigBuilder {
@@ -33,6 +37,7 @@ impl NodeRustlsConfigBuilder {
             authenticator_provider: Arc::new(NoneAuthenticatorProvider),
             config,
             version: Version::V4,
+            use_beta: false,
         }
     }
 
@@ -73,6 +78,13 @@ impl NodeRustlsConfigBuilder {
         self
     }
 
+    /// Sets the beta flag on all frames, required when the server supports the requested protocol
+    /// version only as a beta.
+    pub fn with_beta(mut self, use_beta: bool) -> Self {
+        self.use_beta = use_beta;
+        self
+    }
+
     /// Finalizes building process
     pub async fn build(self) -> Result<NodeRustlsConfig> {
         // replace with map() when async lambdas become available
@@ -87,6 +99,7 @@ impl NodeRustlsConfigBuilder {
             authenticator_provider: self.authenticator_provider,
             config: self.config,
             version: self.version,
+            use_beta: self.use_beta,
         })
     }
 }
@@ -12,6 +12,9 @@ pub struct NodeTcpConfig {
     pub contact_points: Vec<SocketAddr>,
     pub authenticator_provider: Arc<dyn SaslAuthenticatorProvider + Send + Sync>,
     pub version: Version,
+    /// Sets the beta flag on all frames, which is required to use a protocol version supported
+    /// by the server only as a beta (e.g. v5 on Cassandra 3.x).
+    pub use_beta: bool,
 }
 
 /// Builder structure that helps to configure TCP connection for node.
@@ -19,6 +22,7 @@ pub struct NodeTcpConfigBuilder {
     addrs: Vec<NodeAddress>,
     authenticator_provider: Arc<dyn SaslAuthenticatorProvider + Send + Sync>,
     version: Version,
+    use_beta: bool,
 }
 
 impl Default for NodeTcpConfigBuilder {
@@ -27,6 +31,7 @@ impl Default for NodeTcpConfigBuilder {
             addrs: vec![],
             authenticator_provider: Arc::new(NoneAuthenticatorProvider),
             version: Version::V4,
+            use_beta: false,
         }
     }
 }
@@ -73,6 +78,13 @@ impl NodeTcpConfigBuilder {
         self
     }
 
+    /// Sets the beta flag on all frames, required when the server supports the requested protocol
+    /// version only as a beta.
+    pub fn with_beta(mut self, use_beta: bool) -> Self {
+        self.use_beta = use_beta;
+        self
+    }
+
     /// Finalizes building process
     pub async fn build(self) -> Result<NodeTcpConfig> {
         // replace with map() when async lambdas become available
@@ -85,6 +97,7 @@ impl NodeTcpConfigBuilder {
             contact_points,
             authenticator_provider: self.authenticator_provider,
             version: self.version,
+            use_beta: self.use_beta,
         })
     }
 }
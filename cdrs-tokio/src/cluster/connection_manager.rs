@@ -41,6 +41,23 @@ mock! {
     }
 }
 
+/// Checks if establishing a connection should be retried without compression, which happens when
+/// the server rejects the compression requested in STARTUP.
+pub(crate) fn should_retry_without_compression(error: &Error, compression: Compression) -> bool {
+    compression != Compression::None
+        && matches!(error, Error::Server(error) if error.is_unsupported_compression())
+}
+
+/// Checks if given protocol version can be used to connect. Protocol v5 requires segment framing,
+/// which is not implemented yet, so it's only allowed with the `unstable-protocol-v5` feature.
+pub(crate) fn check_version_supported(version: Version) -> Result<()> {
+    if version >= Version::V5 && !cfg!(feature = "unstable-protocol-v5") {
+        return Err(Error::UnsupportedVersion(version.into()));
+    }
+
+    Ok(())
+}
+
 /// Establishes Cassandra connection with given authentication, last used keyspace and compression.
 pub async fn startup<
     T: CdrsTransport + 'static,
@@ -52,6 +69,8 @@ pub async fn startup<
     compression: Compression,
     version: Version,
 ) -> Result<()> {
+    check_version_supported(version)?;
+
     let startup_frame = Frame::new_req_startup(compression.as_str(), version);
     let start_response = transport.write_frame(&startup_frame).await?;
 
@@ -144,3 +163,45 @@ async fn set_keyspace<T: CdrsTransport>(
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{check_version_supported, should_retry_without_compression};
+    use cassandra_protocol::compression::Compression;
+    use cassandra_protocol::error::Error;
+    use cassandra_protocol::frame::frame_error::{AdditionalErrorInfo, CdrsError};
+    use cassandra_protocol::frame::Version;
+    use cassandra_protocol::types::CString;
+
+    #[test]
+    fn should_check_version_supported() {
+        assert!(check_version_supported(Version::V3).is_ok());
+        assert!(check_version_supported(Version::V4).is_ok());
+
+        #[cfg(not(feature = "unstable-protocol-v5"))]
+        assert!(matches!(
+            check_version_supported(Version::V5),
+            Err(Error::UnsupportedVersion(5))
+        ));
+
+        #[cfg(feature = "unstable-protocol-v5")]
+        assert!(check_version_supported(Version::V5).is_ok());
+    }
+
+    #[test]
+    fn should_retry_on_unsupported_compression() {
+        let error = Error::Server(CdrsError {
+            error_code: 0x000A,
+            message: CString::new("Unknown compression algorithm: lz4".into()),
+            additional_info: AdditionalErrorInfo::Protocol,
+            retry_after: None,
+        });
+
+        assert!(should_retry_without_compression(&error, Compression::Lz4));
+        assert!(!should_retry_without_compression(&error, Compression::None));
+        assert!(!should_retry_without_compression(
+            &Error::General("error".into()),
+            Compression::Lz4
+        ));
+    }
+}
@@ -158,6 +158,7 @@ mod tests {
             "".into(),
             Default::default(),
             "".into(),
+            None,
         )];
 
         let connection_manager = MockConnectionManager::<MockCdrsTransport>::new();
@@ -199,6 +200,7 @@ mod tests {
             "".into(),
             Default::default(),
             "".into(),
+            None,
         )];
 
         let contact_points = [Arc::new(
@@ -248,6 +250,7 @@ mod tests {
             "".into(),
             Default::default(),
             "".into(),
+            None,
         )];
 
         let old_host_id = Uuid::new_v4();
@@ -299,6 +302,7 @@ mod tests {
             "".into(),
             Default::default(),
             "".into(),
+            None,
         )];
 
         let mut old_nodes = NodeMap::default();
@@ -347,6 +351,7 @@ mod tests {
             "".into(),
             Default::default(),
             "".into(),
+            None,
         );
 
         let old_node = Node::with_distance(
@@ -401,6 +406,7 @@ mod tests {
             "".into(),
             Default::default(),
             "".into(),
+            None,
         );
 
         let old_node = Node::with_distance(
@@ -452,6 +458,7 @@ mod tests {
             "".into(),
             Default::default(),
             "".into(),
+            None,
         );
 
         let old_metadata = ClusterMetadata::new(Default::default(), Default::default());
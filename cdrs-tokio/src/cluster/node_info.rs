@@ -13,4 +13,6 @@ pub struct NodeInfo {
     pub datacenter: String,
     pub tokens: Vec<Murmur3Token>,
     pub rack: String,
+    /// Schema version reported by the node, used for checking schema agreement.
+    pub schema_version: Option<Uuid>,
 }
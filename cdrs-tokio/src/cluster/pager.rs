@@ -6,8 +6,8 @@ use cassandra_protocol::consistency::Consistency;
 use cassandra_protocol::error;
 use cassandra_protocol::frame::frame_result::{RowsMetadata, RowsMetadataFlags};
 use cassandra_protocol::query::{PreparedQuery, QueryParams, QueryParamsBuilder, QueryValues};
+use cassandra_protocol::types::paging_state::PagingState;
 use cassandra_protocol::types::rows::Row;
-use cassandra_protocol::types::CBytes;
 
 pub struct SessionPager<
     'a,
@@ -220,7 +220,7 @@ impl<
 
 #[derive(Clone, PartialEq, Debug, Default)]
 pub struct PagerState {
-    cursor: Option<CBytes>,
+    cursor: Option<PagingState>,
     has_more_pages: Option<bool>,
 }
 
@@ -229,14 +229,14 @@ impl PagerState {
         Default::default()
     }
 
-    pub fn new_with_cursor(cursor: CBytes) -> Self {
+    pub fn new_with_cursor(cursor: PagingState) -> Self {
         PagerState {
             cursor: Some(cursor),
             has_more_pages: None,
         }
     }
 
-    pub fn new_with_cursor_and_more_flag(cursor: CBytes, has_more: bool) -> Self {
+    pub fn new_with_cursor_and_more_flag(cursor: PagingState, has_more: bool) -> Self {
         PagerState {
             cursor: Some(cursor),
             has_more_pages: Some(has_more),
@@ -244,12 +244,12 @@ impl PagerState {
     }
 
     #[deprecated(note = "Use new_with_cursor().")]
-    pub fn with_cursor(cursor: CBytes) -> Self {
+    pub fn with_cursor(cursor: PagingState) -> Self {
         Self::new_with_cursor(cursor)
     }
 
     #[deprecated(note = "Use new_with_cursor_and_more_flag().")]
-    pub fn with_cursor_and_more_flag(cursor: CBytes, has_more: bool) -> Self {
+    pub fn with_cursor_and_more_flag(cursor: PagingState, has_more: bool) -> Self {
         Self::new_with_cursor_and_more_flag(cursor, has_more)
     }
 
@@ -257,11 +257,11 @@ impl PagerState {
         self.has_more_pages.unwrap_or(false)
     }
 
-    pub fn cursor(&self) -> Option<CBytes> {
+    pub fn cursor(&self) -> Option<PagingState> {
         self.cursor.clone()
     }
 
-    pub fn into_cursor(self) -> Option<CBytes> {
+    pub fn into_cursor(self) -> Option<PagingState> {
         self.cursor
     }
 }
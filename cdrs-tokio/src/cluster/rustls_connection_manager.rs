@@ -4,8 +4,11 @@ use std::ops::Deref;
 use std::sync::Arc;
 use tokio::sync::mpsc::Sender;
 use tokio::time::sleep;
+use tracing::*;
 
-use crate::cluster::connection_manager::{startup, ConnectionManager};
+use crate::cluster::connection_manager::{
+    should_retry_without_compression, startup, ConnectionManager,
+};
 use crate::cluster::KeyspaceHolder;
 use crate::future::BoxFuture;
 use crate::retry::ReconnectionPolicy;
@@ -22,9 +25,11 @@ pub struct RustlsConnectionManager {
     keyspace_holder: Arc<KeyspaceHolder>,
     reconnection_policy: Arc<dyn ReconnectionPolicy + Send + Sync>,
     compression: Compression,
+    compression_threshold: usize,
     buffer_size: usize,
     tcp_nodelay: bool,
     version: Version,
+    use_beta: bool,
 }
 
 impl ConnectionManager<TransportRustls> for RustlsConnectionManager {
@@ -63,9 +68,11 @@ impl RustlsConnectionManager {
         keyspace_holder: Arc<KeyspaceHolder>,
         reconnection_policy: Arc<dyn ReconnectionPolicy + Send + Sync>,
         compression: Compression,
+        compression_threshold: usize,
         buffer_size: usize,
         tcp_nodelay: bool,
         version: Version,
+        use_beta: bool,
     ) -> Self {
         RustlsConnectionManager {
             dns_name,
@@ -74,9 +81,11 @@ impl RustlsConnectionManager {
             keyspace_holder,
             reconnection_policy,
             compression,
+            compression_threshold,
             buffer_size,
             tcp_nodelay,
             version,
+            use_beta,
         }
     }
 
@@ -85,6 +94,36 @@ impl RustlsConnectionManager {
         event_handler: Option<Sender<Frame>>,
         error_handler: Option<Sender<Error>>,
         addr: SocketAddr,
+    ) -> Result<TransportRustls> {
+        match self
+            .establish_connection_with_compression(
+                event_handler.clone(),
+                error_handler.clone(),
+                addr,
+                self.compression,
+            )
+            .await
+        {
+            Err(error) if should_retry_without_compression(&error, self.compression) => {
+                warn!(%error, %addr, "Compression not supported by server - connecting without compression.");
+                self.establish_connection_with_compression(
+                    event_handler,
+                    error_handler,
+                    addr,
+                    Compression::None,
+                )
+                .await
+            }
+            result => result,
+        }
+    }
+
+    async fn establish_connection_with_compression(
+        &self,
+        event_handler: Option<Sender<Frame>>,
+        error_handler: Option<Sender<Error>>,
+        addr: SocketAddr,
+        compression: Compression,
     ) -> Result<TransportRustls> {
         let transport = TransportRustls::new(
             addr,
@@ -93,9 +132,11 @@ impl RustlsConnectionManager {
             self.keyspace_holder.clone(),
             event_handler,
             error_handler,
-            self.compression,
+            compression,
+            self.compression_threshold,
             self.buffer_size,
             self.tcp_nodelay,
+            self.use_beta,
         )
         .await?;
 
@@ -103,7 +144,7 @@ impl RustlsConnectionManager {
             &transport,
             self.authenticator_provider.deref(),
             self.keyspace_holder.deref(),
-            self.compression,
+            compression,
             self.version,
         )
         .await?;
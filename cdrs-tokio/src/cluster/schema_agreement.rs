@@ -0,0 +1,34 @@
+use uuid::Uuid;
+
+/// Checks if all given schema versions are the same, which means the nodes they were read from
+/// agree on the schema. An empty set is considered to be in agreement.
+///
+/// After a schema-altering statement, schema versions should be read from
+/// `system.local.schema_version` and `system.peers.schema_version` (skipping peers which are
+/// down) and checked for agreement. If they don't agree yet, wait a short while and read them
+/// again, until they agree or a timeout elapses. Receiving a schema change event is a good
+/// moment to start checking, but does not mean the schema has propagated to all nodes.
+pub fn schema_versions_agree(versions: &[Uuid]) -> bool {
+    versions.windows(2).all(|pair| pair[0] == pair[1])
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::schema_versions_agree;
+
+    #[test]
+    fn should_agree_on_same_versions() {
+        let version = Uuid::new_v4();
+        assert!(schema_versions_agree(&[version, version, version]));
+        assert!(schema_versions_agree(&[version]));
+        assert!(schema_versions_agree(&[]));
+    }
+
+    #[test]
+    fn should_not_agree_on_different_versions() {
+        let version = Uuid::new_v4();
+        assert!(!schema_versions_agree(&[version, Uuid::new_v4(), version]));
+    }
+}
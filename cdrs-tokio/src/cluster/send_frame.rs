@@ -8,19 +8,41 @@ use cassandra_protocol::error;
 use cassandra_protocol::frame::Frame;
 use cassandra_protocol::query::query_params::Murmur3Token;
 
+/// Request frame to send.
+pub(crate) enum RequestFrame<'a> {
+    /// Frame which doesn't carry a consistency level, e.g. `PREPARE`.
+    Plain(Frame),
+    /// Frame built for given consistency level by given function. The function is called again
+    /// if the retry policy decides to retry with a different consistency level.
+    WithConsistency(
+        Consistency,
+        &'a mut (dyn FnMut(Consistency) -> Frame + Send),
+    ),
+}
+
 pub(crate) async fn send_frame<
     T: CdrsTransport + 'static,
     CM: ConnectionManager<T> + Send + Sync + 'static,
     LB: LoadBalancingStrategy<T, CM> + Send + Sync + 'static,
 >(
     session: &Session<T, CM, LB>,
-    frame: Frame,
+    frame: RequestFrame<'_>,
     is_idempotent: bool,
     keyspace: Option<&str>,
     token: Option<Murmur3Token>,
     routing_key: Option<&[u8]>,
-    consistency: Option<Consistency>,
 ) -> error::Result<Frame> {
+    let (mut frame, consistency, mut build_frame) = match frame {
+        RequestFrame::Plain(frame) => (frame, None, None),
+        RequestFrame::WithConsistency(consistency, build_frame) => (
+            build_frame(consistency),
+            Some(consistency),
+            Some(build_frame),
+        ),
+    };
+
+    session.interceptors().on_request(&mut frame);
+
     let mut retry_session = session.retry_policy().new_session();
 
     let current_keyspace = session.current_keyspace();
@@ -36,7 +58,10 @@ pub(crate) async fn send_frame<
         loop {
             let transport = node.persistent_connection().await?;
             match transport.write_frame(&frame).await {
-                Ok(frame) => return Ok(frame),
+                Ok(response) => {
+                    session.interceptors().on_response(&response);
+                    return Ok(response);
+                }
                 Err(error) => {
                     let query_info = QueryInfo {
                         error: &error,
@@ -44,6 +69,15 @@ pub(crate) async fn send_frame<
                     };
 
                     match retry_session.decide(query_info) {
+                        RetryDecision::Retry(consistency) => {
+                            // frames without a consistency level are retried unchanged
+                            if let Some(build_frame) = build_frame.as_mut() {
+                                frame = build_frame(consistency);
+                                session.interceptors().on_request(&mut frame);
+                            }
+
+                            continue;
+                        }
                         RetryDecision::RetrySameNode => continue,
                         RetryDecision::RetryNextNode => continue 'next_node,
                         RetryDecision::DontRetry => return Err(error),
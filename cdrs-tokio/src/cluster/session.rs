@@ -11,7 +11,7 @@ use crate::cluster::connection_manager::ConnectionManager;
 use crate::cluster::control_connection::ControlConnection;
 #[cfg(feature = "rust-tls")]
 use crate::cluster::rustls_connection_manager::RustlsConnectionManager;
-use crate::cluster::send_frame::send_frame;
+use crate::cluster::send_frame::{send_frame, RequestFrame};
 use crate::cluster::tcp_connection_manager::TcpConnectionManager;
 use crate::cluster::topology::{Node, NodeDistance};
 #[cfg(feature = "rust-tls")]
@@ -33,14 +33,16 @@ use crate::transport::{CdrsTransport, TransportTcp};
 use cassandra_protocol::compression::Compression;
 use cassandra_protocol::error;
 use cassandra_protocol::events::ServerEvent;
+use cassandra_protocol::frame::frame_query::BodyReqQuery;
 use cassandra_protocol::frame::frame_result::{BodyResResultPrepared, TableSpec};
+use cassandra_protocol::frame::interceptor::{Interceptor, InterceptorChain};
 use cassandra_protocol::frame::{Frame, Serialize, Version};
 use cassandra_protocol::query::utils::prepare_flags;
 use cassandra_protocol::query::{
-    PreparedQuery, Query, QueryBatch, QueryParams, QueryParamsBuilder, QueryValues,
+    PreparedQuery, QueryBatch, QueryParams, QueryParamsBuilder, QueryValues,
 };
 use cassandra_protocol::types::value::Value;
-use cassandra_protocol::types::{CIntShort, SHORT_LEN};
+use cassandra_protocol::types::{CIntShort, CStringLong, SHORT_LEN};
 
 pub const DEFAULT_TRANSPORT_BUFFER_SIZE: usize = 1024;
 const DEFAULT_EVENT_CHANNEL_CAPACITY: usize = 128;
@@ -116,6 +118,7 @@ pub struct Session<
     load_balancing: Arc<InitializingWrapperLoadBalancingStrategy<T, CM, LB>>,
     keyspace_holder: Arc<KeyspaceHolder>,
     retry_policy: Box<dyn RetryPolicy + Send + Sync>,
+    interceptors: InterceptorChain,
     control_connection_handle: JoinHandle<()>,
     event_sender: Sender<ServerEvent>,
     cluster_metadata_manager: Arc<ClusterMetadataManager<T, CM>>,
@@ -150,19 +153,34 @@ impl<
     pub async fn exec_with_params_tw(
         &self,
         prepared: &PreparedQuery,
-        query_parameters: QueryParams,
+        mut query_parameters: QueryParams,
         with_tracing: bool,
         with_warnings: bool,
     ) -> error::Result<Frame> {
+        if let Some(values) = &query_parameters.values {
+            values.validate_for_version(self.version)?;
+
+            if let Some(max_values_bytes) = query_parameters.max_values_bytes {
+                values.validate_size(max_values_bytes)?;
+            }
+        }
+
+        query_parameters.validate_consistency()?;
+
+        if self.version >= Version::V5 {
+            prepared.validate_keyspace(query_parameters.keyspace.as_deref())?;
+        }
+
         let consistency = query_parameters.consistency;
+        let is_idempotent = query_parameters.is_idempotent;
+        let token = query_parameters.token;
         let flags = prepare_flags(with_tracing, with_warnings);
-        let options_frame =
-            Frame::new_req_execute(&prepared.id, &query_parameters, flags, self.version);
+        let version = self.version;
 
         let keyspace = prepared
             .keyspace
-            .as_deref()
-            .or_else(|| query_parameters.keyspace.as_deref());
+            .clone()
+            .or_else(|| query_parameters.keyspace.clone());
 
         let routing_key = query_parameters
             .values
@@ -176,17 +194,31 @@ impl<
                             .map(|values| serialize_routing_key(values))
                     })
                 }
+                QueryValues::RawValues(..) => query_parameters
+                    .routing_key
+                    .as_ref()
+                    .map(|values| serialize_routing_key(values)),
                 QueryValues::NamedValues(_) => None,
             });
 
+        let mut build_frame = |consistency| {
+            query_parameters.consistency = consistency;
+            Frame::new_req_execute_with_result_metadata_id(
+                &prepared.id,
+                prepared.result_metadata_id.as_ref(),
+                &query_parameters,
+                flags,
+                version,
+            )
+        };
+
         let mut result = send_frame(
             self,
-            options_frame,
-            query_parameters.is_idempotent,
-            keyspace,
-            query_parameters.token,
+            RequestFrame::WithConsistency(consistency, &mut build_frame),
+            is_idempotent,
+            keyspace.as_deref(),
+            token,
             routing_key.as_deref(),
-            Some(consistency),
         )
         .await;
 
@@ -204,17 +236,24 @@ impl<
                         return Err("Re-preparing an unprepared statement resulted in a different id - probably schema changed on the server.".into());
                     }
 
-                    let flags = prepare_flags(with_tracing, with_warnings);
-                    let options_frame =
-                        Frame::new_req_execute(&new.id, &query_parameters, flags, self.version);
+                    let mut build_frame = |consistency| {
+                        query_parameters.consistency = consistency;
+                        Frame::new_req_execute_with_result_metadata_id(
+                            &new.id,
+                            new.result_metadata_id.as_ref(),
+                            &query_parameters,
+                            flags,
+                            version,
+                        )
+                    };
+
                     result = send_frame(
                         self,
-                        options_frame,
-                        query_parameters.is_idempotent,
-                        keyspace,
-                        query_parameters.token,
+                        RequestFrame::WithConsistency(consistency, &mut build_frame),
+                        is_idempotent,
+                        keyspace.as_deref(),
+                        token,
                         routing_key.as_deref(),
-                        Some(consistency),
                     )
                     .await;
                 }
@@ -283,18 +322,40 @@ impl<
         query: Q,
         with_tracing: bool,
         with_warnings: bool,
+    ) -> error::Result<BodyResResultPrepared> {
+        self.prepare_raw_with_keyspace_tw(query, None, with_tracing, with_warnings)
+            .await
+    }
+
+    /// Prepares a query for execution in given keyspace, which allows preparing queries with
+    /// unqualified table names without issuing `USE` first. Requires protocol v5 - the keyspace
+    /// is not sent with earlier versions. Returns the raw prepared query result.
+    pub async fn prepare_raw_with_keyspace_tw<Q: ToString>(
+        &self,
+        query: Q,
+        keyspace: Option<String>,
+        with_tracing: bool,
+        with_warnings: bool,
     ) -> error::Result<BodyResResultPrepared> {
         let flags = prepare_flags(with_tracing, with_warnings);
 
-        let query_frame = Frame::new_req_prepare(query.to_string(), flags, self.version);
+        let query_frame =
+            Frame::new_req_prepare_with_keyspace(query.to_string(), keyspace, flags, self.version);
 
-        send_frame(self, query_frame, false, None, None, None, None)
-            .await
-            .and_then(|response| response.response_body())
-            .and_then(|body| {
-                body.into_prepared()
-                    .ok_or_else(|| "CDRS BUG: cannot convert frame into prepared".into())
-            })
+        send_frame(
+            self,
+            RequestFrame::Plain(query_frame),
+            false,
+            None,
+            None,
+            None,
+        )
+        .await
+        .and_then(|response| response.response_body())
+        .and_then(|body| {
+            body.into_prepared()
+                .ok_or_else(|| "CDRS BUG: cannot convert frame into prepared".into())
+        })
     }
 
     /// Prepares query without additional tracing information and warnings.
@@ -312,21 +373,50 @@ impl<
         query: Q,
         with_tracing: bool,
         with_warnings: bool,
+    ) -> error::Result<PreparedQuery> {
+        self.prepare_with_keyspace_tw(query, None, with_tracing, with_warnings)
+            .await
+    }
+
+    /// Prepares a query for execution in given keyspace. Requires protocol v5 - the keyspace
+    /// is not sent with earlier versions. Returns the prepared query, associated with the
+    /// keyspace from the result metadata or, if not present, the given one.
+    pub async fn prepare_with_keyspace_tw<Q: ToString>(
+        &self,
+        query: Q,
+        keyspace: Option<String>,
+        with_tracing: bool,
+        with_warnings: bool,
     ) -> error::Result<PreparedQuery> {
         let s = query.to_string();
-        self.prepare_raw_tw(query, with_tracing, with_warnings)
+        let requested_keyspace = keyspace.clone().filter(|_| self.version >= Version::V5);
+
+        self.prepare_raw_with_keyspace_tw(query, keyspace, with_tracing, with_warnings)
             .await
             .map(|result| PreparedQuery {
                 id: result.id,
+                result_metadata_id: result.result_metadata_id,
                 query: s,
                 keyspace: result
                     .metadata
                     .global_table_spec
-                    .map(|TableSpec { ks_name, .. }| ks_name.as_plain()),
+                    .map(|TableSpec { ks_name, .. }| ks_name.as_plain())
+                    .or(requested_keyspace),
                 pk_indexes: result.metadata.pk_indexes,
             })
     }
 
+    /// Prepares a query in given keyspace without additional tracing information and warnings.
+    /// Returns the prepared query.
+    pub async fn prepare_with_keyspace<Q: ToString>(
+        &self,
+        query: Q,
+        keyspace: String,
+    ) -> error::Result<PreparedQuery> {
+        self.prepare_with_keyspace_tw(query, Some(keyspace), false, false)
+            .await
+    }
+
     /// It prepares query without additional tracing information and warnings.
     /// Returns the prepared query.
     pub async fn prepare<Q: ToString>(&self, query: Q) -> error::Result<PreparedQuery> {
@@ -344,17 +434,23 @@ impl<
         let is_idempotent = batch.is_idempotent;
         let keyspace = batch.keyspace.take();
         let consistency = batch.consistency;
+        let version = self.version;
 
-        let query_frame = Frame::new_req_batch(batch, flags, self.version);
+        let mut build_frame = |consistency| {
+            batch.consistency = consistency;
+
+            let mut frame = Frame::new_request(&batch, flags, version);
+            frame.compression_disabled = batch.compression_disabled;
+            frame
+        };
 
         send_frame(
             self,
-            query_frame,
+            RequestFrame::WithConsistency(consistency, &mut build_frame),
             is_idempotent,
             keyspace.as_deref(),
             None,
             None,
-            Some(consistency),
         )
         .await
     }
@@ -373,6 +469,17 @@ impl<
         with_warnings: bool,
         version: Version,
     ) -> error::Result<Frame> {
+        if let Some(values) = &query_params.values {
+            values.validate_for_version(version)?;
+            values.validate_for_query()?;
+
+            if let Some(max_values_bytes) = query_params.max_values_bytes {
+                values.validate_size(max_values_bytes)?;
+            }
+        }
+
+        query_params.validate_consistency()?;
+
         let is_idempotent = query_params.is_idempotent;
         let consistency = query_params.consistency;
         let keyspace = query_params.keyspace.take();
@@ -382,22 +489,27 @@ impl<
             .as_ref()
             .map(|values| serialize_routing_key(values));
 
-        let query = Query {
-            query: query.to_string(),
-            params: query_params,
+        let mut body = BodyReqQuery {
+            query: CStringLong::new(query.to_string()),
+            query_params,
         };
 
         let flags = prepare_flags(with_tracing, with_warnings);
-        let query_frame = Frame::new_query(query, flags, version);
+        let mut build_frame = |consistency| {
+            body.query_params.consistency = consistency;
+
+            let mut frame = Frame::new_request(&body, flags, version);
+            frame.compression_disabled = body.query_params.compression_disabled;
+            frame
+        };
 
         send_frame(
             self,
-            query_frame,
+            RequestFrame::WithConsistency(consistency, &mut build_frame),
             is_idempotent,
             keyspace.as_deref(),
             token,
             routing_key.as_deref(),
-            Some(consistency),
         )
         .await
     }
@@ -497,11 +609,18 @@ impl<
         self.retry_policy.as_ref()
     }
 
+    /// Returns interceptors applied to frames sent by this session.
+    #[inline]
+    pub fn interceptors(&self) -> &InterceptorChain {
+        &self.interceptors
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn new(
         load_balancing: LB,
         keyspace_holder: Arc<KeyspaceHolder>,
         retry_policy: Box<dyn RetryPolicy + Send + Sync>,
+        interceptors: InterceptorChain,
         reconnection_policy: Arc<dyn ReconnectionPolicy + Send + Sync>,
         node_distance_evaluator: Box<dyn NodeDistanceEvaluator + Send + Sync>,
         contact_points: Vec<SocketAddr>,
@@ -564,6 +683,7 @@ impl<
             load_balancing,
             keyspace_holder,
             retry_policy,
+            interceptors,
             control_connection_handle,
             event_sender,
             cluster_metadata_manager,
@@ -614,6 +734,7 @@ where
         load_balancing,
         Default::default(),
         retry_policy.0,
+        Default::default(),
         reconnection_policy.0,
         node_distance_evaluator.0,
         initial_nodes.into_iter().collect(),
@@ -629,10 +750,12 @@ struct SessionConfig<
     LB: LoadBalancingStrategy<T, CM> + Send + Sync,
 > {
     compression: Compression,
+    compression_threshold: usize,
     transport_buffer_size: usize,
     tcp_nodelay: bool,
     load_balancing: LB,
     retry_policy: Box<dyn RetryPolicy + Send + Sync>,
+    interceptors: InterceptorChain,
     reconnection_policy: Arc<dyn ReconnectionPolicy + Send + Sync>,
     node_distance_evaluator: Box<dyn NodeDistanceEvaluator + Send + Sync>,
     event_channel_capacity: usize,
@@ -649,6 +772,7 @@ impl<
     #[allow(clippy::too_many_arguments)]
     fn new(
         compression: Compression,
+        compression_threshold: usize,
         transport_buffer_size: usize,
         tcp_nodelay: bool,
         load_balancing: LB,
@@ -659,10 +783,12 @@ impl<
     ) -> Self {
         SessionConfig {
             compression,
+            compression_threshold,
             transport_buffer_size,
             tcp_nodelay,
             load_balancing,
             retry_policy,
+            interceptors: Default::default(),
             reconnection_policy,
             node_distance_evaluator,
             event_channel_capacity,
@@ -684,9 +810,19 @@ pub trait SessionBuilder<
     /// Sets new compression.
     fn with_compression(self, compression: Compression) -> Self;
 
+    /// Sets minimum frame body length for compression - shorter frames are sent uncompressed,
+    /// since compressing them rarely pays off. Defaults to 0, i.e. all frames are compressed
+    /// (if compression is enabled). Frames with compression disabled explicitly are always sent
+    /// uncompressed.
+    fn with_compression_threshold(self, compression_threshold: usize) -> Self;
+
     /// Set new retry policy.
     fn with_retry_policy(self, retry_policy: Box<dyn RetryPolicy + Send + Sync>) -> Self;
 
+    /// Appends an interceptor to the chain applied to all frames sent by the session and
+    /// responses received for them.
+    fn with_interceptor(self, interceptor: Arc<dyn Interceptor>) -> Self;
+
     /// Set new reconnection policy.
     fn with_reconnection_policy(
         self,
@@ -732,6 +868,7 @@ impl<LB: LoadBalancingStrategy<TransportTcp, TcpConnectionManager> + Send + Sync
         TcpSessionBuilder {
             config: SessionConfig::new(
                 Compression::None,
+                0,
                 DEFAULT_TRANSPORT_BUFFER_SIZE,
                 true,
                 load_balancing,
@@ -753,11 +890,21 @@ impl<LB: LoadBalancingStrategy<TransportTcp, TcpConnectionManager> + Send + Sync
         self
     }
 
+    fn with_compression_threshold(mut self, compression_threshold: usize) -> Self {
+        self.config.compression_threshold = compression_threshold;
+        self
+    }
+
     fn with_retry_policy(mut self, retry_policy: Box<dyn RetryPolicy + Send + Sync>) -> Self {
         self.config.retry_policy = retry_policy;
         self
     }
 
+    fn with_interceptor(mut self, interceptor: Arc<dyn Interceptor>) -> Self {
+        self.config.interceptors.add(interceptor);
+        self
+    }
+
     fn with_reconnection_policy(
         mut self,
         reconnection_policy: Arc<dyn ReconnectionPolicy + Send + Sync>,
@@ -796,15 +943,18 @@ impl<LB: LoadBalancingStrategy<TransportTcp, TcpConnectionManager> + Send + Sync
             keyspace_holder.clone(),
             self.config.reconnection_policy.clone(),
             self.config.compression,
+            self.config.compression_threshold,
             self.config.transport_buffer_size,
             self.config.tcp_nodelay,
             self.node_config.version,
+            self.node_config.use_beta,
         ));
 
         Session::new(
             self.config.load_balancing,
             keyspace_holder,
             self.config.retry_policy,
+            self.config.interceptors,
             self.config.reconnection_policy,
             self.config.node_distance_evaluator,
             self.node_config.contact_points,
@@ -834,6 +984,7 @@ impl<LB: LoadBalancingStrategy<TransportRustls, RustlsConnectionManager> + Send
         RustlsSessionBuilder {
             config: SessionConfig::new(
                 Compression::None,
+                0,
                 DEFAULT_TRANSPORT_BUFFER_SIZE,
                 true,
                 load_balancing,
@@ -857,11 +1008,21 @@ impl<
         self
     }
 
+    fn with_compression_threshold(mut self, compression_threshold: usize) -> Self {
+        self.config.compression_threshold = compression_threshold;
+        self
+    }
+
     fn with_retry_policy(mut self, retry_policy: Box<dyn RetryPolicy + Send + Sync>) -> Self {
         self.config.retry_policy = retry_policy;
         self
     }
 
+    fn with_interceptor(mut self, interceptor: Arc<dyn Interceptor>) -> Self {
+        self.config.interceptors.add(interceptor);
+        self
+    }
+
     fn with_reconnection_policy(
         mut self,
         reconnection_policy: Arc<dyn ReconnectionPolicy + Send + Sync>,
@@ -902,15 +1063,18 @@ impl<
             keyspace_holder.clone(),
             self.config.reconnection_policy.clone(),
             self.config.compression,
+            self.config.compression_threshold,
             self.config.transport_buffer_size,
             self.config.tcp_nodelay,
             self.node_config.version,
+            self.node_config.use_beta,
         ));
 
         Session::new(
             self.config.load_balancing,
             keyspace_holder,
             self.config.retry_policy,
+            self.config.interceptors,
             self.config.reconnection_policy,
             self.config.node_distance_evaluator,
             self.node_config.contact_points,
@@ -920,3 +1084,172 @@ impl<
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use std::sync::{Arc, Mutex};
+
+    use cassandra_protocol::consistency::Consistency;
+    use cassandra_protocol::error::Error;
+    use cassandra_protocol::frame::frame_error::{
+        AdditionalErrorInfo, CdrsError, UnavailableError,
+    };
+    use cassandra_protocol::frame::frame_query::BodyReqQuery;
+    use cassandra_protocol::frame::{FromCursor, Opcode, Version};
+    use cassandra_protocol::query::{QueryParamsBuilder, QueryValues};
+    use cassandra_protocol::types::CString;
+
+    use crate::cluster::connection_manager::MockConnectionManager;
+    use crate::cluster::session::Session;
+    use crate::load_balancing::node_distance_evaluator::AllLocalNodeDistanceEvaluator;
+    use crate::load_balancing::RoundRobinLoadBalancingStrategy;
+    use crate::retry::{
+        DefaultRetryPolicy, NeverReconnectionPolicy, RetryDecision, RetryPolicy, RetrySession,
+    };
+    use crate::transport::MockCdrsTransport;
+
+    struct DowngradingRetryPolicy;
+
+    impl RetryPolicy for DowngradingRetryPolicy {
+        fn new_session(&self) -> Box<dyn RetrySession + Send + Sync> {
+            Box::new(DowngradingRetrySession)
+        }
+    }
+
+    struct DowngradingRetrySession;
+
+    impl RetrySession for DowngradingRetrySession {
+        fn on_unavailable(
+            &mut self,
+            error: &UnavailableError,
+            _is_idempotent: bool,
+        ) -> RetryDecision {
+            if error.cl == Consistency::One {
+                RetryDecision::DontRetry
+            } else {
+                RetryDecision::Retry(Consistency::One)
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn should_retry_with_consistency_from_retry_decision() {
+        let address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 9042);
+        let sent_consistencies = Arc::new(Mutex::new(vec![]));
+
+        let mut connection_manager = MockConnectionManager::<MockCdrsTransport>::new();
+        let transport_consistencies = sent_consistencies.clone();
+        connection_manager
+            .expect_connection()
+            .returning(move |event_handler, _, _| {
+                // keep the control connection down - only the query is of interest
+                if event_handler.is_some() {
+                    return Box::pin(async { Err(Error::General("no control".into())) });
+                }
+
+                let consistencies = transport_consistencies.clone();
+                let mut transport = MockCdrsTransport::new();
+                transport.expect_is_broken().return_const(false);
+                transport.expect_address().return_const(address);
+                transport.expect_write_frame().returning(move |frame| {
+                    assert_eq!(frame.opcode, Opcode::Query);
+
+                    let body = BodyReqQuery::from_cursor(&mut Cursor::new(&frame.body)).unwrap();
+                    let consistency = body.query_params.consistency;
+                    consistencies.lock().unwrap().push(consistency);
+
+                    let result = if consistency == Consistency::One {
+                        Ok(frame.clone())
+                    } else {
+                        Err(Error::Server(CdrsError {
+                            error_code: 0x1000,
+                            message: CString::new("unavailable".into()),
+                            additional_info: AdditionalErrorInfo::Unavailable(UnavailableError {
+                                cl: consistency,
+                                required: 2,
+                                alive: 1,
+                            }),
+                            retry_after: None,
+                        }))
+                    };
+
+                    Box::pin(async move { result })
+                });
+
+                Box::pin(async move { Ok(transport) })
+            });
+
+        let session = Session::new(
+            RoundRobinLoadBalancingStrategy::new(),
+            Default::default(),
+            Box::new(DowngradingRetryPolicy),
+            Default::default(),
+            Arc::new(NeverReconnectionPolicy),
+            Box::new(AllLocalNodeDistanceEvaluator),
+            vec![address],
+            Arc::new(connection_manager),
+            1,
+            Version::V4,
+        );
+
+        let params = QueryParamsBuilder::new()
+            .consistency(Consistency::Quorum)
+            .finalize();
+
+        session
+            .query_with_params("SELECT * FROM t", params)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *sent_consistencies.lock().unwrap(),
+            vec![Consistency::Quorum, Consistency::One]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_not_set_raw_values_in_query() {
+        let address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 9042);
+
+        let mut connection_manager = MockConnectionManager::<MockCdrsTransport>::new();
+        connection_manager
+            .expect_connection()
+            .returning(move |event_handler, _, _| {
+                if event_handler.is_some() {
+                    return Box::pin(async { Err(Error::General("no control".into())) });
+                }
+
+                let mut transport = MockCdrsTransport::new();
+                transport.expect_is_broken().return_const(false);
+                transport.expect_address().return_const(address);
+                transport.expect_write_frame().never();
+
+                Box::pin(async move { Ok(transport) })
+            });
+
+        let session = Session::new(
+            RoundRobinLoadBalancingStrategy::new(),
+            Default::default(),
+            Box::new(DefaultRetryPolicy::default()),
+            Default::default(),
+            Arc::new(NeverReconnectionPolicy),
+            Box::new(AllLocalNodeDistanceEvaluator),
+            vec![address],
+            Arc::new(connection_manager),
+            1,
+            Version::V4,
+        );
+
+        // a single unset value, i.e. a length of -2
+        let params = QueryParamsBuilder::new()
+            .values(QueryValues::RawValues(vec![255, 255, 255, 254], 1))
+            .finalize();
+
+        assert!(session
+            .query_with_params("INSERT INTO t (a) VALUES (?)", params)
+            .await
+            .is_err());
+    }
+}
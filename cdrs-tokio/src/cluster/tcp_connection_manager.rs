@@ -1,12 +1,14 @@
-use derive_more::Constructor;
 use futures::FutureExt;
 use std::net::SocketAddr;
 use std::ops::Deref;
 use std::sync::Arc;
 use tokio::sync::mpsc::Sender;
 use tokio::time::sleep;
+use tracing::*;
 
-use crate::cluster::connection_manager::{startup, ConnectionManager};
+use crate::cluster::connection_manager::{
+    should_retry_without_compression, startup, ConnectionManager,
+};
 use crate::cluster::KeyspaceHolder;
 use crate::future::BoxFuture;
 use crate::retry::ReconnectionPolicy;
@@ -16,15 +18,16 @@ use cassandra_protocol::compression::Compression;
 use cassandra_protocol::error::{Error, Result};
 use cassandra_protocol::frame::{Frame, Version};
 
-#[derive(Constructor)]
 pub struct TcpConnectionManager {
     authenticator_provider: Arc<dyn SaslAuthenticatorProvider + Send + Sync>,
     keyspace_holder: Arc<KeyspaceHolder>,
     reconnection_policy: Arc<dyn ReconnectionPolicy + Send + Sync>,
     compression: Compression,
+    compression_threshold: usize,
     buffer_size: usize,
     tcp_nodelay: bool,
     version: Version,
+    use_beta: bool,
 }
 
 impl ConnectionManager<TransportTcp> for TcpConnectionManager {
@@ -55,20 +58,77 @@ impl ConnectionManager<TransportTcp> for TcpConnectionManager {
 }
 
 impl TcpConnectionManager {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        authenticator_provider: Arc<dyn SaslAuthenticatorProvider + Send + Sync>,
+        keyspace_holder: Arc<KeyspaceHolder>,
+        reconnection_policy: Arc<dyn ReconnectionPolicy + Send + Sync>,
+        compression: Compression,
+        compression_threshold: usize,
+        buffer_size: usize,
+        tcp_nodelay: bool,
+        version: Version,
+        use_beta: bool,
+    ) -> Self {
+        TcpConnectionManager {
+            authenticator_provider,
+            keyspace_holder,
+            reconnection_policy,
+            compression,
+            compression_threshold,
+            buffer_size,
+            tcp_nodelay,
+            version,
+            use_beta,
+        }
+    }
+
     async fn establish_connection(
         &self,
         event_handler: Option<Sender<Frame>>,
         error_handler: Option<Sender<Error>>,
         addr: SocketAddr,
+    ) -> Result<TransportTcp> {
+        match self
+            .establish_connection_with_compression(
+                event_handler.clone(),
+                error_handler.clone(),
+                addr,
+                self.compression,
+            )
+            .await
+        {
+            Err(error) if should_retry_without_compression(&error, self.compression) => {
+                warn!(%error, %addr, "Compression not supported by server - connecting without compression.");
+                self.establish_connection_with_compression(
+                    event_handler,
+                    error_handler,
+                    addr,
+                    Compression::None,
+                )
+                .await
+            }
+            result => result,
+        }
+    }
+
+    async fn establish_connection_with_compression(
+        &self,
+        event_handler: Option<Sender<Frame>>,
+        error_handler: Option<Sender<Error>>,
+        addr: SocketAddr,
+        compression: Compression,
     ) -> Result<TransportTcp> {
         let transport = TransportTcp::new(
             addr,
             self.keyspace_holder.clone(),
             event_handler,
             error_handler,
-            self.compression,
+            compression,
+            self.compression_threshold,
             self.buffer_size,
             self.tcp_nodelay,
+            self.use_beta,
         )
         .await?;
 
@@ -76,7 +136,7 @@ impl TcpConnectionManager {
             &transport,
             self.authenticator_provider.deref(),
             self.keyspace_holder.deref(),
-            self.compression,
+            compression,
             self.version,
         )
         .await?;
@@ -1,9 +1,10 @@
+use fxhash::{FxHashMap, FxHashSet};
 use std::collections::BTreeMap;
 use std::fmt::{Debug, Formatter};
 use std::net::SocketAddr;
 use std::sync::Arc;
 
-use crate::cluster::topology::{Node, NodeMap};
+use crate::cluster::topology::{DatacenterMetadata, Node, NodeMap};
 use crate::cluster::ConnectionManager;
 use crate::transport::CdrsTransport;
 use cassandra_protocol::query::query_params::Murmur3Token;
@@ -76,6 +77,92 @@ impl<T: CdrsTransport, CM: ConnectionManager<T>> TokenMap<T, CM> {
             .map(|(_, node)| node.clone())
     }
 
+    /// Returns up to `replication_factor` distinct nodes replicating given token - the primary
+    /// replica owning the first token not smaller than given one, followed by its successors on
+    /// the ring. Wraps around past the largest token and skips nodes owning multiple tokens
+    /// which have already been chosen.
+    pub fn replicas(
+        &self,
+        token: Murmur3Token,
+        replication_factor: usize,
+    ) -> Vec<Arc<Node<T, CM>>> {
+        let mut replicas: Vec<Arc<Node<T, CM>>> = Vec::with_capacity(replication_factor);
+
+        for node in self.nodes_for_token(token) {
+            if replicas.len() == replication_factor {
+                break;
+            }
+
+            if !replicas.iter().any(|replica| Arc::ptr_eq(replica, &node)) {
+                replicas.push(node);
+            }
+        }
+
+        replicas
+    }
+
+    /// Returns distinct nodes replicating given token according to `NetworkTopologyStrategy` -
+    /// walking the ring like `replicas`, but choosing up to the replication factor of nodes from
+    /// each datacenter, preferring nodes from racks not chosen yet. Nodes from the same rack are
+    /// only chosen if a datacenter has fewer racks than its replication factor.
+    pub fn network_topology_replicas(
+        &self,
+        token: Murmur3Token,
+        datacenter_replication_factor: &FxHashMap<String, usize>,
+        datacenters: &FxHashMap<String, DatacenterMetadata>,
+    ) -> Vec<Arc<Node<T, CM>>> {
+        let desired_replica_count = datacenter_replication_factor.values().sum();
+        let mut remaining_replicas = datacenter_replication_factor.clone();
+        let mut same_rack_replicas: FxHashMap<&str, usize> = datacenter_replication_factor
+            .iter()
+            .map(|(dc, replication_factor)| {
+                let rack_count = datacenters.get(dc).map(|dc| dc.rack_count).unwrap_or(0);
+                (dc.as_str(), replication_factor.saturating_sub(rack_count))
+            })
+            .collect();
+
+        let mut result: Vec<Arc<Node<T, CM>>> = Vec::with_capacity(desired_replica_count);
+        let mut used_dc_racks: FxHashSet<(&str, &str)> = Default::default();
+
+        let ring = self
+            .token_ring
+            .range(token..)
+            .chain(self.token_ring.iter())
+            .take(self.token_ring.len());
+
+        for (_, replica) in ring {
+            if result.len() == desired_replica_count {
+                break;
+            }
+
+            if result.iter().any(|node| Arc::ptr_eq(node, replica)) {
+                continue;
+            }
+
+            let datacenter = replica.datacenter();
+            let dc_remaining_replicas = match remaining_replicas.get_mut(datacenter) {
+                // found enough nodes in this datacenter
+                Some(dc_remaining_replicas) if *dc_remaining_replicas > 0 => dc_remaining_replicas,
+                _ => continue,
+            };
+
+            if used_dc_racks.insert((datacenter, replica.rack())) {
+                *dc_remaining_replicas -= 1;
+                result.push(replica.clone());
+            } else if let Some(same_rack_replicas) = same_rack_replicas.get_mut(datacenter) {
+                // we need to put nodes from the same rack multiple times to meet the replication
+                // factor
+                if *same_rack_replicas > 0 {
+                    *same_rack_replicas -= 1;
+                    *dc_remaining_replicas -= 1;
+                    result.push(replica.clone());
+                }
+            }
+        }
+
+        result
+    }
+
     /// Creates a new map with a new node inserted.
     pub fn clone_with_node(&self, node: Arc<Node<T, CM>>) -> Self {
         let mut map = self.clone();
@@ -106,6 +193,7 @@ impl<T: CdrsTransport, CM: ConnectionManager<T>> TokenMap<T, CM> {
 
 #[cfg(test)]
 mod tests {
+    use fxhash::FxHashMap;
     use itertools::Itertools;
     use lazy_static::lazy_static;
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
@@ -113,7 +201,7 @@ mod tests {
     use uuid::Uuid;
 
     use crate::cluster::connection_manager::MockConnectionManager;
-    use crate::cluster::topology::{Node, NodeMap};
+    use crate::cluster::topology::{DatacenterMetadata, Node, NodeMap};
     use crate::cluster::TokenMap;
     use crate::transport::MockCdrsTransport;
     use cassandra_protocol::query::query_params::Murmur3Token;
@@ -204,6 +292,38 @@ mod tests {
         verify_tokens(&[*HOST_ID_3, *HOST_ID_2], Murmur3Token::new(3));
     }
 
+    fn verify_replicas(host_ids: &[Uuid], token: Murmur3Token, replication_factor: usize) {
+        let token_map = TokenMap::new(&prepare_nodes());
+        let replicas = token_map
+            .replicas(token, replication_factor)
+            .iter()
+            .map(|node| node.host_id().unwrap())
+            .collect_vec();
+
+        assert_eq!(replicas, host_ids);
+    }
+
+    #[test]
+    fn should_return_distinct_replicas() {
+        verify_replicas(&[*HOST_ID_1, *HOST_ID_3], Murmur3Token::new(-2), 2);
+        verify_replicas(
+            &[*HOST_ID_3, *HOST_ID_2, *HOST_ID_1],
+            Murmur3Token::new(3),
+            5,
+        );
+    }
+
+    #[test]
+    fn should_wrap_replicas_around_top_token() {
+        verify_replicas(&[*HOST_ID_2, *HOST_ID_1], Murmur3Token::new(20), 2);
+        verify_replicas(
+            &[*HOST_ID_2, *HOST_ID_1, *HOST_ID_3],
+            Murmur3Token::new(15),
+            3,
+        );
+        verify_replicas(&[*HOST_ID_1, *HOST_ID_3], Murmur3Token::new(25), 2);
+    }
+
     #[test]
     fn should_return_replicas_in_a_ring() {
         verify_tokens(
@@ -211,4 +331,72 @@ mod tests {
             Murmur3Token::new(20),
         );
     }
+
+    fn prepare_datacenter_nodes(
+    ) -> NodeMap<MockCdrsTransport, MockConnectionManager<MockCdrsTransport>> {
+        let connection_manager = Arc::new(MockConnectionManager::<MockCdrsTransport>::new());
+
+        // (port, token, rack, datacenter)
+        [
+            (1, 0, "r1", "dc1"),
+            (2, 10, "r1", "dc1"),
+            (3, 20, "r2", "dc1"),
+            (4, 5, "r1", "dc2"),
+            (5, 15, "r1", "dc2"),
+            (6, 25, "r2", "dc2"),
+        ]
+        .iter()
+        .map(|(port, token, rack, datacenter)| {
+            let host_id = Uuid::new_v4();
+            (
+                host_id,
+                Arc::new(Node::new(
+                    connection_manager.clone(),
+                    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), *port),
+                    None,
+                    Some(host_id),
+                    None,
+                    vec![Murmur3Token::new(*token)],
+                    (*rack).into(),
+                    (*datacenter).into(),
+                )),
+            )
+        })
+        .collect()
+    }
+
+    fn verify_network_topology_replicas(ports: &[u16], token: Murmur3Token, dc1_rack_count: usize) {
+        let token_map = TokenMap::new(&prepare_datacenter_nodes());
+
+        let mut datacenter_replication_factor = FxHashMap::default();
+        datacenter_replication_factor.insert("dc1".into(), 2);
+        datacenter_replication_factor.insert("dc2".into(), 2);
+
+        let mut datacenters = FxHashMap::default();
+        datacenters.insert("dc1".into(), DatacenterMetadata::new(dc1_rack_count));
+        datacenters.insert("dc2".into(), DatacenterMetadata::new(2));
+
+        let replicas = token_map
+            .network_topology_replicas(token, &datacenter_replication_factor, &datacenters)
+            .iter()
+            .map(|node| node.broadcast_rpc_address().port())
+            .collect_vec();
+
+        assert_eq!(replicas, ports);
+    }
+
+    #[test]
+    fn should_return_network_topology_replicas_in_distinct_racks() {
+        verify_network_topology_replicas(&[1, 4, 3, 6], Murmur3Token::new(0), 2);
+    }
+
+    #[test]
+    fn should_wrap_network_topology_replicas_around_top_token() {
+        verify_network_topology_replicas(&[6, 1, 4, 3], Murmur3Token::new(21), 2);
+    }
+
+    #[test]
+    fn should_reuse_racks_when_not_enough_racks() {
+        verify_network_topology_replicas(&[1, 4, 2, 6], Murmur3Token::new(0), 1);
+    }
 }
@@ -9,6 +9,7 @@ mod keyspace_metadata;
 mod node;
 mod node_distance;
 mod node_state;
+mod partitioner;
 mod replication_strategy;
 
 pub use self::datacenter_metadata::DatacenterMetadata;
@@ -16,6 +17,7 @@ pub use self::keyspace_metadata::KeyspaceMetadata;
 pub use self::node::Node;
 pub use self::node_distance::NodeDistance;
 pub use self::node_state::NodeState;
+pub use self::partitioner::Partitioner;
 pub use self::replication_strategy::ReplicationStrategy;
 
 /// Map from host id to a node.
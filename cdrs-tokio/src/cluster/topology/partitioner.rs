@@ -0,0 +1,114 @@
+use derive_more::Display;
+
+use cassandra_protocol::error::{Error, Result};
+use cassandra_protocol::query::query_params::Token;
+use cassandra_protocol::types::list::List;
+use cassandra_protocol::types::rows::Row;
+use cassandra_protocol::types::{AsRustType, IntoRustByName};
+
+/// Partitioner used by the cluster to distribute data, as reported in `system.local`.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Display)]
+pub enum Partitioner {
+    Murmur3,
+    Random,
+    ByteOrdered,
+}
+
+impl Partitioner {
+    /// Maps a full partitioner class name to a partitioner, if known.
+    pub fn from_name(name: &str) -> Option<Partitioner> {
+        match name {
+            "org.apache.cassandra.dht.Murmur3Partitioner" => Some(Partitioner::Murmur3),
+            "org.apache.cassandra.dht.RandomPartitioner" => Some(Partitioner::Random),
+            "org.apache.cassandra.dht.ByteOrderedPartitioner" => Some(Partitioner::ByteOrdered),
+            _ => None,
+        }
+    }
+
+    /// Parses a decimal token string, as stored in system tables, according to the partitioner.
+    pub fn parse_token(self, value: &str) -> Result<Token> {
+        match self {
+            Partitioner::Murmur3 => Token::parse_murmur3(value),
+            Partitioner::Random => Token::parse_random(value),
+            Partitioner::ByteOrdered => Err(Error::General(
+                "Byte ordered partitioner tokens are not supported".into(),
+            )),
+        }
+    }
+
+    /// Decodes the `tokens` set of a `system.local` or `system.peers` row and parses each token
+    /// according to the partitioner.
+    pub fn parse_tokens(self, row: &Row) -> Result<Vec<Token>> {
+        let tokens: List = row.get_r_by_name("tokens")?;
+        let tokens: Vec<String> = tokens.as_r_type()?;
+
+        tokens.iter().map(|token| self.parse_token(token)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cassandra_protocol::frame::frame_result::{ColType, ColTypeOption, ColTypeOptionValue};
+    use cassandra_protocol::frame::Version;
+    use cassandra_protocol::query::query_params::Token;
+    use cassandra_protocol::testing::mock_row;
+    use cassandra_protocol::types::rows::Row;
+    use cassandra_protocol::types::value::Value;
+
+    use super::Partitioner;
+
+    fn tokens_row(tokens: &[&str]) -> Row {
+        let tokens_type = ColTypeOption {
+            id: ColType::Set,
+            value: Some(ColTypeOptionValue::CSet(Box::new(ColTypeOption {
+                id: ColType::Varchar,
+                value: None,
+            }))),
+        };
+
+        mock_row(
+            Version::V4,
+            vec![(
+                "tokens",
+                tokens_type,
+                Value::list_from_iter(tokens.iter().copied()),
+            )],
+        )
+    }
+
+    #[test]
+    fn should_parse_murmur3_tokens() {
+        let row = tokens_row(&["-9223372036854775808", "0", "4611686018427387904"]);
+
+        assert_eq!(
+            Partitioner::Murmur3.parse_tokens(&row).unwrap(),
+            vec![
+                Token::Murmur3(i64::MIN),
+                Token::Murmur3(0),
+                Token::Murmur3(4611686018427387904),
+            ]
+        );
+        assert!(Partitioner::ByteOrdered.parse_tokens(&row).is_err());
+    }
+
+    #[test]
+    fn should_parse_random_tokens() {
+        let row = tokens_row(&["0", "85070591730234615865843651857942052864"]);
+        let tokens = Partitioner::Random.parse_tokens(&row).unwrap();
+
+        assert_eq!(tokens.len(), 2);
+        assert!(tokens[0] < tokens[1]);
+        assert!(Partitioner::Random
+            .parse_tokens(&tokens_row(&["-1"]))
+            .is_err());
+    }
+
+    #[test]
+    fn should_map_partitioner_names() {
+        assert_eq!(
+            Partitioner::from_name("org.apache.cassandra.dht.Murmur3Partitioner"),
+            Some(Partitioner::Murmur3)
+        );
+        assert_eq!(Partitioner::from_name("Murmur3Partitioner"), None);
+    }
+}
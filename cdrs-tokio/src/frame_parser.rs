@@ -1,99 +1,84 @@
-use std::convert::TryFrom;
-use std::io::Cursor;
 use tokio::io::AsyncReadExt;
 
 use cassandra_protocol::compression::Compression;
 use cassandra_protocol::error;
 use cassandra_protocol::frame::frame_response::ResponseBody;
-use cassandra_protocol::frame::{
-    Direction, Flags, Frame, FromCursor, Opcode, Version, LENGTH_LEN, STREAM_LEN,
-};
-use cassandra_protocol::types::data_serialization_types::decode_timeuuid;
-use cassandra_protocol::types::{try_i16_from_bytes, try_i32_from_bytes, CStringList, UUID_LEN};
+use cassandra_protocol::frame::raw_frame::RawFrame;
+use cassandra_protocol::frame::{Frame, Opcode};
 
-async fn parse_raw_frame<T: AsyncReadExt + Unpin>(
-    cursor: &mut T,
-    compressor: Compression,
-) -> error::Result<Frame> {
-    let mut version_bytes = [0; Version::BYTE_LENGTH];
-    let mut flag_bytes = [0; Flags::BYTE_LENGTH];
-    let mut opcode_bytes = [0; Opcode::BYTE_LENGTH];
-    let mut stream_bytes = [0; STREAM_LEN];
-    let mut length_bytes = [0; LENGTH_LEN];
-
-    // NOTE: order of reads matters
-    cursor.read_exact(&mut version_bytes).await?;
-    cursor.read_exact(&mut flag_bytes).await?;
-    cursor.read_exact(&mut stream_bytes).await?;
-    cursor.read_exact(&mut opcode_bytes).await?;
-    cursor.read_exact(&mut length_bytes).await?;
-
-    let version = Version::try_from(version_bytes[0])?;
-    let direction = Direction::from(version_bytes[0]);
-    let flags = Flags::from_bits_truncate(flag_bytes[0]);
-    let stream = try_i16_from_bytes(&stream_bytes)?;
-    let opcode = Opcode::try_from(opcode_bytes[0])?;
-    let length = try_i32_from_bytes(&length_bytes)? as usize;
-
-    let mut body_bytes = vec![0; length];
-
-    cursor.read_exact(&mut body_bytes).await?;
-
-    let full_body = if flags.contains(Flags::COMPRESSION) {
-        compressor.decode(body_bytes)?
-    } else {
-        Compression::None.decode(body_bytes)?
-    };
-
-    // Use cursor to get tracing id, warnings and actual body
-    let mut body_cursor = Cursor::new(full_body.as_slice());
-
-    let tracing_id = if flags.contains(Flags::TRACING) {
-        let mut tracing_bytes = vec![0; UUID_LEN];
-        std::io::Read::read_exact(&mut body_cursor, &mut tracing_bytes)?;
+async fn parse_raw_frame<T: AsyncReadExt + Unpin>(cursor: &mut T) -> error::Result<RawFrame> {
+    let mut header = [0; 9];
+    cursor.read_exact(&mut header).await?;
 
-        decode_timeuuid(tracing_bytes.as_slice()).ok()
-    } else {
-        None
-    };
-
-    let warnings = if flags.contains(Flags::WARNING) {
-        CStringList::from_cursor(&mut body_cursor)?.into_plain()
-    } else {
-        vec![]
-    };
+    let length = RawFrame::parse_header(&header)?;
 
-    let mut body = vec![];
-
-    std::io::Read::read_to_end(&mut body_cursor, &mut body)?;
-
-    let frame = Frame {
-        version,
-        direction,
-        flags,
-        opcode,
-        stream,
-        body,
-        tracing_id,
-        warnings,
-    };
+    let mut body = vec![0; length];
+    cursor.read_exact(&mut body).await?;
 
-    Ok(frame)
+    Ok(RawFrame { header, body })
 }
 
 pub async fn parse_frame<T: AsyncReadExt + Unpin>(
     cursor: &mut T,
     compressor: Compression,
 ) -> error::Result<Frame> {
-    convert_frame_into_result(parse_raw_frame(cursor, compressor).await?)
+    convert_frame_into_result(parse_raw_frame(cursor).await?.into_frame(compressor)?)
 }
 
 fn convert_frame_into_result(frame: Frame) -> error::Result<Frame> {
     match frame.opcode {
         Opcode::Error => frame.response_body().and_then(|err| match err {
-            ResponseBody::Error(err) => Err(error::Error::Server(err)),
+            ResponseBody::Error(mut err) => {
+                err.retry_after = frame.retry_after();
+                Err(error::Error::Server(err))
+            }
             _ => unreachable!(),
         }),
         _ => Ok(frame),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use cassandra_protocol::error::Error;
+    use cassandra_protocol::frame::frame_builder::FrameBuilder;
+    use cassandra_protocol::frame::frame_error::{
+        AdditionalErrorInfo, CdrsError, RETRY_AFTER_PAYLOAD_KEY,
+    };
+    use cassandra_protocol::frame::{Direction, Opcode, Serialize};
+    use cassandra_protocol::types::CString;
+
+    use super::convert_frame_into_result;
+
+    #[test]
+    fn should_carry_retry_after_into_server_error() {
+        let error = CdrsError {
+            error_code: 0x1001,
+            message: CString::new("overloaded".into()),
+            additional_info: AdditionalErrorInfo::Overloaded,
+            retry_after: None,
+        };
+
+        let frame = FrameBuilder::new(Opcode::Error)
+            .direction(Direction::Response)
+            .custom_payload(
+                vec![(
+                    RETRY_AFTER_PAYLOAD_KEY.to_string(),
+                    100i32.to_be_bytes().to_vec(),
+                )]
+                .into_iter()
+                .collect(),
+            )
+            .body(error.serialize_to_vec())
+            .build();
+
+        match convert_frame_into_result(frame) {
+            Err(Error::Server(error)) => {
+                assert_eq!(error.retry_after, Some(Duration::from_millis(100)));
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
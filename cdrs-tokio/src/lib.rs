@@ -46,6 +46,8 @@ pub mod load_balancing;
 
 pub mod future;
 pub mod retry;
+pub mod rows_stream;
+pub mod speculative_execution;
 pub mod transport;
 
 pub use cassandra_protocol::authenticators;
@@ -53,7 +55,10 @@ pub use cassandra_protocol::compression;
 pub use cassandra_protocol::consistency;
 pub use cassandra_protocol::error;
 pub use cassandra_protocol::frame;
+pub use cassandra_protocol::prelude;
 pub use cassandra_protocol::query;
+#[cfg(feature = "testing")]
+pub use cassandra_protocol::testing;
 pub use cassandra_protocol::types;
 
 pub type Error = error::Error;
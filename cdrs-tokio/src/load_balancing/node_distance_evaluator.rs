@@ -73,6 +73,7 @@ mod tests {
                     "".into(),
                     Default::default(),
                     "".into(),
+                    None,
                 ))
                 .unwrap(),
             NodeDistance::Remote
@@ -86,6 +87,7 @@ mod tests {
                     local_dc.into(),
                     Default::default(),
                     "".into(),
+                    None,
                 ))
                 .unwrap(),
             NodeDistance::Local
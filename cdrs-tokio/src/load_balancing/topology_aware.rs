@@ -1,4 +1,4 @@
-use fxhash::{FxHashMap, FxHashSet};
+use fxhash::FxHashMap;
 use itertools::Itertools;
 use rand::prelude::*;
 use std::cmp::Ordering as CmpOrdering;
@@ -113,7 +113,7 @@ impl<T: CdrsTransport, CM: ConnectionManager<T>> TopologyAwareLoadBalancingStrat
                 datacenter_replication_factor,
             } => self.network_topology_strategy_replicas(
                 token,
-                datacenter_replication_factor.clone(),
+                datacenter_replication_factor,
                 consistency,
                 cluster,
             ),
@@ -124,7 +124,7 @@ impl<T: CdrsTransport, CM: ConnectionManager<T>> TopologyAwareLoadBalancingStrat
     fn network_topology_strategy_replicas(
         &self,
         token: Murmur3Token,
-        mut datacenter_replication_factor: FxHashMap<String, usize>,
+        datacenter_replication_factor: &FxHashMap<String, usize>,
         consistency: Option<Consistency>,
         cluster: &ClusterMetadata<T, CM>,
     ) -> QueryPlan<T, CM> {
@@ -135,54 +135,11 @@ impl<T: CdrsTransport, CM: ConnectionManager<T>> TopologyAwareLoadBalancingStrat
         // 4. append round-robin unignored local non-replicas
         // 5. optionally, add shuffled remote unignored non-replicas
 
-        let replicas = cluster.token_map().nodes_for_token(token).collect_vec();
-
-        let desired_replica_count = datacenter_replication_factor.values().sum();
-        let mut same_rack_replicas: FxHashMap<String, usize> = datacenter_replication_factor
-            .iter()
-            .map(|(dc, replication_factor)| {
-                let rack_count = cluster.datacenter(dc).map(|dc| dc.rack_count).unwrap_or(0);
-                (dc.into(), replication_factor.saturating_sub(rack_count))
-            })
-            .collect();
-
-        let mut result = Vec::with_capacity(desired_replica_count);
-        let mut used_dc_racks: FxHashSet<(&str, &str)> = Default::default();
-
-        for replica in &replicas {
-            if let Some(datacenter_replication_factor) =
-                datacenter_replication_factor.get_mut(replica.datacenter())
-            {
-                if *datacenter_replication_factor == 0 {
-                    // found enough nodes in this datacenter
-                    continue;
-                }
-
-                let current_node_dc = replica.datacenter();
-                let current_node_rack = replica.rack();
-
-                if used_dc_racks.contains(&(current_node_dc, current_node_rack)) {
-                    // check if we need to put nodes from the same rack multiple times to meet
-                    // the replication factor
-                    if let Some(same_rack_replicas) = same_rack_replicas.get_mut(current_node_dc) {
-                        if *same_rack_replicas > 0 {
-                            *same_rack_replicas -= 1;
-                            *datacenter_replication_factor -= 1;
-                            result.push(replica.clone());
-                        }
-                    }
-                } else {
-                    *datacenter_replication_factor -= 1;
-
-                    used_dc_racks.insert((current_node_dc, current_node_rack));
-                    result.push(replica.clone());
-                }
-
-                if result.len() == desired_replica_count {
-                    break;
-                }
-            }
-        }
+        let mut result = cluster.token_map().network_topology_replicas(
+            token,
+            datacenter_replication_factor,
+            cluster.datacenters(),
+        );
 
         // result now contains mixed local/remote and ignored/unignored nodes - put local in front
         result.sort_unstable_by(|a, b| {
@@ -248,7 +205,8 @@ impl<T: CdrsTransport, CM: ConnectionManager<T>> TopologyAwareLoadBalancingStrat
     ) -> QueryPlan<T, CM> {
         let mut replicas = cluster
             .token_map()
-            .nodes_for_token_capped(token, replica_count)
+            .replicas(token, replica_count)
+            .into_iter()
             .filter(|node| !node.is_ignored())
             .collect_vec();
 
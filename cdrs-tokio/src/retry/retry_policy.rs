@@ -1,12 +1,17 @@
 use derive_more::Display;
 
+use cassandra_protocol::consistency::Consistency;
 use cassandra_protocol::error::Error;
 use cassandra_protocol::frame::frame_error::{
-    AdditionalErrorInfo, CdrsError, ReadTimeoutError, WriteTimeoutError, WriteType,
+    AdditionalErrorInfo, CdrsError, ReadTimeoutError, UnavailableError, WriteTimeoutError,
+    WriteType,
 };
 
 #[derive(Debug, PartialEq, Eq, Ord, PartialOrd, Hash, Copy, Clone, Display)]
 pub enum RetryDecision {
+    /// Retry on the same node with given consistency level.
+    #[display(fmt = "Retry({})", _0)]
+    Retry(Consistency),
     RetrySameNode,
     RetryNextNode,
     DontRetry,
@@ -20,8 +25,53 @@ pub struct QueryInfo<'a> {
 
 /// Query-specific information about current state of retrying.
 pub trait RetrySession {
-    /// Decide what to do with the failing query.
-    fn decide(&mut self, query_info: QueryInfo) -> RetryDecision;
+    /// Decide what to do with the failing query. By default, dispatches to one of the `on_*`
+    /// methods, depending on the error.
+    fn decide(&mut self, query_info: QueryInfo) -> RetryDecision {
+        match query_info.error {
+            Error::Server(CdrsError {
+                additional_info: AdditionalErrorInfo::Unavailable(error),
+                ..
+            }) => self.on_unavailable(error, query_info.is_idempotent),
+            Error::Server(CdrsError {
+                additional_info: AdditionalErrorInfo::ReadTimeout(error),
+                ..
+            }) => self.on_read_timeout(error, query_info.is_idempotent),
+            Error::Server(CdrsError {
+                additional_info: AdditionalErrorInfo::WriteTimeout(error),
+                ..
+            }) => self.on_write_timeout(error, query_info.is_idempotent),
+            _ => self.on_request_error(query_info),
+        }
+    }
+
+    /// Called when the coordinator knew there were not enough replicas alive.
+    fn on_unavailable(&mut self, _error: &UnavailableError, _is_idempotent: bool) -> RetryDecision {
+        RetryDecision::DontRetry
+    }
+
+    /// Called when a read timed out on the coordinator.
+    fn on_read_timeout(
+        &mut self,
+        _error: &ReadTimeoutError,
+        _is_idempotent: bool,
+    ) -> RetryDecision {
+        RetryDecision::DontRetry
+    }
+
+    /// Called when a write timed out on the coordinator.
+    fn on_write_timeout(
+        &mut self,
+        _error: &WriteTimeoutError,
+        _is_idempotent: bool,
+    ) -> RetryDecision {
+        RetryDecision::DontRetry
+    }
+
+    /// Called for all other errors.
+    fn on_request_error(&mut self, _query_info: QueryInfo) -> RetryDecision {
+        RetryDecision::DontRetry
+    }
 }
 
 /// Retry policy determines what to do in case of communication error.
@@ -68,10 +118,49 @@ pub struct DefaultRetrySession {
 }
 
 impl RetrySession for DefaultRetrySession {
-    fn decide(&mut self, query_info: QueryInfo) -> RetryDecision {
+    // Another coordinator might see a different set of live replicas, regardless of whether the
+    // shortfall was cluster-wide or dc-local (as for `LocalQuorum` or `EachQuorum`), so the
+    // consistency level doesn't change the decision.
+    fn on_unavailable(&mut self, _error: &UnavailableError, _is_idempotent: bool) -> RetryDecision {
+        if !self.was_unavailable_retry {
+            self.was_unavailable_retry = true;
+            RetryDecision::RetryNextNode
+        } else {
+            RetryDecision::DontRetry
+        }
+    }
+
+    fn on_read_timeout(&mut self, error: &ReadTimeoutError, _is_idempotent: bool) -> RetryDecision {
+        if !self.was_read_timeout_retry
+            && error.received >= error.block_for
+            && error.replica_has_responded()
+        {
+            self.was_read_timeout_retry = true;
+            RetryDecision::RetrySameNode
+        } else {
+            RetryDecision::DontRetry
+        }
+    }
+
+    fn on_write_timeout(
+        &mut self,
+        error: &WriteTimeoutError,
+        is_idempotent: bool,
+    ) -> RetryDecision {
+        if !self.was_write_timeout_retry && is_idempotent && error.write_type == WriteType::BatchLog
+        {
+            self.was_write_timeout_retry = true;
+            RetryDecision::RetrySameNode
+        } else {
+            RetryDecision::DontRetry
+        }
+    }
+
+    fn on_request_error(&mut self, query_info: QueryInfo) -> RetryDecision {
         match query_info.error {
             Error::Io(_)
             | Error::General(_)
+            | Error::Protocol(_)
             | Error::Server(CdrsError {
                 additional_info: AdditionalErrorInfo::Overloaded,
                 ..
@@ -90,45 +179,6 @@ impl RetrySession for DefaultRetrySession {
                     RetryDecision::DontRetry
                 }
             }
-            Error::Server(CdrsError {
-                additional_info: AdditionalErrorInfo::Unavailable(_),
-                ..
-            }) => {
-                if !self.was_unavailable_retry {
-                    self.was_unavailable_retry = true;
-                    RetryDecision::RetryNextNode
-                } else {
-                    RetryDecision::DontRetry
-                }
-            }
-            Error::Server(CdrsError {
-                additional_info: AdditionalErrorInfo::ReadTimeout(error @ ReadTimeoutError { .. }),
-                ..
-            }) => {
-                if !self.was_read_timeout_retry
-                    && error.received >= error.block_for
-                    && error.replica_has_responded()
-                {
-                    self.was_read_timeout_retry = true;
-                    RetryDecision::RetrySameNode
-                } else {
-                    RetryDecision::DontRetry
-                }
-            }
-            Error::Server(CdrsError {
-                additional_info: AdditionalErrorInfo::WriteTimeout(error @ WriteTimeoutError { .. }),
-                ..
-            }) => {
-                if !self.was_write_timeout_retry
-                    && query_info.is_idempotent
-                    && error.write_type == WriteType::BatchLog
-                {
-                    self.was_write_timeout_retry = true;
-                    RetryDecision::RetrySameNode
-                } else {
-                    RetryDecision::DontRetry
-                }
-            }
             Error::Server(CdrsError {
                 additional_info: AdditionalErrorInfo::IsBootstrapping,
                 ..
@@ -137,3 +187,159 @@ impl RetrySession for DefaultRetrySession {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use cassandra_protocol::consistency::Consistency;
+    use cassandra_protocol::error::Error;
+    use cassandra_protocol::frame::frame_error::{
+        AdditionalErrorInfo, CdrsError, ReadTimeoutError, UnavailableError, WriteTimeoutError,
+        WriteType,
+    };
+    use cassandra_protocol::frame::FromCursor;
+    use cassandra_protocol::types::CString;
+
+    use crate::retry::{DefaultRetrySession, QueryInfo, RetryDecision, RetrySession};
+
+    fn server_error(additional_info: AdditionalErrorInfo) -> Error {
+        Error::Server(CdrsError {
+            error_code: 0,
+            message: CString::new("error".into()),
+            additional_info,
+            retry_after: None,
+        })
+    }
+
+    fn decide(
+        session: &mut DefaultRetrySession,
+        error: &Error,
+        is_idempotent: bool,
+    ) -> RetryDecision {
+        session.decide(QueryInfo {
+            error,
+            is_idempotent,
+        })
+    }
+
+    #[test]
+    fn should_retry_unavailable_once_on_next_node() {
+        let error = server_error(AdditionalErrorInfo::Unavailable(UnavailableError {
+            cl: Consistency::Quorum,
+            required: 2,
+            alive: 1,
+        }));
+
+        let mut session = DefaultRetrySession::default();
+        assert_eq!(
+            decide(&mut session, &error, false),
+            RetryDecision::RetryNextNode
+        );
+        assert_eq!(
+            decide(&mut session, &error, false),
+            RetryDecision::DontRetry
+        );
+    }
+
+    #[test]
+    fn should_retry_dc_local_unavailable() {
+        for cl in [Consistency::EachQuorum, Consistency::LocalQuorum] {
+            let error = server_error(AdditionalErrorInfo::Unavailable(UnavailableError {
+                cl,
+                required: 2,
+                alive: 1,
+            }));
+
+            assert_eq!(
+                decide(&mut DefaultRetrySession::default(), &error, false),
+                RetryDecision::RetryNextNode
+            );
+        }
+    }
+
+    #[test]
+    fn should_retry_read_timeout_once_on_same_node_when_data_missing() {
+        // quorum, received 2, block_for 2, data not present
+        let bytes = [0, 4, 0, 0, 0, 2, 0, 0, 0, 2, 0];
+        let error = server_error(AdditionalErrorInfo::ReadTimeout(
+            ReadTimeoutError::from_cursor(&mut Cursor::new(&bytes[..])).unwrap(),
+        ));
+
+        let mut session = DefaultRetrySession::default();
+        assert_eq!(
+            decide(&mut session, &error, false),
+            RetryDecision::DontRetry
+        );
+
+        let bytes = [0, 4, 0, 0, 0, 2, 0, 0, 0, 2, 1];
+        let error = server_error(AdditionalErrorInfo::ReadTimeout(
+            ReadTimeoutError::from_cursor(&mut Cursor::new(&bytes[..])).unwrap(),
+        ));
+
+        assert_eq!(
+            decide(&mut session, &error, false),
+            RetryDecision::RetrySameNode
+        );
+        assert_eq!(
+            decide(&mut session, &error, false),
+            RetryDecision::DontRetry
+        );
+    }
+
+    #[test]
+    fn should_retry_idempotent_batch_log_write_timeout_once() {
+        let error = server_error(AdditionalErrorInfo::WriteTimeout(WriteTimeoutError {
+            cl: Consistency::Quorum,
+            received: 1,
+            block_for: 2,
+            write_type: WriteType::BatchLog,
+        }));
+
+        let mut session = DefaultRetrySession::default();
+        assert_eq!(
+            decide(&mut session, &error, false),
+            RetryDecision::DontRetry
+        );
+        assert_eq!(
+            decide(&mut session, &error, true),
+            RetryDecision::RetrySameNode
+        );
+        assert_eq!(decide(&mut session, &error, true), RetryDecision::DontRetry);
+
+        let error = server_error(AdditionalErrorInfo::WriteTimeout(WriteTimeoutError {
+            cl: Consistency::Quorum,
+            received: 1,
+            block_for: 2,
+            write_type: WriteType::Simple,
+        }));
+        assert_eq!(
+            decide(&mut DefaultRetrySession::default(), &error, true),
+            RetryDecision::DontRetry
+        );
+    }
+
+    #[test]
+    fn should_retry_request_errors_on_next_node_when_idempotent() {
+        let error = server_error(AdditionalErrorInfo::Overloaded);
+        let mut session = DefaultRetrySession::default();
+
+        assert_eq!(
+            decide(&mut session, &error, true),
+            RetryDecision::RetryNextNode
+        );
+        assert_eq!(
+            decide(&mut session, &error, false),
+            RetryDecision::DontRetry
+        );
+
+        let error = server_error(AdditionalErrorInfo::IsBootstrapping);
+        assert_eq!(
+            decide(&mut session, &error, false),
+            RetryDecision::RetryNextNode
+        );
+
+        let error = server_error(AdditionalErrorInfo::Syntax);
+        assert_eq!(decide(&mut session, &error, true), RetryDecision::DontRetry);
+    }
+}
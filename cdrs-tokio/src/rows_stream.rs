@@ -0,0 +1,141 @@
+//! Streaming decoding of rows, which allows processing large result pages without buffering the
+//! whole body in memory.
+use cassandra_protocol::constants::NOT_SET_LENGTH;
+use futures::stream::{self, Stream};
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::error::Error;
+use crate::frame::frame_result::RowsMetadata;
+use crate::frame::Version;
+use crate::types::rows::Row;
+use crate::types::CBytes;
+use crate::Result;
+
+struct RowsStreamState<R> {
+    reader: R,
+    metadata: Arc<RowsMetadata>,
+    version: Version,
+    remaining: Option<i32>,
+}
+
+/// Decodes rows one at a time from a reader positioned at the rows part of a result body, i.e.
+/// right after the rows metadata. `version` is the protocol version of the response frame.
+pub fn stream_rows<R: AsyncRead + Unpin>(
+    metadata: Arc<RowsMetadata>,
+    version: Version,
+    reader: R,
+) -> impl Stream<Item = Result<Row>> {
+    let state = RowsStreamState {
+        reader,
+        metadata,
+        version,
+        remaining: None,
+    };
+
+    stream::try_unfold(state, next_row)
+}
+
+async fn next_row<R: AsyncRead + Unpin>(
+    mut state: RowsStreamState<R>,
+) -> Result<Option<(Row, RowsStreamState<R>)>> {
+    let remaining = match state.remaining {
+        Some(remaining) => remaining,
+        None => state.reader.read_i32().await?,
+    };
+
+    if remaining <= 0 {
+        return Ok(None);
+    }
+
+    let mut row_content = Vec::with_capacity(state.metadata.columns_count as usize);
+    for _ in 0..state.metadata.columns_count {
+        row_content.push(read_cell(&mut state.reader).await?);
+    }
+
+    state.remaining = Some(remaining - 1);
+
+    let row = Row::new(state.metadata.clone(), row_content, state.version);
+    Ok(Some((row, state)))
+}
+
+async fn read_cell<R: AsyncRead + Unpin>(reader: &mut R) -> Result<CBytes> {
+    let len = reader.read_i32().await?;
+    if len == NOT_SET_LENGTH {
+        return Err(Error::Protocol("Unexpected not set value in result".into()));
+    }
+
+    if len < 0 {
+        return Ok(CBytes::new_empty());
+    }
+
+    let mut bytes = vec![0; len as usize];
+    reader.read_exact(&mut bytes).await?;
+
+    Ok(CBytes::new(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use super::stream_rows;
+    use crate::frame::frame_result::{
+        ColSpec, ColType, ColTypeOption, RowsMetadata, RowsMetadataFlags, TableSpec,
+    };
+    use crate::frame::Version;
+    use crate::types::CString;
+
+    #[tokio::test]
+    async fn should_stream_rows() {
+        let metadata = Arc::new(RowsMetadata {
+            flags: RowsMetadataFlags::GLOBAL_TABLE_SPACE,
+            columns_count: 1,
+            paging_state: None,
+            new_metadata_id: None,
+            global_table_spec: Some(TableSpec {
+                ks_name: CString::new("ks".into()),
+                table_name: CString::new("table".into()),
+            }),
+            col_specs: vec![ColSpec {
+                table_spec: None,
+                name: CString::new("id".into()),
+                col_type: ColTypeOption {
+                    id: ColType::Int,
+                    value: None,
+                },
+            }],
+        });
+
+        let body: &[u8] = &[
+            0, 0, 0, 3, // rows count
+            0, 0, 0, 4, 0, 0, 0, 1, // row 1
+            0, 0, 0, 4, 0, 0, 0, 2, // row 2
+            255, 255, 255, 255, // row 3
+        ];
+
+        let rows: Vec<_> = stream_rows(metadata.clone(), Version::V4, body)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 3);
+
+        let first: Option<i32> = rows[0].get_opt_by_index(0).unwrap();
+        let second: Option<i32> = rows[1].get_opt_by_index(0).unwrap();
+        let third: Option<i32> = rows[2].get_opt_by_index(0).unwrap();
+
+        assert_eq!(first, Some(1));
+        assert_eq!(second, Some(2));
+        assert_eq!(third, None);
+
+        let body: &[u8] = &[
+            0, 0, 0, 1, // rows count
+            255, 255, 255, 254, // row 1 - not set
+        ];
+
+        let result: Result<Vec<_>, _> =
+            stream_rows(metadata, Version::V4, body).try_collect().await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,69 @@
+//! Pre-emptively query another node if the current one takes too long to respond.
+
+use derive_more::Constructor;
+use std::time::Duration;
+
+/// Current state of a query, used to decide about speculative executions.
+#[derive(Debug, Clone, Copy, Constructor)]
+pub struct Context {
+    /// Number of executions already running for the query, including the initial one.
+    pub running_executions: usize,
+}
+
+/// Decides when to start additional executions of an idempotent query, if the previous ones
+/// didn't respond in time.
+pub trait SpeculativeExecutionPolicy {
+    /// Returns the delay after which another execution should be started, or `None` if no more
+    /// executions should be started.
+    fn execution_interval(&self, context: &Context) -> Option<Duration>;
+}
+
+/// A policy that schedules a configurable number of speculative executions, separated by a fixed
+/// delay.
+#[derive(Debug, Clone, Copy, Constructor)]
+pub struct ConstantSpeculativeExecutionPolicy {
+    delay: Duration,
+    max_speculations: usize,
+}
+
+impl SpeculativeExecutionPolicy for ConstantSpeculativeExecutionPolicy {
+    fn execution_interval(&self, context: &Context) -> Option<Duration> {
+        // the initial execution is not a speculative one
+        if context.running_executions <= self.max_speculations {
+            Some(self.delay)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::speculative_execution::{
+        ConstantSpeculativeExecutionPolicy, Context, SpeculativeExecutionPolicy,
+    };
+
+    #[test]
+    fn should_fire_at_configured_delay() {
+        let policy = ConstantSpeculativeExecutionPolicy::new(Duration::from_millis(100), 2);
+        assert_eq!(
+            policy.execution_interval(&Context::new(1)),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            policy.execution_interval(&Context::new(2)),
+            Some(Duration::from_millis(100))
+        );
+    }
+
+    #[test]
+    fn should_cap_at_max_speculations() {
+        let policy = ConstantSpeculativeExecutionPolicy::new(Duration::from_millis(100), 2);
+        assert_eq!(policy.execution_interval(&Context::new(3)), None);
+
+        let policy = ConstantSpeculativeExecutionPolicy::new(Duration::from_millis(100), 0);
+        assert_eq!(policy.execution_interval(&Context::new(1)), None);
+    }
+}
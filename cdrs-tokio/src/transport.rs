@@ -35,7 +35,7 @@ use crate::Error;
 use crate::Result;
 use cassandra_protocol::compression::Compression;
 use cassandra_protocol::frame::frame_result::ResultKind;
-use cassandra_protocol::frame::{Frame, StreamId};
+use cassandra_protocol::frame::{Flags, Frame, StreamId};
 use cassandra_protocol::frame::{FromBytes, Opcode, EVENT_STREAM_ID};
 use cassandra_protocol::types::INT_LEN;
 
@@ -71,14 +71,17 @@ pub struct TransportTcp {
 }
 
 impl TransportTcp {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         addr: SocketAddr,
         keyspace_holder: Arc<KeyspaceHolder>,
         event_handler: Option<mpsc::Sender<Frame>>,
         error_handler: Option<mpsc::Sender<Error>>,
         compression: Compression,
+        compression_threshold: usize,
         buffer_size: usize,
         tcp_nodelay: bool,
+        use_beta: bool,
     ) -> io::Result<TransportTcp> {
         TcpStream::connect(addr).await.and_then(move |socket| {
             socket.set_nodelay(tcp_nodelay)?;
@@ -88,6 +91,8 @@ impl TransportTcp {
                 inner: AsyncTransport::new(
                     addr,
                     compression,
+                    compression_threshold,
+                    use_beta,
                     buffer_size,
                     read_half,
                     write_half,
@@ -134,8 +139,10 @@ impl TransportRustls {
         event_handler: Option<mpsc::Sender<Frame>>,
         error_handler: Option<mpsc::Sender<Error>>,
         compression: Compression,
+        compression_threshold: usize,
         buffer_size: usize,
         tcp_nodelay: bool,
+        use_beta: bool,
     ) -> io::Result<Self> {
         let stream = TcpStream::connect(addr).await?;
         stream.set_nodelay(tcp_nodelay)?;
@@ -148,6 +155,8 @@ impl TransportRustls {
             inner: AsyncTransport::new(
                 addr,
                 compression,
+                compression_threshold,
+                use_beta,
                 buffer_size,
                 read_half,
                 write_half,
@@ -181,6 +190,8 @@ impl CdrsTransport for TransportRustls {
 struct AsyncTransport {
     addr: SocketAddr,
     compression: Compression,
+    compression_threshold: usize,
+    use_beta: bool,
     write_sender: mpsc::Sender<Request>,
     is_broken: Arc<AtomicBool>,
     processing_handle: JoinHandle<()>,
@@ -197,6 +208,8 @@ impl AsyncTransport {
     fn new<T: AsyncRead + AsyncWrite + Send + 'static>(
         addr: SocketAddr,
         compression: Compression,
+        compression_threshold: usize,
+        use_beta: bool,
         buffer_size: usize,
         read_half: ReadHalf<T>,
         write_half: WriteHalf<T>,
@@ -221,6 +234,8 @@ impl AsyncTransport {
         AsyncTransport {
             addr,
             compression,
+            compression_threshold,
+            use_beta,
             write_sender,
             is_broken,
             processing_handle,
@@ -241,11 +256,22 @@ impl AsyncTransport {
         let (sender, receiver) = oneshot::channel();
         let stream_id = frame.stream;
 
+        // beta protocol versions require the flag on every frame, not only on STARTUP
+        let extra_flags = if self.use_beta {
+            Flags::BETA
+        } else {
+            Flags::empty()
+        };
+
         // startup message is never compressed
         let data = if frame.opcode != Opcode::Startup {
-            frame.encode_with(self.compression)?
+            frame.encode_with_extra_flags(
+                self.compression,
+                self.compression_threshold,
+                extra_flags,
+            )?
         } else {
-            frame.encode_with(Compression::None)?
+            frame.encode_with_extra_flags(Compression::None, 0, extra_flags)?
         };
 
         self.write_sender
@@ -410,3 +436,44 @@ struct Request {
     stream_id: StreamId,
     handler: ResponseHandler,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cassandra_protocol::frame::Version;
+    use tokio::io::{duplex, AsyncReadExt};
+
+    #[tokio::test]
+    async fn should_set_beta_flag_on_non_startup_frames() {
+        let (client, mut server) = duplex(1024);
+        let (read_half, write_half) = split(client);
+        let transport = AsyncTransport::new(
+            "127.0.0.1:9042".parse().unwrap(),
+            Compression::None,
+            0,
+            true,
+            1,
+            read_half,
+            write_half,
+            None,
+            None,
+            Arc::new(KeyspaceHolder::default()),
+        );
+
+        let frame = Frame::new_req_options(Version::V5);
+        let expected = frame
+            .encode_with_extra_flags(Compression::None, 0, Flags::BETA)
+            .unwrap();
+
+        let mut written = vec![0; expected.len()];
+        tokio::select! {
+            _ = transport.write_frame(&frame) => panic!("Unexpected response!"),
+            result = server.read_exact(&mut written) => {
+                result.unwrap();
+            }
+        }
+
+        assert_eq!(written, expected);
+        assert!(Flags::from_bits_truncate(written[1]).contains(Flags::BETA));
+    }
+}
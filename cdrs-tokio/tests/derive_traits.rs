@@ -35,6 +35,109 @@ use uuid::Uuid;
 #[cfg(feature = "e2e-tests")]
 use std::collections::HashMap;
 
+#[test]
+fn derive_into_values() {
+    use cdrs_tokio::types::value::Value;
+    use cdrs_tokio_helpers_derive::IntoValues;
+
+    #[derive(IntoValues)]
+    struct Insert {
+        id: i32,
+        name: String,
+        score: Option<f64>,
+    }
+
+    let values = Insert {
+        id: 1,
+        name: "a".into(),
+        score: None,
+    }
+    .into_values();
+
+    assert_eq!(
+        values,
+        vec![Value::from(1), Value::from("a".to_string()), Value::Null]
+    );
+}
+
+#[test]
+fn derive_from_row() {
+    use cdrs_tokio::frame::frame_result::{
+        ColSpec, ColType, ColTypeOption, RowsMetadata, RowsMetadataFlags,
+    };
+    use cdrs_tokio::frame::Version;
+    use cdrs_tokio::types::rows::Row;
+    use cdrs_tokio::types::{CBytes, CString};
+    use cdrs_tokio_helpers_derive::FromRow;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    #[derive(Debug, PartialEq, FromRow)]
+    struct User {
+        id: i32,
+        name: String,
+        group_id: Option<Uuid>,
+    }
+
+    #[derive(Debug, PartialEq, FromRow)]
+    struct UserTuple(i32, String, Option<Uuid>);
+
+    let col_spec = |name: &str, id| ColSpec {
+        table_spec: None,
+        name: CString::new(name.into()),
+        col_type: ColTypeOption { id, value: None },
+    };
+
+    let metadata = Arc::new(RowsMetadata {
+        flags: RowsMetadataFlags::empty(),
+        columns_count: 3,
+        paging_state: None,
+        new_metadata_id: None,
+        global_table_spec: None,
+        col_specs: vec![
+            col_spec("id", ColType::Int),
+            col_spec("name", ColType::Varchar),
+            col_spec("group_id", ColType::Uuid),
+        ],
+    });
+
+    let row = Row::new(
+        metadata.clone(),
+        vec![
+            CBytes::new(vec![0, 0, 0, 7]),
+            CBytes::new(b"alice".to_vec()),
+            CBytes::new_empty(),
+        ],
+        Version::V4,
+    );
+
+    assert_eq!(
+        User::from_row(&row).unwrap(),
+        User {
+            id: 7,
+            name: "alice".into(),
+            group_id: None,
+        }
+    );
+    assert_eq!(
+        UserTuple::from_row(&row).unwrap(),
+        UserTuple(7, "alice".into(), None)
+    );
+
+    let group_id = Uuid::from_bytes([1; 16]);
+    let row = Row::new(
+        metadata,
+        vec![
+            CBytes::new(vec![0, 0, 0, 7]),
+            CBytes::new(b"alice".to_vec()),
+            CBytes::new(group_id.as_bytes().to_vec()),
+        ],
+        Version::V4,
+    );
+
+    assert_eq!(User::from_row(&row).unwrap().group_id, Some(group_id));
+}
+
 #[tokio::test]
 #[cfg(feature = "e2e-tests")]
 async fn simple_udt() {